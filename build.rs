@@ -0,0 +1,11 @@
+fn main() {
+	// Messages and server of proto/sc.proto, compiled with a vendored protoc so no system install is needed
+	#[cfg(feature = "grpc")]
+	{
+		println!("cargo:rerun-if-changed=proto/sc.proto");
+		let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc for this platform");
+		std::env::set_var("PROTOC", protoc);
+		tonic_build::configure().build_client(false).compile_protos(&["proto/sc.proto"], &["proto"]).expect("compile proto/sc.proto");
+	}
+	println!("cargo:rerun-if-changed=build.rs");
+}
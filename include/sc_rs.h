@@ -0,0 +1,78 @@
+#ifndef SC_RS_H
+#define SC_RS_H
+
+/* Generated by cbindgen from src/sc/capi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Opaque calculator handle
+typedef struct ScHandle ScHandle;
+
+// Headline values of the last `sc_calc`; per-molecule arrays are indexed by molecule (0 or 1)
+typedef struct ScResults {
+  // Shape complementarity (median S by default)
+  double sc;
+  // Median nearest-neighbor distance between the trimmed surfaces (Å)
+  double distance;
+  // Trimmed interface area of both surfaces (Å²)
+  double area;
+  // Buried SASA of both molecules (Å²)
+  double bsa;
+  double gap_volume;
+  double gap_index;
+  size_t n_atoms[2];
+  size_t n_trimmed_dots[2];
+} ScResults;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// New calculator with default settings; release with `sc_free`
+struct ScHandle *sc_new(void);
+
+// # Safety
+// `sc` must come from `sc_new` and not be used afterwards; null is ignored.
+void sc_free(struct ScHandle *sc);
+
+// Add one atom to `molecule` (0 or 1; any other value fails). A `radius` ≤ 0 is looked up in the
+// embedded radii table from the residue and atom names.
+//
+// # Safety
+// `sc` must be a live handle; `residue` and `atom` must be NUL-terminated strings.
+int sc_add_atom(struct ScHandle *sc,
+                int molecule,
+                const char *residue,
+                const char *atom,
+                double x,
+                double y,
+                double z,
+                double radius);
+
+// Compute Sc for the atoms added so far
+//
+// # Safety
+// `sc` must be a live handle.
+int sc_calc(struct ScHandle *sc);
+
+// Copy the results of the last successful `sc_calc` into `out`; fails before the first one
+//
+// # Safety
+// `sc` must be a live handle and `out` must point to writable `ScResults`.
+int sc_results(struct ScHandle *sc, struct ScResults *out);
+
+// Message of the last failed call on `sc`, valid until the next call; empty when none failed
+//
+// # Safety
+// `sc` must be a live handle or null.
+const char *sc_last_error(const struct ScHandle *sc);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SC_RS_H */
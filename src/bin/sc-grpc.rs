@@ -0,0 +1,14 @@
+use sc_rs::sc::grpc;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        eprintln!("Usage: sc-grpc [--addr <host:port>]   (default 127.0.0.1:50051; protocol in proto/sc.proto)");
+        return Ok(());
+    }
+    let addr = args.iter().position(|a| a == "--addr").and_then(|i| args.get(i + 1)).map(String::as_str).unwrap_or("127.0.0.1:50051").parse()?;
+    eprintln!("sc-grpc listening on {addr}");
+    grpc::serve(addr).await?;
+    Ok(())
+}
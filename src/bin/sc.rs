@@ -1,10 +1,16 @@
 use std::env;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::Write;
+use std::process::ExitCode;
 
-use sc_rs::sc::types::{Atom, Results};
+use sc_rs::sc::types::{Atom, ChainStats, ClashReport, SurfaceDensity, Convergence, CurvatureStats, NormalAngleStats, Prescreen, DensityPoint, DirectionalSc, DotKind, DotKindSc, ResidueTypeSc, InterfaceGeometry, InterfaceResidue, Patch, ProbeRadiusPoint, ProfileBin, ResidueContact, ResidueId, Results, SkippedAtom, StatisticValue, SweepPoint, SymmetryStats, Timings, Void, Warning, WarningKind, WaterBridge};
 use sc_rs::sc::vector3::Vec3;
-use sc_rs::sc::ScCalculator;
+use sc_rs::sc::pdb::{chain_selected, write_pdb_bfactors, read_pdb_waters_from_path, read_structure_all_atoms_from_path, read_structure_atoms_from_path, read_structure_atoms_with_warnings_from_path};
+use sc_rs::sc::interface::{AROMATIC_RESIDUES, HYDROPHOBIC_RESIDUES};
+use sc_rs::sc::regions::{read_regions_from_path, RegionSc, ResidueRange};
+use sc_rs::sc::weights::{read_weights_from_path, WeightedSc};
+use sc_rs::sc::zernike::{ZernikeDescriptor, DEFAULT_ZERNIKE_ORDER};
+use sc_rs::sc::{pockets, PocketSettings, AtomSubset, Focus, FocusCenter, Phase, Progress, RadiiSource, ScCalculator, ScStatistic, Settings, SurfaceCalculatorError, TrimMode};
 
 #[derive(serde::Serialize)]
 struct Output {
@@ -12,90 +18,1739 @@ struct Output {
     sc: f64,
     median_distance: f64,
     trimmed_area: f64,
+    buried_sasa: f64,
+    gap_volume: f64,
+    gap_index: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    electrostatic_complementarity: Option<f64>,
+    /// Solvent-excluded volume of each molecule on its own (`--volume`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volumes: Option<[f64; 2]>,
     atoms_mol1: usize,
     atoms_mol2: usize,
+    /// Occluding atoms from the `--context` chains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    atoms_context: Option<usize>,
+    /// Cutoff used to classify atoms (derived with `--auto-sep-cutoff`), and the atoms of each
+    /// molecule within it (buried) and beyond it (far)
+    separation_cutoff: f64,
+    attention: [AttentionCounts; 2],
+    patches: [Vec<Patch>; 2],
+    /// Per-chain breakdown of each surface
+    chains: [Vec<ChainStats>; 2],
+    voids: Vec<Void>,
+    geometry: InterfaceGeometry,
+    /// Curvature complementarity of the paired trimmed dots
+    curvature: CurvatureStats,
+    /// Atom pairs across the interface overlapping beyond the clash tolerance
+    clashes: ClashReport,
     elapsed_ms: u128,
+    /// Wall time per phase in seconds
+    timings: Timings,
+    statistics: Vec<StatisticValue>,
+    /// S(A->B) and S(B->A) behind `sc`, and their difference
+    directional: DirectionalSc,
+    /// Angles between paired normals and the orientation and distance factors of S
+    normal_angles: NormalAngleStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symmetry: Option<SymmetryStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    water_bridges: Option<Vec<WaterBridge>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sweep: Option<Vec<SweepPoint>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    convergence: Option<Convergence>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    probe_radius_scan: Option<Vec<ProbeRadiusPoint>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    density_scan: Option<Vec<DensityPoint>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    regions: Option<Vec<RegionSc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weighted_sc: Option<WeightedSc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<Vec<ProfileBin>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dot_kind_sc: Option<DotKindSc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    residue_type_sc: Option<ResidueTypeSc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dot_density: Option<DensityOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped_atoms: Vec<SkippedAtom>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<Warning>,
 }
 
-fn parse_pdb_atoms(path: &str, chain1: &str, chain2: &str) -> anyhow::Result<(Vec<(Vec3, String, String, String)>, Vec<(Vec3, String, String, String)>)> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut mol1 = Vec::new();
-    let mut mol2 = Vec::new();
-    for line in reader.lines() {
-        let l = line?;
-        // Use only standard protein ATOM records; ignore ligands/ions/water in HETATM
-        if l.starts_with("ATOM") {
-            if l.len() < 54 { continue; }
-            // Skip alternate locations other than ' ' or 'A' to mirror common PDB handling
-            let alt = if l.len() >= 17 { l[16..17].chars().next().unwrap_or(' ') } else { ' ' };
-            if alt != ' ' && alt != 'A' { continue; }
-            let atom_name = l[12..16].trim().to_string();
-            // Skip hydrogens (use heavy atoms only)
-            let element = if l.len() >= 78 { l[76..78].trim().to_string() } else { String::new() };
-            if element.eq_ignore_ascii_case("H") || atom_name.starts_with('H') || atom_name.ends_with('H') || atom_name.contains("H") && atom_name.chars().next().unwrap_or(' ').is_ascii_digit() {
-                continue;
-            }
-            let res_name = if l.len() >= 20 { l[17..20].trim().to_string() } else { String::from("UNK") };
-            let chain_id = if l.len() >= 22 { l[21..22].to_string() } else { String::from(" ") };
-            let x: f64 = l[30..38].trim().parse().unwrap_or(0.0);
-            let y: f64 = l[38..46].trim().parse().unwrap_or(0.0);
-            let z: f64 = l[46..54].trim().parse().unwrap_or(0.0);
-            let rec = (Vec3::new(x,y,z), atom_name, res_name, chain_id.clone());
-            if chain_id == chain1 { mol1.push(rec); }
-            else if chain_id == chain2 { mol2.push(rec); }
-        }
-    }
-    Ok((mol1, mol2))
-}
-
-fn main() -> anyhow::Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 4 {
-        eprintln!("Usage: sc <pdb_file> <chain1> <chain2> [--json] [--no-parallel]");
-        std::process::exit(1);
+#[derive(serde::Serialize, Clone)]
+struct AttentionCounts {
+    buried: usize,
+    far: usize,
+}
+
+/// `--density-report`: achieved contact dot density per surface and the atoms sampled below
+/// `min_fraction` of their target
+#[derive(serde::Serialize, Clone)]
+struct DensityOutput {
+    min_fraction: f64,
+    surfaces: [SurfaceDensity; 2],
+    undersampled: Vec<UndersampledAtom>,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct UndersampledAtom {
+    /// 1 or 2
+    molecule: usize,
+    atom: String,
+    target: f64,
+    achieved: f64,
+    n_dots: usize,
+}
+
+/// `--config sc.toml` (or `$SC_CONFIG`): defaults for the command-line options, which override them.
+/// Keys mirror the flag names; unknown keys are an error so typos do not pass silently.
+#[derive(serde::Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    selection: SelectionConfig,
+    settings: SettingsConfig,
+    radii: RadiiConfig,
+    output: OutputConfig,
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct SelectionConfig {
+    chain1: Option<String>,
+    chain2: Option<String>,
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct SettingsConfig {
+    probe_radius: Option<f64>,
+    density: Option<f64>,
+    band: Option<f64>,
+    sep_cutoff: Option<f64>,
+    /// Derive the separation cutoff from the radii (`--auto-sep-cutoff`)
+    auto_sep_cutoff: Option<bool>,
+    gaussian_w: Option<f64>,
+    /// Same syntax as `--statistic`, e.g. "median,trimmed:0.1"
+    statistic: Option<String>,
+    trim_distance: Option<f64>,
+    threads: Option<usize>,
+    parallel: Option<bool>,
+    streaming: Option<bool>,
+    homodimer: Option<bool>,
+    /// Solvent-excluded volume of each molecule (`--volume`)
+    volume: Option<bool>,
+    lenient: Option<bool>,
+    drop_duplicates: Option<bool>,
+    /// Same syntax as `--focus`: "x,y,z" or "A:45-60,B:101"
+    focus: Option<String>,
+    focus_radius: Option<f64>,
+    /// Same syntax as `--mask`: "A:45-60,B:101"
+    mask: Option<String>,
+    mask_occludes: Option<bool>,
+    /// Cα-only model: residue-dependent CA radii and the separation cutoff derived from them
+    ca_only: Option<bool>,
+    /// MARTINI coarse-grained model: bead radii and the separation cutoff derived from them
+    martini: Option<bool>,
+    /// Same syntax as `--atom-subset`: "backbone" or "backbone,sidechain"
+    atom_subset: Option<String>,
+    /// Degrees from opposed within which paired normals count as antiparallel
+    antiparallel_tolerance: Option<f64>,
+    /// Van der Waals overlap in A beyond which an atom pair counts as a clash
+    clash_tolerance: Option<f64>,
+}
+
+impl SettingsConfig {
+    /// Overlay the values that are set onto `settings`; a switch set to false turns it off
+    fn apply(&self, settings: &mut Settings) -> anyhow::Result<()> {
+        if let Some(rp) = self.probe_radius { settings.rp = rp; }
+        if let Some(d) = self.density { settings.dot_density = d; }
+        if let Some(b) = self.band { settings.peripheral_band = b; }
+        if let Some(c) = self.sep_cutoff { settings.separation_cutoff = c; }
+        if let Some(on) = self.auto_sep_cutoff { settings.auto_separation_cutoff = on; }
+        if let Some(w) = self.gaussian_w { settings.gaussian_w = w; }
+        if let Some(stats) = &self.statistic {
+            let stats = stats.split(',').map(parse_statistic).collect::<anyhow::Result<Vec<_>>>()?;
+            if let Some((first, rest)) = stats.split_first() {
+                settings.statistic = *first;
+                settings.extra_statistics = rest.to_vec();
+            }
+        }
+        if let Some(d) = self.trim_distance { settings.trim_mode = TrimMode::PartnerDistance(d); }
+        if self.threads.is_some() { settings.num_threads = self.threads; }
+        if let Some(parallel) = self.parallel { settings.enable_parallel = parallel; }
+        if let Some(on) = self.streaming { settings.streaming = on; }
+        if let Some(on) = self.homodimer { settings.homodimer = on; }
+        if let Some(on) = self.volume { settings.molecular_volume = on; }
+        if let Some(on) = self.lenient { settings.skip_unassigned_atoms = on; }
+        if let Some(on) = self.drop_duplicates { settings.drop_coincident_atoms = on; }
+        if let Some(center) = &self.focus {
+            settings.focus = Some(Focus { center: parse_focus(center)?, radius: self.focus_radius.unwrap_or(12.0) });
+        }
+        if let Some(mask) = &self.mask {
+            settings.mask = mask.split(',').map(str::trim).map(|f| parse_residue_range(f, "mask", "CHAIN:START[-END]")).collect::<anyhow::Result<_>>()?;
+        }
+        if let Some(on) = self.mask_occludes { settings.mask_occludes = on; }
+        if self.ca_only == Some(true) && self.martini == Some(true) { return Err(fail(Failure::Usage, "--ca-only and --martini select different radii tables; use one")); }
+        // Turning a model off returns to the default table only if that model had selected it
+        match self.ca_only {
+            Some(true) => {
+                settings.radii_source = Some(RadiiSource::CalphaOnly);
+                settings.auto_separation_cutoff = true;
+            }
+            Some(false) if matches!(settings.radii_source, Some(RadiiSource::CalphaOnly)) => settings.radii_source = None,
+            _ => {}
+        }
+        match self.martini {
+            Some(true) => {
+                settings.radii_source = Some(RadiiSource::Martini);
+                settings.auto_separation_cutoff = true;
+            }
+            Some(false) if matches!(settings.radii_source, Some(RadiiSource::Martini)) => settings.radii_source = None,
+            _ => {}
+        }
+        if let Some(subset) = &self.atom_subset { settings.atom_subset = parse_atom_subset(subset)?; }
+        if let Some(t) = self.antiparallel_tolerance { settings.antiparallel_tolerance = t; }
+        if let Some(t) = self.clash_tolerance { settings.clash_tolerance = t; }
+        Ok(())
     }
-    let pdb = &args[1];
-    let chain1 = &args[2];
-    let chain2 = &args[3];
-    let json = args.iter().any(|a| a == "--json");
+}
+
+/// `--focus` center: a point `x,y,z`, or residues `CHAIN:START[-END]` separated by commas
+fn parse_focus(value: &str) -> anyhow::Result<FocusCenter> {
+    let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+    if fields.len() == 3 && !value.contains(':') {
+        let c = fields.iter().map(|v| v.parse::<f64>().map_err(|e| fail(Failure::Usage, format!("invalid focus coordinate '{v}': {e}")))).collect::<anyhow::Result<Vec<_>>>()?;
+        return Ok(FocusCenter::Point(Vec3::new(c[0], c[1], c[2])));
+    }
+    let ranges = fields.iter().map(|f| parse_residue_range(f, "focus", "CHAIN:START[-END] or x,y,z")).collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(FocusCenter::Residues(ranges))
+}
+
+/// One `CHAIN:START[-END]` residue span of `--focus` or `--mask`
+fn parse_residue_range(f: &str, what: &str, expected: &str) -> anyhow::Result<ResidueRange> {
+    let invalid = || fail(Failure::Usage, format!("invalid {what} residues '{f}' (expected {expected})"));
+    let (chain, span) = f.split_once(':').ok_or_else(invalid)?;
+    let (start, end) = span.split_once('-').unwrap_or((span, span));
+    let (start, end): (i32, i32) = (start.trim().parse().map_err(|_| invalid())?, end.trim().parse().map_err(|_| invalid())?);
+    Ok(ResidueRange { chain: chain.to_string(), start: start.min(end), end: start.max(end) })
+}
+
+/// Core settings from the command line over the config's `[settings]`: a flag wins over its key
+/// Flags read by `settings_from_flags` that take a value
+const SETTINGS_VALUE_FLAGS: &[&str] = &[
+    "--probe-radius", "--density", "--band", "--sep-cutoff", "--gaussian-w", "--statistic", "--trim-distance", "--threads",
+    "--focus", "--focus-radius", "--mask", "--atom-subset", "--antiparallel-tolerance", "--clash-tolerance",
+];
+
+/// Arguments that are neither flags nor the value following a settings flag or one of `value_flags`,
+/// so positionals may come before, between or after the flags
+fn positionals<'a>(args: &'a [String], value_flags: &[&str]) -> Vec<&'a String> {
+    let takes_value = |a: &String| SETTINGS_VALUE_FLAGS.contains(&a.as_str()) || value_flags.contains(&a.as_str());
+    args.iter().enumerate().filter(|&(i, a)| !a.starts_with('-') && (i == 0 || !takes_value(&args[i - 1]))).map(|(_, a)| a).collect()
+}
+
+fn settings_from_flags(args: &[String], cfg: SettingsConfig) -> anyhow::Result<SettingsConfig> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let number = |name: &str| flag(name).map(|v| v.parse::<f64>()).transpose();
+    let switch = |name: &str| args.iter().any(|a| a == name);
+    Ok(SettingsConfig {
+        // Unset parameters keep the Lawrence & Colman defaults
+        probe_radius: number("--probe-radius")?.or(cfg.probe_radius),
+        density: number("--density")?.or(cfg.density),
+        band: number("--band")?.or(cfg.band),
+        sep_cutoff: number("--sep-cutoff")?.or(cfg.sep_cutoff),
+        auto_sep_cutoff: switch("--auto-sep-cutoff").then_some(true).or(cfg.auto_sep_cutoff),
+        gaussian_w: number("--gaussian-w")?.or(cfg.gaussian_w),
+        statistic: flag("--statistic").cloned().or(cfg.statistic),
+        trim_distance: number("--trim-distance")?.or(cfg.trim_distance),
+        threads: flag("--threads").map(|v| v.parse::<usize>()).transpose()?.or(cfg.threads),
+        parallel: if switch("--no-parallel") { Some(false) } else { cfg.parallel },
+        streaming: switch("--streaming").then_some(true).or(cfg.streaming),
+        homodimer: switch("--homodimer").then_some(true).or(cfg.homodimer),
+        volume: switch("--volume").then_some(true).or(cfg.volume),
+        lenient: switch("--lenient").then_some(true).or(cfg.lenient),
+        drop_duplicates: switch("--drop-duplicates").then_some(true).or(cfg.drop_duplicates),
+        focus: flag("--focus").cloned().or(cfg.focus),
+        focus_radius: number("--focus-radius")?.or(cfg.focus_radius),
+        mask: flag("--mask").cloned().or(cfg.mask),
+        mask_occludes: switch("--mask-occludes").then_some(true).or(cfg.mask_occludes),
+        ca_only: switch("--ca-only").then_some(true).or(cfg.ca_only),
+        martini: switch("--martini").then_some(true).or(cfg.martini),
+        atom_subset: flag("--atom-subset").cloned().or(cfg.atom_subset),
+        antiparallel_tolerance: number("--antiparallel-tolerance")?.or(cfg.antiparallel_tolerance),
+        clash_tolerance: number("--clash-tolerance")?.or(cfg.clash_tolerance),
+    })
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RadiiConfig {
+    /// Radii JSON file, relative to the config file; the embedded table when unset
+    path: Option<String>,
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct OutputConfig {
+    json: bool,
+    /// Results file (`--output`); stdout when unset
+    path: Option<String>,
+    /// json, csv, tsv, yaml or text (`--format`)
+    format: Option<String>,
+    /// No progress bar or warnings (`--quiet`)
+    quiet: bool,
+    contacts: Option<String>,
+    residues: Option<String>,
+    pairs: Option<String>,
+    /// Per-dot gap distances (`--dot-distances`)
+    dot_distances: Option<String>,
+    /// Dot kinds for `--dot-kinds`, comma-separated
+    dot_kinds: Option<String>,
+    /// Residue types for `--residue-types`: hydrophobic, aromatic or comma-separated names
+    residue_types: Option<String>,
+    /// Annotated structure copy (`--bfactor-pdb`) and its value, sc or area (`--bfactor-value`)
+    bfactor_pdb: Option<String>,
+    bfactor_value: Option<String>,
+    /// 3D Zernike descriptors of both buried patches (`--zernike`) and their order (`--zernike-order`)
+    zernike: Option<String>,
+    zernike_order: Option<usize>,
+}
+
+/// Failure classes of the CLI, each with a stable exit code so workflow engines can branch on the
+/// kind of failure. `--json-errors` prints the class, code and message as a JSON object on stderr.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Failure {
+    /// Anything not covered below (exit code 1)
+    Other,
+    /// Missing arguments, bad flag values or invalid settings (2)
+    Usage,
+    /// Unreadable or malformed input: structure, config, radii, region or water files (3)
+    Parse,
+    /// A chain selection matched no atoms (4)
+    EmptySelection,
+    /// Surface construction failed: coincident atoms, invalid geometry, missing radii (5)
+    Geometry,
+    /// The selections do not touch, so there is no interface to score (6)
+    NoInterface,
+    /// A result file could not be written (7)
+    Output,
+    /// `sc validate` or `sc selfcheck` found values outside the tolerances (8)
+    Mismatch,
+}
+
+impl Failure {
+    fn exit_code(self) -> u8 {
+        match self { Failure::Other => 1, Failure::Usage => 2, Failure::Parse => 3, Failure::EmptySelection => 4, Failure::Geometry => 5, Failure::NoInterface => 6, Failure::Output => 7, Failure::Mismatch => 8 }
+    }
+
+    /// Class of an error: an explicit `ClassifiedError`, else by the library or parse error in its chain
+    fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(e) = cause.downcast_ref::<ClassifiedError>() { return e.failure; }
+            if let Some(e) = cause.downcast_ref::<SurfaceCalculatorError>() {
+                return match e {
+                    SurfaceCalculatorError::InvalidSettings(_) | SurfaceCalculatorError::InvalidGroup(..) => Failure::Usage,
+                    SurfaceCalculatorError::Io(_) => Failure::Parse,
+                    SurfaceCalculatorError::NoAtoms | SurfaceCalculatorError::EmptyMolecule(_) | SurfaceCalculatorError::EmptySelection { .. } => Failure::EmptySelection,
+                    SurfaceCalculatorError::NoDots => Failure::NoInterface,
+                    SurfaceCalculatorError::Cancelled => Failure::Other,
+                    _ => Failure::Geometry,
+                };
+            }
+            if cause.is::<std::num::ParseFloatError>() || cause.is::<std::num::ParseIntError>() { return Failure::Usage; }
+        }
+        Failure::Other
+    }
+}
+
+/// CLI error tagged with its failure class; displays as the message alone
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+struct ClassifiedError {
+    failure: Failure,
+    message: String,
+}
+
+fn fail(failure: Failure, message: impl Into<String>) -> anyhow::Error {
+    ClassifiedError { failure, message: message.into() }.into()
+}
+
+#[derive(serde::Serialize)]
+struct ErrorReport {
+    error: Failure,
+    exit_code: u8,
+    message: String,
+}
+
+fn read_config(path: &str) -> anyhow::Result<Config> {
+    let text = std::fs::read_to_string(path).map_err(|e| fail(Failure::Parse, format!("cannot read config {path}: {e}")))?;
+    let mut config: Config = toml::from_str(&text).map_err(|e| fail(Failure::Parse, format!("invalid config {path}: {e}")))?;
+    if let Some(radii) = &mut config.radii.path {
+        let dir = std::path::Path::new(path).parent().unwrap_or(std::path::Path::new(""));
+        *radii = dir.join(&*radii).to_string_lossy().into_owned();
+    }
+    Ok(config)
+}
+
+/// Result format of the main report. CSV and TSV hold one header line and one row of the scalar
+/// results; JSON and YAML carry everything `--json` reports.
+#[derive(Clone, Copy, PartialEq)]
+enum Format { Text, Json, Csv, Tsv, Yaml }
+
+fn parse_format(value: &str) -> anyhow::Result<Format> {
+    Ok(match value.to_ascii_lowercase().as_str() {
+        "text" | "txt" => Format::Text,
+        "json" => Format::Json,
+        "csv" => Format::Csv,
+        "tsv" => Format::Tsv,
+        "yaml" | "yml" => Format::Yaml,
+        _ => return Err(fail(Failure::Usage, format!("unknown format '{value}' (json, csv, tsv, yaml, text)"))),
+    })
+}
+
+fn write_summary(dest: &mut dyn Write, out: &Output, sep: &str) -> anyhow::Result<()> {
+    // Columns added later go at the end, so consumers reading by position keep working
+    let header = ["version", "sc", "median_distance", "trimmed_area", "buried_sasa", "gap_volume", "gap_index", "electrostatic_complementarity", "atoms_mol1", "atoms_mol2", "elapsed_ms", "sc_ab", "sc_ba", "asymmetry"];
+    let row = [out.version.to_string(), format!("{:.3}", out.sc), format!("{:.3}", out.median_distance), format!("{:.3}", out.trimmed_area), format!("{:.3}", out.buried_sasa),
+        format!("{:.3}", out.gap_volume), format!("{:.3}", out.gap_index), out.electrostatic_complementarity.map(|ec| format!("{ec:.3}")).unwrap_or_default(),
+        out.atoms_mol1.to_string(), out.atoms_mol2.to_string(), out.elapsed_ms.to_string(),
+        format!("{:.3}", out.directional.sc_ab), format!("{:.3}", out.directional.sc_ba), format!("{:.3}", out.directional.asymmetry)];
+    writeln!(dest, "{}", header.join(sep))?;
+    writeln!(dest, "{}", row.join(sep))?;
+    Ok(())
+}
+
+/// Progress bar on stderr driven by the calculator's progress callback; indicatif hides it when
+/// stderr is not a terminal. With `log_phases` (`-vv`), each phase start is also logged with the
+/// time since the start of the run.
+fn progress_bar(log_phases: bool) -> (indicatif::ProgressBar, Progress) {
+    let style = indicatif::ProgressStyle::with_template("{msg:<10} [{bar:40}] {pos}/{len} ({elapsed})").expect("valid template").progress_chars("=> ");
+    let bar = indicatif::ProgressBar::new(0).with_style(style);
+    let (handle, start, current) = (bar.clone(), std::time::Instant::now(), std::sync::Mutex::new(None::<Phase>));
+    let progress = Progress::new(move |phase, done, total| {
+        let mut current = current.lock().unwrap_or_else(|e| e.into_inner());
+        if *current != Some(phase) {
+            *current = Some(phase);
+            handle.set_length(total as u64);
+            handle.set_position(done as u64);
+            handle.set_message(format!("{phase:?}").to_lowercase());
+            if log_phases {
+                let line = format!("[{:>8.3} s] {phase:?} ({total} items)", start.elapsed().as_secs_f64());
+                if handle.is_hidden() { eprintln!("{line}"); } else { handle.println(line); }
+            }
+        } else {
+            // Parallel workers may report slightly out of order
+            handle.set_position((done as u64).max(handle.position()));
+        }
+    });
+    (bar, progress)
+}
+
+fn parse_list(value: &str) -> anyhow::Result<Vec<f64>> {
+    value.split(',').map(|v| v.trim().parse::<f64>().map_err(|e| fail(Failure::Usage, format!("invalid number '{v}': {e}")))).collect()
+}
+
+fn parse_dot_kinds(value: &str) -> anyhow::Result<Vec<DotKind>> {
+    value.split(',').map(|v| v.parse::<DotKind>().map_err(|e| fail(Failure::Usage, e))).collect()
+}
+
+/// `--residue-types`: a named set (hydrophobic, aromatic) or comma-separated residue names
+fn parse_residue_types(value: &str) -> Vec<String> {
+    let names: &[&str] = match value.trim() {
+        "hydrophobic" => HYDROPHOBIC_RESIDUES,
+        "aromatic" => AROMATIC_RESIDUES,
+        _ => return value.split(',').map(|v| v.trim().to_ascii_uppercase()).filter(|v| !v.is_empty()).collect(),
+    };
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+/// Copy of the input PDB with each interface residue's local Sc (`sc`) or buried area (`area`) in the
+/// B-factor column; other residues get 0
+fn write_bfactor_pdb(path: &str, input: &str, residues: &[Vec<InterfaceResidue>; 2], value: &str) -> anyhow::Result<()> {
+    let values: std::collections::HashMap<ResidueId, f64> = residues.iter().flatten()
+        .filter_map(|r| Some((r.residue.clone(), if value == "area" { r.buried_area } else { r.sc? })))
+        .collect();
+    let data = std::fs::read_to_string(input)?;
+    std::fs::write(path, write_pdb_bfactors(&data, &values, 0.0))?;
+    Ok(())
+}
+
+fn write_contacts(path: &str, contacts: &[ResidueContact]) -> anyhow::Result<()> {
+    let mut f = File::create(path)?;
+    if path.to_ascii_lowercase().ends_with(".csv") {
+        writeln!(f, "chain1,resnum1,icode1,residue1,chain2,resnum2,icode2,residue2,area,min_distance,n_dots")?;
+        for c in contacts {
+            let (r1, r2) = (&c.residue1, &c.residue2);
+            writeln!(f, "{},{},{},{},{},{},{},{},{:.3},{:.3},{}",
+                r1.chain, r1.resnum, r1.icode.map(String::from).unwrap_or_default(), r1.residue,
+                r2.chain, r2.resnum, r2.icode.map(String::from).unwrap_or_default(), r2.residue,
+                c.area, c.min_distance, c.n_dots)?;
+        }
+    } else {
+        writeln!(f, "{}", serde_json::to_string_pretty(contacts)?)?;
+    }
+    Ok(())
+}
+
+fn write_interface_residues(path: &str, residues: &[Vec<InterfaceResidue>; 2]) -> anyhow::Result<()> {
+    let mut f = File::create(path)?;
+    if path.to_ascii_lowercase().ends_with(".csv") {
+        writeln!(f, "molecule,chain,resnum,icode,residue,buried_area,n_buried_dots,trimmed_area,sc")?;
+        for (mol, list) in residues.iter().enumerate() {
+            for r in list {
+                let id = &r.residue;
+                writeln!(f, "{},{},{},{},{},{:.3},{},{:.3},{}",
+                    mol + 1, id.chain, id.resnum, id.icode.map(String::from).unwrap_or_default(), id.residue,
+                    r.buried_area, r.n_buried_dots, r.trimmed_area, r.sc.map(|s| format!("{s:.3}")).unwrap_or_default())?;
+            }
+        }
+    } else {
+        writeln!(f, "{}", serde_json::to_string_pretty(residues)?)?;
+    }
+    Ok(())
+}
+
+/// 3D Zernike descriptors of both buried patches: one row per molecule in CSV, an array in JSON
+fn write_zernike(path: &str, descriptors: &[ZernikeDescriptor; 2]) -> anyhow::Result<()> {
+    let mut f = File::create(path)?;
+    if path.to_ascii_lowercase().ends_with(".csv") {
+        let columns: Vec<String> = ZernikeDescriptor::indices(descriptors[0].order).iter().map(|(n, l)| format!("z{n}_{l}")).collect();
+        writeln!(f, "molecule,n_dots,area,radius,{}", columns.join(","))?;
+        for (mol, d) in descriptors.iter().enumerate() {
+            let values: Vec<String> = d.values.iter().map(|v| format!("{v:.6}")).collect();
+            writeln!(f, "{},{},{:.3},{:.3},{}", mol + 1, d.n_dots, d.area, d.radius, values.join(","))?;
+        }
+    } else {
+        writeln!(f, "{}", serde_json::to_string_pretty(descriptors)?)?;
+    }
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct PairRow {
+    surface: usize,
+    dot: usize,
+    neighbor: usize,
+    x: f64, y: f64, z: f64,
+    neighbor_x: f64, neighbor_y: f64, neighbor_z: f64,
+    distance: f64,
+    normal_angle: f64,
+    s: f64,
+}
+
+/// Dot pairs of both surfaces; with `kinds`, only pairs whose own dot is of one of those kinds
+fn write_pairs(path: &str, sc: &ScCalculator, kinds: Option<&[DotKind]>) -> anyhow::Result<()> {
+    let mut rows = Vec::new();
+    for mol in 0..2 {
+        for p in sc.dot_pairs(mol) {
+            let (a, b) = (&sc.base.dots(mol)[p.dot], &sc.base.dots(1 - mol)[p.neighbor]);
+            if kinds.is_some_and(|k| !k.contains(&a.kind)) { continue; }
+            rows.push(PairRow {
+                surface: mol + 1, dot: p.dot, neighbor: p.neighbor,
+                x: a.coor.x, y: a.coor.y, z: a.coor.z,
+                neighbor_x: b.coor.x, neighbor_y: b.coor.y, neighbor_z: b.coor.z,
+                distance: p.distance, normal_angle: p.normal_angle(), s: p.score,
+            });
+        }
+    }
+    let mut f = File::create(path)?;
+    if path.to_ascii_lowercase().ends_with(".csv") {
+        writeln!(f, "surface,dot,neighbor,x,y,z,neighbor_x,neighbor_y,neighbor_z,distance,normal_angle,s")?;
+        for r in &rows {
+            writeln!(f, "{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.4},{:.2},{:.4}",
+                r.surface, r.dot, r.neighbor, r.x, r.y, r.z, r.neighbor_x, r.neighbor_y, r.neighbor_z, r.distance, r.normal_angle, r.s)?;
+        }
+    } else {
+        writeln!(f, "{}", serde_json::to_string_pretty(&rows)?)?;
+    }
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct DotDistanceRow {
+    surface: usize,
+    chain: String,
+    resnum: i32,
+    residue: String,
+    atom: String,
+    x: f64, y: f64, z: f64,
+    /// Gap to the nearest partner dot (A)
+    distance: f64,
+}
+
+/// Each trimmed dot with its owning atom and distance to the partner surface, for coloring surfaces
+/// by local gap. `.pdb` writes the dots as HETATM pseudo-atoms (name DOT) with the distance in the
+/// B-factor column; `.json` a JSON array; anything else a table, tab-separated for `.tsv`
+fn write_dot_distances(path: &str, sc: &ScCalculator) -> anyhow::Result<()> {
+    let mut rows = Vec::new();
+    for mol in 0..2 {
+        for p in sc.dot_pairs(mol) {
+            let dot = &sc.base.dots(mol)[p.dot];
+            let atom = &sc.base.atoms()[dot.atom_index];
+            rows.push(DotDistanceRow {
+                surface: mol + 1, chain: atom.chain.clone(), resnum: atom.resnum, residue: atom.residue.trim().to_string(), atom: atom.atom.trim().to_string(),
+                x: dot.coor.x, y: dot.coor.y, z: dot.coor.z, distance: p.distance,
+            });
+        }
+    }
+    let mut f = File::create(path)?;
+    let lower = path.to_ascii_lowercase();
+    if lower.ends_with(".pdb") {
+        for (i, r) in rows.iter().enumerate() {
+            let chain = r.chain.chars().next().unwrap_or(' ');
+            writeln!(f, "HETATM{:>5}  DOT {:>3} {}{:>4}    {:>8.3}{:>8.3}{:>8.3}{:>6.2}{:>6.2}", (i + 1) % 100_000, r.residue, chain, r.resnum, r.x, r.y, r.z, 1.0, r.distance.min(999.99))?;
+        }
+        writeln!(f, "END")?;
+    } else if lower.ends_with(".json") {
+        writeln!(f, "{}", serde_json::to_string_pretty(&rows)?)?;
+    } else {
+        let sep = if lower.ends_with(".tsv") { "\t" } else { "," };
+        writeln!(f, "{}", ["surface", "chain", "resnum", "residue", "atom", "x", "y", "z", "distance"].join(sep))?;
+        for r in &rows {
+            writeln!(f, "{}", [r.surface.to_string(), r.chain.clone(), r.resnum.to_string(), r.residue.clone(), r.atom.clone(),
+                format!("{:.3}", r.x), format!("{:.3}", r.y), format!("{:.3}", r.z), format!("{:.4}", r.distance)].join(sep))?;
+        }
+    }
+    Ok(())
+}
+
+/// `--atom-subset`: one subset for both molecules, or one per molecule separated by a comma
+fn parse_atom_subset(value: &str) -> anyhow::Result<[AtomSubset; 2]> {
+    let subsets = value.split(',').map(|v| match v.trim() {
+        "all" => Ok(AtomSubset::All),
+        "backbone" => Ok(AtomSubset::Backbone),
+        "sidechain" => Ok(AtomSubset::SideChain),
+        _ => Err(fail(Failure::Usage, format!("unknown atom subset '{v}' (all, backbone, sidechain)"))),
+    }).collect::<anyhow::Result<Vec<_>>>()?;
+    match subsets[..] {
+        [s] => Ok([s, s]),
+        [s1, s2] => Ok([s1, s2]),
+        _ => Err(fail(Failure::Usage, format!("invalid atom subset '{value}' (expected <subset> or <subset1>,<subset2>)"))),
+    }
+}
+
+fn parse_statistic(value: &str) -> anyhow::Result<ScStatistic> {
+    let (name, arg) = value.split_once(':').map(|(n, a)| (n, Some(a))).unwrap_or((value, None));
+    let num = |a: Option<&str>| -> anyhow::Result<f64> {
+        a.ok_or_else(|| fail(Failure::Usage, format!("statistic '{name}' needs a value, e.g. {name}:0.1")))?.parse::<f64>().map_err(|e| fail(Failure::Usage, format!("invalid statistic value: {e}")))
+    };
+    Ok(match name {
+        "median" => ScStatistic::Median,
+        "mean" => ScStatistic::Mean,
+        "trimmed" => ScStatistic::TrimmedMean(num(arg)?),
+        "quantile" => ScStatistic::Quantile(num(arg)?),
+        _ => return Err(fail(Failure::Usage, format!("unknown statistic '{value}' (median, mean, trimmed:<f>, quantile:<q>)"))),
+    })
+}
+
+#[derive(serde::Serialize)]
+struct BenchRow {
+    atoms: usize,
+    dots: usize,
+    sc: f64,
+    /// Trimmed interface area
+    area: f64,
+    elapsed_ms: f64,
+    /// Phase times of the fastest repeat, in seconds
+    timings: Timings,
+}
+
+#[derive(serde::Serialize)]
+struct BenchReport {
+    version: &'static str,
+    repeat: usize,
+    runs: Vec<BenchRow>,
+}
+
+/// Two globules of about `n_atoms` atoms each on a jittered 3.2 A lattice, overlapping slightly so
+/// that roughly a tenth of each surface forms the interface (atoms of the second globule closer
+/// than the lattice spacing to the first are removed). Generated from a fixed seed, so every build benchmarks the same
+/// systems.
+fn synthetic_complex(n_atoms: usize) -> (Vec<Atom>, Vec<Atom>) {
+    const SPACING: f64 = 3.2;
+    const NAMES: [&str; 5] = ["N", "CA", "C", "O", "CB"];
+    let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut jitter = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * 0.8
+    };
+    let steps = ((3.0 * n_atoms as f64 / (4.0 * std::f64::consts::PI)).cbrt()).ceil() as i32 + 1;
+    let axis = || -steps..=steps;
+    let mut sites: Vec<Vec3> = axis()
+        .flat_map(|i| axis().flat_map(move |j| axis().map(move |k| Vec3::new(i as f64, j as f64, k as f64) * SPACING)))
+        .collect();
+    sites.sort_by(|a, b| a.magnitude_squared().total_cmp(&b.magnitude_squared()).then(a.x.total_cmp(&b.x)).then(a.y.total_cmp(&b.y)).then(a.z.total_cmp(&b.z)));
+    sites.truncate(n_atoms);
+    let radius = sites.last().map(|p| p.magnitude()).unwrap_or(0.0);
+    // A contact patch of radius r on spheres of radius R needs an overlap of about r^2 / 2R
+    let center2 = Vec3::new(1.8 * radius, 0.0, 0.0);
+    let mut molecule = |center: Vec3, keep: &dyn Fn(Vec3) -> bool| -> Vec<Atom> {
+        sites.iter().map(|&p| center + p + Vec3::new(jitter(), jitter(), jitter())).filter(|&p| keep(p)).enumerate().map(|(n, p)| {
+            let mut atom = Atom::new();
+            atom.atom = NAMES[n % NAMES.len()].to_string();
+            atom.residue = "ALA".to_string();
+            atom.resnum = (n / NAMES.len() + 1) as i32;
+            atom.coor = p;
+            atom
+        }).collect()
+    };
+    let mol1 = molecule(Vec3::zero(), &|_| true);
+    let mol2 = molecule(center2, &|p| mol1.iter().all(|a| a.coor.distance_squared(p) >= SPACING * SPACING));
+    (mol1, mol2)
+}
+
+/// `sc bench`: time the calculator on synthetic complexes of increasing size
+fn run_bench(args: &[String]) -> anyhow::Result<()> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let sizes: Vec<usize> = match flag("--sizes") {
+        Some(v) => v.split(',').map(|n| n.trim().parse::<usize>().map_err(|e| fail(Failure::Usage, format!("invalid size '{n}': {e}")))).collect::<anyhow::Result<_>>()?,
+        None => vec![500, 2000, 8000],
+    };
+    let repeat = flag("--repeat").map(|v| v.parse::<usize>()).transpose()?.unwrap_or(3).max(1);
+    let threads = flag("--threads").map(|v| v.parse::<usize>()).transpose()?;
     let no_parallel = args.iter().any(|a| a == "--no-parallel");
+    let json = args.iter().any(|a| a == "--json");
+    let mut rows = Vec::new();
+    for &n in &sizes {
+        let (mol1, mol2) = synthetic_complex(n);
+        let mut best: Option<BenchRow> = None;
+        for _ in 0..repeat {
+            let mut sc = ScCalculator::new();
+            sc.settings_mut().enable_parallel = !no_parallel;
+            sc.settings_mut().num_threads = threads;
+            let t0 = std::time::Instant::now();
+            sc.add_atoms(0, mol1.iter().cloned())?;
+            sc.add_atoms(1, mol2.iter().cloned())?;
+            let results = sc.calc()?;
+            let elapsed_ms = t0.elapsed().as_secs_f64() * 1000.0;
+            if best.as_ref().is_none_or(|b| elapsed_ms < b.elapsed_ms) {
+                best = Some(BenchRow { atoms: mol1.len() + mol2.len(), dots: results.surfaces[0].n_all_dots + results.surfaces[1].n_all_dots, sc: results.sc, area: results.area, elapsed_ms, timings: results.timings });
+            }
+        }
+        rows.extend(best);
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&BenchReport { version: env!("CARGO_PKG_VERSION"), repeat, runs: rows })?);
+        return Ok(());
+    }
+    println!("sc-rs {} bench (best of {repeat}, times in ms)", env!("CARGO_PKG_VERSION"));
+    println!("{:>7} {:>8} {:>6} {:>8} {:>9} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7}",
+        "atoms", "dots", "SC", "area", "total", "radii", "attn", "neigh", "contact", "toroid", "concave", "burial", "trim", "stat", "reports");
+    for row in &rows {
+        let t = &row.timings;
+        let ms = [t.radii, t.attention, t.neighbors, t.contact, t.toroidal, t.concave, t.burial, t.trimming, t.statistic, t.reports].map(|s| s * 1000.0);
+        println!("{:>7} {:>8} {:>6.3} {:>8.1} {:>9.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1}",
+            row.atoms, row.dots, row.sc, row.area, row.elapsed_ms, ms[0], ms[1], ms[2], ms[3], ms[4], ms[5], ms[6], ms[7], ms[8], ms[9]);
+    }
+    Ok(())
+}
 
-    let (mol1, mol2) = parse_pdb_atoms(pdb, chain1, chain2)?;
-    if mol1.is_empty() || mol2.is_empty() {
-        anyhow::bail!("No atoms found for one or both chains");
+#[derive(serde::Serialize)]
+struct ChainRow {
+    chain: String,
+    residues: usize,
+    atoms: usize,
+}
+
+#[derive(serde::Serialize)]
+struct ChainContactRow {
+    chain1: String,
+    chain2: String,
+    /// Atoms of each chain within the cutoff of the other
+    atoms1: usize,
+    atoms2: usize,
+}
+
+#[derive(serde::Serialize)]
+struct ChainsReport {
+    cutoff: f64,
+    chains: Vec<ChainRow>,
+    contacts: Vec<ChainContactRow>,
+}
+
+/// `sc chains`: list the chains of a structure and which pairs touch, to help choose selections.
+/// Contacts count heavy atoms within `--cutoff` (default 4.5 A) of an atom of the other chain.
+fn run_chains(args: &[String]) -> anyhow::Result<()> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let Some(path) = args.first().filter(|a| !a.starts_with("--")) else {
+        return Err(fail(Failure::Usage, "usage: sc chains <pdb_or_pqr_file> [--cutoff <A>] [--json]"));
+    };
+    let cutoff = flag("--cutoff").map(|v| v.parse::<f64>()).transpose()?.unwrap_or(4.5);
+    if cutoff <= 0.0 { return Err(fail(Failure::Usage, "--cutoff must be positive")); }
+    let json = args.iter().any(|a| a == "--json");
+    let atoms = read_structure_all_atoms_from_path(path).map_err(|e| fail(Failure::Parse, format!("cannot read {path}: {e}")))?;
+
+    // Chains in order of first appearance
+    let mut names: Vec<&str> = Vec::new();
+    for a in &atoms { if !names.contains(&a.chain.as_str()) { names.push(&a.chain); } }
+    let chain_of: Vec<usize> = atoms.iter().map(|a| names.iter().position(|&c| c == a.chain).unwrap_or(0)).collect();
+    let chains: Vec<ChainRow> = names.iter().enumerate().map(|(c, name)| {
+        let mut residues: Vec<(i32, Option<char>)> = atoms.iter().zip(&chain_of).filter(|(_, &k)| k == c).map(|(a, _)| (a.resnum, a.icode)).collect();
+        residues.sort_unstable();
+        residues.dedup();
+        ChainRow { chain: name.to_string(), residues: residues.len(), atoms: chain_of.iter().filter(|&&k| k == c).count() }
+    }).collect();
+
+    // Hash grid with cells of one cutoff, so every partner lies in the 27 cells around an atom
+    let key = |p: Vec3| ((p.x / cutoff).floor() as i64, (p.y / cutoff).floor() as i64, (p.z / cutoff).floor() as i64);
+    let mut grid: std::collections::HashMap<(i64, i64, i64), Vec<usize>> = std::collections::HashMap::new();
+    for (i, a) in atoms.iter().enumerate() { grid.entry(key(a.coor)).or_default().push(i); }
+    // touching[i] holds the chains with an atom within the cutoff of atom i
+    let n = names.len();
+    let mut counts = vec![0usize; n * n];
+    let mut touching = Vec::new();
+    for (i, a) in atoms.iter().enumerate() {
+        touching.clear();
+        let (cx, cy, cz) = key(a.coor);
+        for dx in -1..=1 { for dy in -1..=1 { for dz in -1..=1 {
+            let Some(members) = grid.get(&(cx + dx, cy + dy, cz + dz)) else { continue };
+            for &j in members {
+                if chain_of[j] != chain_of[i] && !touching.contains(&chain_of[j]) && a.coor.distance_squared(atoms[j].coor) <= cutoff * cutoff {
+                    touching.push(chain_of[j]);
+                }
+            }
+        }}}
+        for &c in &touching { counts[chain_of[i] * n + c] += 1; }
     }
+    let mut contacts = Vec::new();
+    for c1 in 0..n { for c2 in c1 + 1..n {
+        if counts[c1 * n + c2] > 0 {
+            contacts.push(ChainContactRow { chain1: names[c1].to_string(), chain2: names[c2].to_string(), atoms1: counts[c1 * n + c2], atoms2: counts[c2 * n + c1] });
+        }
+    }}
 
+    if json {
+        println!("{}", serde_json::to_string_pretty(&ChainsReport { cutoff, chains, contacts })?);
+        return Ok(());
+    }
+    println!("{:<6} {:>9} {:>8}", "chain", "residues", "atoms");
+    for c in &chains { println!("{:<6} {:>9} {:>8}", format!("'{}'", c.chain), c.residues, c.atoms); }
+    if contacts.is_empty() {
+        println!("\nNo chains within {cutoff:.1} A of each other");
+        return Ok(());
+    }
+    println!("\nContacts (heavy atoms within {cutoff:.1} A):");
+    println!("{:<6} {:<6} {:>8} {:>8}", "chain1", "chain2", "atoms1", "atoms2");
+    for c in &contacts { println!("{:<6} {:<6} {:>8} {:>8}", format!("'{}'", c.chain1), format!("'{}'", c.chain2), c.atoms1, c.atoms2); }
+    Ok(())
+}
+
+/// `sc pockets`: surface pockets of one molecule (the chains in an optional selection, else all),
+/// found by clustering its buried probe spheres, largest first
+fn run_pockets(args: &[String]) -> anyhow::Result<()> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let Some(path) = args.first().filter(|a| !a.starts_with("--")) else {
+        return Err(fail(Failure::Usage, "usage: sc pockets <pdb_or_pqr_file> [<chains>] [--probe-radius <A>] [--min-buriedness <f>] [--link <A>] [--min-probes <n>] [--json]"));
+    };
+    let selection = args.get(1).filter(|a| !a.starts_with("--"));
+    let mut settings = Settings::default();
+    if let Some(rp) = flag("--probe-radius").map(|v| v.parse::<f64>()).transpose()? { settings.rp = rp; }
+    settings.validate()?;
+    let mut params = PocketSettings::default();
+    if let Some(v) = flag("--min-buriedness").map(|v| v.parse::<f64>()).transpose()? { params.min_buriedness = v; }
+    if let Some(v) = flag("--link").map(|v| v.parse::<f64>()).transpose()? { params.link_distance = v; }
+    if let Some(v) = flag("--min-probes").map(|v| v.parse::<usize>()).transpose()? { params.min_probes = v; }
+    if params.link_distance <= 0.0 { return Err(fail(Failure::Usage, "--link must be positive")); }
+    let json = args.iter().any(|a| a == "--json");
+    let atoms: Vec<Atom> = read_structure_all_atoms_from_path(path).map_err(|e| fail(Failure::Parse, format!("cannot read {path}: {e}")))?
+        .into_iter().filter(|a| selection.is_none_or(|s| chain_selected(s, &a.chain))).collect();
+    if atoms.is_empty() { return Err(SurfaceCalculatorError::EmptySelection { selection: selection.cloned().unwrap_or_default(), path: path.clone() }.into()); }
+    let found = pockets(&atoms, &settings, &params)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&found)?);
+        return Ok(());
+    }
+    if found.is_empty() {
+        println!("No pockets (at least {} probes with buriedness >= {:.2})", params.min_probes, params.min_buriedness);
+        return Ok(());
+    }
+    println!("{:>4} {:>9} {:>6} {:>7} {:>26}  residues", "rank", "volume", "burial", "probes", "center");
+    for (i, p) in found.iter().enumerate() {
+        let center = format!("{:.2},{:.2},{:.2}", p.center.x, p.center.y, p.center.z);
+        let residues: Vec<String> = p.residues.iter().map(|r| format!("{}:{}{}{}", r.chain.trim(), r.residue.trim(), r.resnum, r.icode.map(String::from).unwrap_or_default())).collect();
+        println!("{:>4} {:>9.1} {:>6.2} {:>7} {:>26}  {}", i + 1, p.volume, p.buriedness, p.n_probes, center, residues.join(" "));
+    }
+    Ok(())
+}
+
+/// One manifest row: a structure, two chain selections, and optional overrides of the `[settings]`
+/// keys of the config file (`density`, `statistic`, `trim_distance`, ...)
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Job {
+    #[serde(default)]
+    id: Option<String>,
+    file: String,
+    selection1: String,
+    selection2: String,
+    #[serde(default)]
+    settings: SettingsConfig,
+}
+
+/// Result record of one job; the values are empty when it failed
+#[derive(serde::Serialize)]
+struct JobRecord {
+    id: String,
+    file: String,
+    selection1: String,
+    selection2: String,
+    sc: Option<f64>,
+    median_distance: Option<f64>,
+    trimmed_area: Option<f64>,
+    buried_sasa: Option<f64>,
+    gap_volume: Option<f64>,
+    gap_index: Option<f64>,
+    atoms_mol1: Option<usize>,
+    atoms_mol2: Option<usize>,
+    /// With `--prescreen`: the job's contacts and coarse Sc, and whether it ranked among those
+    /// given the full calculation (the full values are empty otherwise)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contacts: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prescreen_sc: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selected: Option<bool>,
+    elapsed_ms: u128,
+    error: Option<Failure>,
+    message: Option<String>,
+}
+
+/// Jobs of a JSONL manifest (`.jsonl`, `.ndjson`: one object per line, overrides under `settings`)
+/// or a CSV/TSV one whose header names `id`, `file`, `selection1`, `selection2` and any settings
+/// keys as further columns. Blank lines and lines starting with `#` are skipped, and relative
+/// structure paths are resolved against the manifest's directory.
+fn read_manifest(path: &str) -> anyhow::Result<Vec<Job>> {
+    let text = std::fs::read_to_string(path).map_err(|e| fail(Failure::Parse, format!("cannot read manifest {path}: {e}")))?;
+    let lower = path.to_ascii_lowercase();
+    let mut lines = text.lines().enumerate().map(|(n, l)| (n + 1, l.trim())).filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+    let invalid = |line: usize, e: &dyn std::fmt::Display| fail(Failure::Parse, format!("invalid manifest {path} line {line}: {e}"));
+    let mut jobs: Vec<Job> = if lower.ends_with(".jsonl") || lower.ends_with(".ndjson") {
+        lines.map(|(n, l)| serde_json::from_str(l).map_err(|e| invalid(n, &e))).collect::<anyhow::Result<_>>()?
+    } else {
+        let sep = if lower.ends_with(".tsv") { '\t' } else { ',' };
+        let Some((_, header)) = lines.next() else { return Ok(Vec::new()) };
+        let header = csv_fields(header, sep);
+        lines.map(|(n, l)| {
+            // Cells become JSON values so the rows deserialize like JSONL; empty cells are unset
+            let (mut row, mut settings) = (serde_json::Map::new(), serde_json::Map::new());
+            let cells = csv_fields(l, sep);
+            for (k, v) in header.iter().map(String::as_str).zip(cells.iter().map(String::as_str)).filter(|(_, v)| !v.is_empty()) {
+                match (k, v.parse::<f64>(), v.parse::<bool>()) {
+                    ("id" | "file" | "selection1" | "selection2", _, _) => { row.insert(k.to_string(), v.into()); }
+                    ("threads", Ok(x), _) => { settings.insert(k.to_string(), (x as u64).into()); }
+                    (_, Ok(x), _) if k != "statistic" => { settings.insert(k.to_string(), x.into()); }
+                    (_, _, Ok(b)) => { settings.insert(k.to_string(), b.into()); }
+                    _ => { settings.insert(k.to_string(), v.into()); }
+                }
+            }
+            row.insert("settings".to_string(), settings.into());
+            serde_json::from_value(row.into()).map_err(|e| invalid(n, &e))
+        }).collect::<anyhow::Result<_>>()?
+    };
+    let dir = std::path::Path::new(path).parent().unwrap_or(std::path::Path::new(""));
+    for job in &mut jobs { job.file = dir.join(&job.file).to_string_lossy().into_owned(); }
+    Ok(jobs)
+}
+
+/// Under `RadiiSource::CalphaOnly` (`--ca-only`), keep only the CA atoms: their radii stand for
+/// whole residues, and other atoms have none
+fn calpha_filter(atoms: Vec<Atom>, settings: &Settings) -> Vec<Atom> {
+    if !matches!(settings.radii_source, Some(RadiiSource::CalphaOnly)) { return atoms; }
+    atoms.into_iter().filter(|a| a.atom.trim() == "CA").collect()
+}
+
+/// Apply one manifest job's settings to `sc` (which keeps its radii table and buffers between
+/// jobs) and load its two selections in place of the previous atoms
+fn load_job(sc: &mut ScCalculator, base: &Settings, job: &Job) -> anyhow::Result<()> {
+    *sc.settings_mut() = base.clone();
+    job.settings.apply(sc.settings_mut())?;
+    sc.settings().validate()?;
+    let (mol1, mol2) = read_structure_atoms_from_path(&job.file, &job.selection1, &job.selection2).map_err(|e| fail(Failure::Parse, format!("cannot read {}: {e}", job.file)))?;
+    for (atoms, selection) in [(&mol1, &job.selection1), (&mol2, &job.selection2)] {
+        if atoms.is_empty() { return Err(SurfaceCalculatorError::EmptySelection { selection: selection.clone(), path: job.file.clone() }.into()); }
+    }
+    sc.reset();
+    sc.add_atoms(0, calpha_filter(mol1, sc.settings()))?;
+    sc.add_atoms(1, calpha_filter(mol2, sc.settings()))?;
+    Ok(())
+}
+
+/// Run one manifest job on `sc`
+fn run_job(sc: &mut ScCalculator, base: &Settings, job: &Job) -> anyhow::Result<Results> {
+    load_job(sc, base, job)?;
+    let results = sc.calc()?;
+    if results.area <= 0.0 { return Err(fail(Failure::NoInterface, "no interface between the selections: no dots remain after trimming")); }
+    Ok(results)
+}
+
+/// Fields of one CSV/TSV line, trimmed; a quoted field may hold the separator and `""` for a quote,
+/// as `csv_cell` writes them
+fn csv_fields(line: &str, sep: char) -> Vec<String> {
+    let (mut fields, mut field, mut quoted) = (Vec::new(), String::new(), false);
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => { field.push('"'); chars.next(); }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => { quoted = true; field.clear(); }
+            c if c == sep && !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Quote a CSV cell when it holds the separator, a quote or a line break
+fn csv_cell(value: &str, sep: &str) -> String {
+    if value.contains(sep) || value.contains('"') || value.contains('\n') { format!("\"{}\"", value.replace('"', "\"\"")) } else { value.to_string() }
+}
+
+/// `--prescreen <keep>[,<density>]`: how many jobs get the full calculation, and the coarse dot
+/// density (default 2 dots/A^2) they are ranked at
+fn parse_prescreen(value: &str) -> anyhow::Result<(usize, f64)> {
+    let (keep, density) = value.split_once(',').unwrap_or((value, "2"));
+    let keep = keep.trim().parse::<usize>().map_err(|e| fail(Failure::Usage, format!("invalid --prescreen count '{keep}': {e}")))?;
+    let density = density.trim().parse::<f64>().map_err(|e| fail(Failure::Usage, format!("invalid --prescreen density '{density}': {e}")))?;
+    Ok((keep, density))
+}
+
+/// `sc batch`: run every job of a manifest, writing one record per job as it finishes. Failed jobs
+/// are recorded with their error class and do not stop the batch; the exit code is 1 if any failed.
+/// With `--prescreen`, every job is first estimated (contacts, coarse Sc) and only the best ones
+/// get the full calculation; records are then written in manifest order once the ranking is done.
+fn run_batch(args: &[String]) -> anyhow::Result<()> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let Some(manifest) = args.first().filter(|a| !a.starts_with("--")) else {
+        return Err(fail(Failure::Usage, "usage: sc batch <manifest.csv|manifest.jsonl> [--config <sc.toml>] [--output <path>] [--format jsonl|csv|tsv] [--threads <n>] [--no-parallel] [--prescreen <keep>[,<density>]] [--quiet]"));
+    };
+    let prescreen = flag("--prescreen").map(|v| parse_prescreen(v)).transpose()?;
+    let config = flag("--config").cloned().or_else(|| env::var("SC_CONFIG").ok()).as_deref().map(read_config).transpose()?.unwrap_or_default();
+    let mut base = Settings::default();
+    if let Some(path) = config.radii.path { base.radii_source = Some(RadiiSource::Path(path)); }
+    config.settings.apply(&mut base)?;
+    if let Some(n) = flag("--threads").map(|v| v.parse::<usize>()).transpose()? { base.num_threads = Some(n); }
+    if args.iter().any(|a| a == "--no-parallel") { base.enable_parallel = false; }
+    let quiet = args.iter().any(|a| a == "--quiet" || a == "-q") || config.output.quiet;
+    let output_path = flag("--output").cloned().or(config.output.path);
+    let format = flag("--format").cloned().or(config.output.format)
+        .or_else(|| output_path.as_deref().and_then(|p| p.rsplit_once('.')).map(|(_, ext)| ext.to_string()))
+        .unwrap_or_else(|| "jsonl".to_string()).to_ascii_lowercase();
+    let sep = match format.as_str() {
+        "jsonl" | "ndjson" | "json" => None,
+        "csv" => Some(","),
+        "tsv" => Some("\t"),
+        _ => return Err(fail(Failure::Usage, format!("unknown batch format '{format}' (jsonl, csv, tsv)"))),
+    };
+    let jobs = read_manifest(manifest)?;
+
+    let mut dest: Box<dyn Write> = match &output_path {
+        Some(path) => Box::new(File::create(path).map_err(|e| fail(Failure::Output, format!("cannot create {path}: {e}")))?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut header = vec!["id", "file", "selection1", "selection2", "sc", "median_distance", "trimmed_area", "buried_sasa", "gap_volume", "gap_index", "atoms_mol1", "atoms_mol2"];
+    if prescreen.is_some() { header.extend(["contacts", "prescreen_sc", "selected"]); }
+    header.extend(["elapsed_ms", "error", "message"]);
+    if let Some(sep) = sep { writeln!(dest, "{}", header.join(sep))?; }
+    let bar = (!quiet).then(|| indicatif::ProgressBar::new(jobs.len() as u64)
+        .with_style(indicatif::ProgressStyle::with_template("jobs [{bar:40}] {pos}/{len} ({elapsed}, eta {eta})").expect("valid template").progress_chars("=> ")));
     let mut sc = ScCalculator::new();
-    if no_parallel { sc.settings_mut().enable_parallel = false; }
-    // Defaults already set; keep them
-    for (pos, atom_name, res_name, _chain) in mol1.iter() {
-        let mut a = Atom::new();
-        a.coor = *pos;
-        a.atom = atom_name.clone();
-        a.residue = res_name.clone();
-        sc.add_atom(0, a)?;
-    }
-    for (pos, atom_name, res_name, _chain) in mol2.iter() {
-        let mut a = Atom::new();
-        a.coor = *pos;
-        a.atom = atom_name.clone();
-        a.residue = res_name.clone();
-        sc.add_atom(1, a)?;
+    // Pre-screen pass: an estimate (or the error) and its time per job, and the jobs ranked best
+    let mut screened: Vec<Option<(anyhow::Result<Prescreen>, u128)>> = jobs.iter().map(|_| None).collect();
+    let mut selected = vec![prescreen.is_none(); jobs.len()];
+    if let Some((keep, density)) = prescreen {
+        for (n, job) in jobs.iter().enumerate() {
+            let t0 = std::time::Instant::now();
+            let estimate = load_job(&mut sc, &base, job).and_then(|_| Ok(sc.prescreen(density)?));
+            screened[n] = Some((estimate, t0.elapsed().as_millis()));
+            if let Some(bar) = &bar { bar.inc(1); }
+        }
+        let estimates: Vec<Prescreen> = screened.iter().map(|e| match e { Some((Ok(p), _)) => p.clone(), _ => Prescreen::default() }).collect();
+        let best = Prescreen::select(&estimates, keep);
+        for &i in &best { selected[i] = true; }
+        if let Some(bar) = &bar { bar.set_length((jobs.len() + best.len()) as u64); }
+    }
+    let mut failed = 0;
+    for (n, job) in jobs.iter().enumerate() {
+        let t0 = std::time::Instant::now();
+        let mut record = JobRecord {
+            id: job.id.clone().unwrap_or_else(|| (n + 1).to_string()), file: job.file.clone(), selection1: job.selection1.clone(), selection2: job.selection2.clone(),
+            sc: None, median_distance: None, trimmed_area: None, buried_sasa: None, gap_volume: None, gap_index: None, atoms_mol1: None, atoms_mol2: None,
+            contacts: None, prescreen_sc: None, selected: prescreen.map(|_| selected[n]), elapsed_ms: 0, error: None, message: None,
+        };
+        let mut screen_ms = 0;
+        let result = match screened[n].take() {
+            Some((Err(e), ms)) => { screen_ms = ms; Some(Err(e)) }
+            Some((Ok(estimate), ms)) => {
+                (screen_ms, record.contacts, record.prescreen_sc) = (ms, Some(estimate.contacts), estimate.sc);
+                selected[n].then(|| run_job(&mut sc, &base, job))
+            }
+            None => Some(run_job(&mut sc, &base, job)),
+        };
+        record.elapsed_ms = screen_ms + t0.elapsed().as_millis();
+        match result {
+            None => {}
+            Some(Ok(r)) => {
+                (record.sc, record.median_distance, record.trimmed_area, record.buried_sasa) = (Some(r.sc), Some(r.distance), Some(r.area), Some(r.bsa));
+                (record.gap_volume, record.gap_index) = (Some(r.gap_volume), Some(r.gap_index));
+                (record.atoms_mol1, record.atoms_mol2) = (Some(r.surfaces[0].n_atoms), Some(r.surfaces[1].n_atoms));
+            }
+            Some(Err(e)) => {
+                failed += 1;
+                (record.error, record.message) = (Some(Failure::of(&e)), Some(format!("{e:#}")));
+            }
+        }
+        let write = match sep {
+            None => writeln!(dest, "{}", serde_json::to_string(&record)?),
+            Some(sep) => {
+                let num = |v: Option<f64>| v.map(|x| format!("{x:.3}")).unwrap_or_default();
+                let count = |v: Option<usize>| v.map(|x| x.to_string()).unwrap_or_default();
+                let error = record.error.and_then(|f| serde_json::to_value(f).ok()).and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+                let mut cells = vec![record.id.clone(), record.file.clone(), record.selection1.clone(), record.selection2.clone(),
+                    num(record.sc), num(record.median_distance), num(record.trimmed_area), num(record.buried_sasa), num(record.gap_volume), num(record.gap_index),
+                    count(record.atoms_mol1), count(record.atoms_mol2)];
+                if prescreen.is_some() { cells.extend([count(record.contacts), num(record.prescreen_sc), record.selected.map(|b| b.to_string()).unwrap_or_default()]); }
+                cells.extend([record.elapsed_ms.to_string(), error, record.message.clone().unwrap_or_default()]);
+                writeln!(dest, "{}", cells.iter().map(|c| csv_cell(c, sep)).collect::<Vec<_>>().join(sep))
+            }
+        };
+        // Flushed per job so finished records survive an interrupted batch
+        write.and_then(|_| dest.flush()).map_err(|e| fail(Failure::Output, format!("cannot write results: {e}")))?;
+        if let Some(bar) = &bar { if selected[n] { bar.inc(1); } }
     }
+    if let Some(bar) = &bar { bar.finish_and_clear(); }
+    if failed > 0 { return Err(fail(Failure::Other, format!("{failed} of {} jobs failed", jobs.len()))); }
+    Ok(())
+}
+
+/// Values read from a reference log, unset when the log does not report them
+#[derive(Default, serde::Serialize)]
+struct Reference {
+    source: &'static str,
+    sc: Option<f64>,
+    median_distance: Option<f64>,
+    trimmed_area: Option<f64>,
+    atoms: [Option<f64>; 2],
+    dots: [Option<f64>; 2],
+    trimmed_dots: [Option<f64>; 2],
+}
+
+/// Parse a CCP4 `sc` log or a Rosetta ShapeComplementarityCalculator report. Both print labelled
+/// rows followed by the molecule 1 and 2 values and, in Rosetta's table, the total or average;
+/// CCP4 may instead print one section per molecule. Tracer prefixes
+/// (`core.scoring.sc.ShapeComplementarityCalculator:`) and `=`/`:` separators are ignored.
+fn read_reference(path: &str) -> anyhow::Result<Reference> {
+    let text = std::fs::read_to_string(path).map_err(|e| fail(Failure::Parse, format!("cannot read reference {path}: {e}")))?;
+    let mut r = Reference {
+        source: if text.contains("ShapeComplementarityCalculator") { "rosetta" } else if text.to_ascii_uppercase().contains("CCP4") { "ccp4" } else { "log" },
+        ..Default::default()
+    };
+    // Molecule of the current CCP4 per-molecule section
+    let mut section: Option<usize> = None;
+    for line in text.lines() {
+        let mut l = line.trim();
+        if let Some((head, rest)) = l.split_once(':') { if !head.contains(' ') && head.contains('.') { l = rest.trim(); } }
+        let tokens: Vec<&str> = l.split_whitespace().filter(|t| !matches!(*t, "=" | ":")).collect();
+        let n_label = tokens.iter().position(|t| t.parse::<f64>().is_ok()).unwrap_or(tokens.len());
+        let values: Vec<f64> = tokens[n_label..].iter().map_while(|t| t.parse().ok()).collect();
+        let label = tokens[..n_label].join(" ").to_ascii_lowercase();
+        let label = label.trim_end_matches([':', '=']).trim();
+        if values.is_empty() { continue; }
+        if label.ends_with("molecule") && values.len() == 1 { section = Some((values[0] as usize).clamp(1, 2) - 1); continue; }
+        // Per-molecule values, or one value for the current section
+        let per_molecule = |slot: &mut [Option<f64>; 2]| match (values.len(), section) {
+            (1, Some(m)) => slot[m] = Some(values[0]),
+            (1, None) => {}
+            _ => *slot = [Some(values[0]), Some(values[1])],
+        };
+        // Combined value: the total/average column, else the molecules summed or averaged
+        let combined = |sum: bool| match values.len() {
+            1 => values[0],
+            2 => if sum { values[0] + values[1] } else { (values[0] + values[1]) / 2.0 },
+            _ => values[2],
+        };
+        // A lone value inside a per-molecule section belongs to that molecule, except the closing statistic
+        let scalar = values.len() > 1 || section.is_none();
+        match label {
+            l if l.starts_with("shape complementarity statistic") => r.sc = Some(combined(false)),
+            l if scalar && (l == "sc" || l.starts_with("shape complementarity") || (l.ends_with(" sc") && !l.contains("mean"))) => r.sc = Some(combined(false)),
+            l if scalar && l.starts_with("median dist") => r.median_distance = Some(combined(false)),
+            l if scalar && l.starts_with("trimmed area") => r.trimmed_area = Some(combined(true)),
+            l if l.starts_with("trimmed dots") || l.ends_with("trimmed dots") => per_molecule(&mut r.trimmed_dots),
+            "atoms" | "number of atoms" => per_molecule(&mut r.atoms),
+            "dots" | "surface dots" | "all dots" | "number of dots" => per_molecule(&mut r.dots),
+            _ => {}
+        }
+    }
+    if r.sc.is_none() { return Err(fail(Failure::Parse, format!("no Sc value found in reference {path}"))); }
+    Ok(r)
+}
+
+/// Dot positions from a PDB-style file (ATOM/HETATM records) or whitespace-separated `x y z` rows
+fn read_dot_positions(path: &str) -> anyhow::Result<Vec<Vec3>> {
+    let text = std::fs::read_to_string(path).map_err(|e| fail(Failure::Parse, format!("cannot read dots {path}: {e}")))?;
+    Ok(text.lines().filter_map(|l| {
+        if l.starts_with("ATOM") || l.starts_with("HETATM") {
+            let coord = |r: std::ops::Range<usize>| l.get(r).and_then(|v| v.trim().parse::<f64>().ok());
+            return Some(Vec3::new(coord(30..38)?, coord(38..46)?, coord(46..54)?));
+        }
+        let mut xyz = l.split_whitespace().map(|t| t.parse::<f64>());
+        match (xyz.next(), xyz.next(), xyz.next()) {
+            (Some(Ok(x)), Some(Ok(y)), Some(Ok(z))) => Some(Vec3::new(x, y, z)),
+            _ => None,
+        }
+    }).collect())
+}
+
+/// Distance from each query to the nearest point, searched in a hash grid of `cell` edges out to
+/// one cell (infinite when nothing lies that close)
+fn nearest_distances(points: &[Vec3], queries: &[Vec3], cell: f64) -> Vec<f64> {
+    let key = |p: Vec3| ((p.x / cell).floor() as i64, (p.y / cell).floor() as i64, (p.z / cell).floor() as i64);
+    let mut grid: std::collections::HashMap<(i64, i64, i64), Vec<Vec3>> = std::collections::HashMap::new();
+    for &p in points { grid.entry(key(p)).or_default().push(p); }
+    queries.iter().map(|&q| {
+        let (cx, cy, cz) = key(q);
+        let mut best = f64::INFINITY;
+        for dx in -1..=1 { for dy in -1..=1 { for dz in -1..=1 {
+            for &p in grid.get(&(cx + dx, cy + dy, cz + dz)).map(Vec::as_slice).unwrap_or_default() { best = best.min(q.distance(p)); }
+        }}}
+        best
+    }).collect()
+}
+
+#[derive(serde::Serialize)]
+struct Comparison {
+    metric: String,
+    ours: f64,
+    reference: f64,
+    delta: f64,
+    ok: bool,
+}
+
+#[derive(serde::Serialize)]
+struct DotComparison {
+    ours: usize,
+    reference: usize,
+    /// Reference dots with one of ours within `tolerance`
+    matched: usize,
+    tolerance: f64,
+    median_distance: f64,
+    ok: bool,
+}
+
+#[derive(serde::Serialize)]
+struct ValidationReport {
+    source: &'static str,
+    comparisons: Vec<Comparison>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dots: Option<DotComparison>,
+    ok: bool,
+}
+
+/// `sc validate`: run the calculation and compare Sc, distances, areas and dot counts (and, with
+/// `--dots`, dot positions) with a CCP4 `sc` or Rosetta reference. Sc must agree within
+/// `--tolerance` (absolute, default 0.01), the other values within `--rel-tolerance` (default 2%).
+fn run_validate(args: &[String]) -> anyhow::Result<()> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let positional = positionals(args, &["--reference", "--dots", "--tolerance", "--rel-tolerance", "--dot-tolerance", "--config"]);
+    let (Some(pdb), Some(chain1), Some(chain2), Some(reference)) = (positional.first(), positional.get(1), positional.get(2), flag("--reference")) else {
+        return Err(fail(Failure::Usage, "usage: sc validate <pdb_or_pqr_file> <chain1> <chain2> --reference <log> [--dots <file>] [--all-dots] [--tolerance <sc>] [--rel-tolerance <f>] [--dot-tolerance <A>] [--config <sc.toml>] [--json]"));
+    };
+    let tolerance = flag("--tolerance").map(|v| v.parse::<f64>()).transpose()?.unwrap_or(0.01);
+    let rel_tolerance = flag("--rel-tolerance").map(|v| v.parse::<f64>()).transpose()?.unwrap_or(0.02);
+    let dot_tolerance = flag("--dot-tolerance").map(|v| v.parse::<f64>()).transpose()?.unwrap_or(0.5);
+    let config = flag("--config").cloned().or_else(|| env::var("SC_CONFIG").ok()).as_deref().map(read_config).transpose()?.unwrap_or_default();
+    let reference_values = read_reference(reference)?;
+
+    let (mol1, mol2) = read_structure_atoms_from_path(pdb, chain1, chain2).map_err(|e| fail(Failure::Parse, format!("cannot read {pdb}: {e}")))?;
+    for (atoms, selection) in [(&mol1, *chain1), (&mol2, *chain2)] {
+        if atoms.is_empty() { return Err(SurfaceCalculatorError::EmptySelection { selection: selection.clone(), path: (*pdb).clone() }.into()); }
+    }
+    let mut sc = ScCalculator::new();
+    if let Some(path) = config.radii.path { sc.settings_mut().radii_source = Some(RadiiSource::Path(path)); }
+    settings_from_flags(args, config.settings)?.apply(sc.settings_mut())?;
+    let results = sc.calc_atoms(&mol1, &mol2)?;
+
+    let mut comparisons = Vec::new();
+    let mut compare = |metric: &str, ours: f64, reference: Option<f64>, absolute: bool| {
+        let Some(reference) = reference else { return };
+        let delta = ours - reference;
+        let ok = if absolute { delta.abs() <= tolerance } else { delta.abs() <= rel_tolerance * reference.abs().max(f64::EPSILON) };
+        comparisons.push(Comparison { metric: metric.to_string(), ours, reference, delta, ok });
+    };
+    let r = &reference_values;
+    compare("sc", results.sc, r.sc, true);
+    compare("median_distance", results.distance, r.median_distance, false);
+    compare("trimmed_area", results.area, r.trimmed_area, false);
+    for m in 0..2 {
+        let surface = &results.surfaces[m];
+        compare(&format!("atoms_mol{}", m + 1), surface.n_atoms as f64, r.atoms[m], false);
+        compare(&format!("dots_mol{}", m + 1), surface.n_all_dots as f64, r.dots[m], false);
+        compare(&format!("trimmed_dots_mol{}", m + 1), surface.n_trimmed_dots as f64, r.trimmed_dots[m], false);
+    }
+    let dots = flag("--dots").map(|path| -> anyhow::Result<DotComparison> {
+        let reference = read_dot_positions(path)?;
+        let ours: Vec<Vec3> = if args.iter().any(|a| a == "--all-dots") {
+            (0..2).flat_map(|m| sc.base.dots(m).iter().map(|d| d.coor)).collect()
+        } else {
+            (0..2).flat_map(|m| sc.dot_pairs(m).iter().map(move |p| p.dot).map(move |i| (m, i))).map(|(m, i)| sc.base.dots(m)[i].coor).collect()
+        };
+        let mut nearest = nearest_distances(&ours, &reference, dot_tolerance.max(1.0));
+        let matched = nearest.iter().filter(|&&d| d <= dot_tolerance).count();
+        nearest.sort_by(f64::total_cmp);
+        let median_distance = nearest.get(nearest.len() / 2).copied().unwrap_or(0.0);
+        let ok = matched as f64 >= (1.0 - rel_tolerance) * reference.len() as f64;
+        Ok(DotComparison { ours: ours.len(), reference: reference.len(), matched, tolerance: dot_tolerance, median_distance, ok })
+    }).transpose()?;
+    let ok = comparisons.iter().all(|c| c.ok) && dots.as_ref().is_none_or(|d| d.ok);
+    let report = ValidationReport { source: reference_values.source, comparisons, dots, ok };
+
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("Reference: {reference} ({})", report.source);
+        println!("{:<18} {:>12} {:>12} {:>10}  ", "metric", "sc-rs", "reference", "delta");
+        for c in &report.comparisons {
+            println!("{:<18} {:>12.3} {:>12.3} {:>+10.3}  {}", c.metric, c.ours, c.reference, c.delta, if c.ok { "ok" } else { "MISMATCH" });
+        }
+        if let Some(d) = &report.dots {
+            println!("Dots: {} of {} reference dots within {:.2} A of ours ({} ours), median nearest {:.3} A  {}",
+                d.matched, d.reference, d.tolerance, d.ours, d.median_distance, if d.ok { "ok" } else { "MISMATCH" });
+        }
+    }
+    if !report.ok { return Err(fail(Failure::Mismatch, "values outside tolerance")); }
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct DeterminismRow {
+    metric: String,
+    parallel: f64,
+    serial: f64,
+    delta: f64,
+    ok: bool,
+}
+
+#[derive(serde::Serialize)]
+struct DeterminismReport {
+    tolerance: f64,
+    rows: Vec<DeterminismRow>,
+    ok: bool,
+}
+
+/// `sc selfcheck`: run one calculation in parallel and serially and report every value that differs
+/// by more than `--tolerance` (absolute, default 1e-9). Counts must match exactly.
+fn run_selfcheck(args: &[String]) -> anyhow::Result<()> {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let positional = positionals(args, &["--tolerance", "--config"]);
+    let (Some(pdb), Some(chain1), Some(chain2)) = (positional.first(), positional.get(1), positional.get(2)) else {
+        return Err(fail(Failure::Usage, "usage: sc selfcheck <pdb_or_pqr_file> <chain1> <chain2> [--tolerance <d>] [--threads <n>] [--config <sc.toml>] [--json]"));
+    };
+    let tolerance = flag("--tolerance").map(|v| v.parse::<f64>()).transpose()?.unwrap_or(1e-9);
+    let config = flag("--config").cloned().or_else(|| env::var("SC_CONFIG").ok()).as_deref().map(read_config).transpose()?.unwrap_or_default();
+    let (mol1, mol2) = read_structure_atoms_from_path(pdb, chain1, chain2).map_err(|e| fail(Failure::Parse, format!("cannot read {pdb}: {e}")))?;
+    for (atoms, selection) in [(&mol1, *chain1), (&mol2, *chain2)] {
+        if atoms.is_empty() { return Err(SurfaceCalculatorError::EmptySelection { selection: selection.clone(), path: (*pdb).clone() }.into()); }
+    }
+    let mut base = Settings::default();
+    if let Some(path) = config.radii.path { base.radii_source = Some(RadiiSource::Path(path)); }
+    settings_from_flags(args, config.settings)?.apply(&mut base)?;
+    let [parallel, serial] = [true, false].map(|enabled| -> anyhow::Result<Results> {
+        let mut sc = ScCalculator::new();
+        *sc.settings_mut() = Settings { enable_parallel: enabled, ..base.clone() };
+        Ok(sc.calc_atoms(&mol1, &mol2)?)
+    });
+    let (parallel, serial) = (parallel?, serial?);
+
+    let mut rows = Vec::new();
+    let mut compare = |metric: String, p: f64, s: f64, exact: bool| {
+        let delta = p - s;
+        // NaN compares equal to NaN: both runs failed to pair in the same way
+        let ok = (p.is_nan() && s.is_nan()) || if exact { delta == 0.0 } else { delta.abs() <= tolerance };
+        rows.push(DeterminismRow { metric, parallel: p, serial: s, delta, ok });
+    };
+    compare("sc".into(), parallel.sc, serial.sc, false);
+    for (st_p, st_s) in parallel.statistics.iter().zip(&serial.statistics).skip(1) { compare(format!("sc ({:?})", st_p.statistic), st_p.sc, st_s.sc, false); }
+    compare("median_distance".into(), parallel.distance, serial.distance, false);
+    compare("trimmed_area".into(), parallel.area, serial.area, false);
+    compare("buried_sasa".into(), parallel.bsa, serial.bsa, false);
+    compare("gap_volume".into(), parallel.gap_volume, serial.gap_volume, false);
+    for m in 0..2 {
+        let (p, s) = (&parallel.surfaces[m], &serial.surfaces[m]);
+        compare(format!("trimmed_area_mol{}", m + 1), p.trimmed_area, s.trimmed_area, false);
+        compare(format!("dots_mol{}", m + 1), p.n_all_dots as f64, s.n_all_dots as f64, true);
+        compare(format!("trimmed_dots_mol{}", m + 1), p.n_trimmed_dots as f64, s.n_trimmed_dots as f64, true);
+        compare(format!("buried_atoms_mol{}", m + 1), p.n_buried_atoms as f64, s.n_buried_atoms as f64, true);
+    }
+    let ok = rows.iter().all(|r| r.ok);
+    let report = DeterminismReport { tolerance, rows, ok };
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{:<20} {:>14} {:>14} {:>12}", "metric", "parallel", "serial", "delta");
+        for r in &report.rows {
+            println!("{:<20} {:>14.6} {:>14.6} {:>12.3e}  {}", r.metric, r.parallel, r.serial, r.delta, if r.ok { "ok" } else { "DIFFERS" });
+        }
+        println!("{}", if report.ok { "Parallel and serial runs agree" } else { "Parallel and serial runs differ" });
+    }
+    if !report.ok { return Err(fail(Failure::Mismatch, format!("parallel and serial runs differ beyond {tolerance:e}"))); }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+    let Err(error) = run(&args) else { return ExitCode::SUCCESS };
+    // A closed stdout (`sc ... | head`) is not a failure
+    if error.chain().any(|c| c.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe)) { return ExitCode::SUCCESS; }
+    let failure = Failure::of(&error);
+    if args.iter().any(|a| a == "--json-errors") {
+        let report = ErrorReport { error: failure, exit_code: failure.exit_code(), message: format!("{error:#}") };
+        eprintln!("{}", serde_json::to_string(&report).unwrap_or_default());
+    } else {
+        eprintln!("Error: {error:#}");
+    }
+    ExitCode::from(failure.exit_code())
+}
+
+fn run(args: &[String]) -> anyhow::Result<()> {
+    if args.get(1).is_some_and(|a| a == "bench") { return run_bench(&args[2..]); }
+    if args.get(1).is_some_and(|a| a == "chains") { return run_chains(&args[2..]); }
+    if args.get(1).is_some_and(|a| a == "pockets") { return run_pockets(&args[2..]); }
+    if args.get(1).is_some_and(|a| a == "batch") { return run_batch(&args[2..]); }
+    if args.get(1).is_some_and(|a| a == "validate") { return run_validate(&args[2..]); }
+    if args.get(1).is_some_and(|a| a == "selfcheck") { return run_selfcheck(&args[2..]); }
+    let config_path = args.iter().position(|a| a == "--config").and_then(|i| args.get(i + 1)).cloned().or_else(|| env::var("SC_CONFIG").ok());
+    let config = config_path.as_deref().map(read_config).transpose()?.unwrap_or_default();
+    // Chains may come from the config's [selection]
+    let positional = |i: usize| args.get(i).filter(|a| !a.starts_with("--"));
+    let chain1 = positional(2).cloned().or(config.selection.chain1);
+    let chain2 = positional(3).filter(|_| positional(2).is_some()).cloned().or(config.selection.chain2);
+    let (Some(pdb), Some(chain1), Some(chain2)) = (positional(1), chain1, chain2) else {
+        eprintln!("Usage: sc <pdb_or_pqr_file> <chain1> <chain2> [--config <sc.toml>] [--json] [--output <path>] [--format json|csv|tsv|yaml|text] [--quiet|-v|-vv] [--json-errors] [--no-parallel] [--contacts <file.csv|file.json>] [--residues <file.csv|file.json>] [--bfactor-pdb <file.pdb>] [--bfactor-value sc|area] [--zernike <file.csv|file.json>] [--zernike-order <n>] [--sweep-w <w,..>] [--sweep-band <d,..>] [--probe-radii <A,..>] [--densities <dots/A^2,..>] [--converge <tol>] [--regions <file>] [--weights <file>] [--pairs <file.csv|file.json>] [--dot-distances <file.csv|file.tsv|file.json|file.pdb>] [--dot-kinds <kind>[,<kind>..]] [--residue-types hydrophobic|aromatic|<RES>[,<RES>..]] [--density-report [<fraction>]] [--profile <width>[,<step>]] [--statistic <s>[,<s>..]] [--trim-distance <d>] [--antiparallel-tolerance <deg>] [--clash-tolerance <A>] [--homodimer] [--volume] [--threads <n>] [--streaming] [--waters [<cutoff>]] [--lenient] [--drop-duplicates] [--probe-radius <A>] [--density <dots/A^2>] [--band <A>] [--sep-cutoff <A>|--auto-sep-cutoff] [--gaussian-w <w>] [--focus <x,y,z|chain:start-end,..>] [--focus-radius <A>] [--mask <chain:start-end,..>] [--mask-occludes] [--atom-subset all|backbone|sidechain[,<subset2>]] [--ca-only] [--martini] [--context <chain>[,<chain>..]]");
+        eprintln!("       sc bench [--sizes <atoms per molecule,..>] [--repeat <k>] [--threads <n>] [--no-parallel] [--json]");
+        eprintln!("       sc chains <pdb_or_pqr_file> [--cutoff <A>] [--json]");
+        eprintln!("       sc pockets <pdb_or_pqr_file> [<chains>] [--probe-radius <A>] [--min-buriedness <f>] [--link <A>] [--min-probes <n>] [--json]");
+        eprintln!("       sc batch <manifest.csv|manifest.jsonl> [--config <sc.toml>] [--output <path>] [--format jsonl|csv|tsv] [--threads <n>] [--no-parallel] [--prescreen <keep>[,<density>]] [--quiet]");
+        eprintln!("       sc validate <pdb_or_pqr_file> <chain1> <chain2> --reference <log> [--dots <file>] [--all-dots] [--tolerance <sc>] [--rel-tolerance <f>] [--dot-tolerance <A>] [--config <sc.toml>] [--json]");
+        eprintln!("       sc selfcheck <pdb_or_pqr_file> <chain1> <chain2> [--tolerance <d>] [--threads <n>] [--config <sc.toml>] [--json]");
+        return Err(fail(Failure::Usage, "missing structure file or chain selections"));
+    };
+    let (cfg, out_cfg) = (settings_from_flags(args, config.settings)?, config.output);
+    let json = args.iter().any(|a| a == "--json") || out_cfg.json;
+    // 0 with --quiet (results only), 1 by default (progress bar, warnings), 2 with -v (run summary
+    // on stderr), 3 with -vv (each phase logged as it starts)
+    let verbosity = if args.iter().any(|a| a == "--quiet" || a == "-q") || out_cfg.quiet { 0 } else {
+        1 + args.iter().map(|a| match a.as_str() { "-v" | "--verbose" => 1, "-vv" => 2, _ => 0 }).sum::<usize>().min(2)
+    };
+    let output_path = args.iter().position(|a| a == "--output").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.path);
+    // An explicit format wins, then --json, then the --output extension
+    let format = match args.iter().position(|a| a == "--format").and_then(|i| args.get(i + 1)).or(out_cfg.format.as_ref()) {
+        Some(f) => parse_format(f)?,
+        None if json => Format::Json,
+        None => output_path.as_deref().and_then(|p| p.rsplit_once('.')).and_then(|(_, ext)| parse_format(ext).ok()).unwrap_or(Format::Text),
+    };
+    // Optional H-bond cutoff may follow --waters; default 3.5 A
+    let water_cutoff = args.iter().position(|a| a == "--waters")
+        .map(|i| args.get(i + 1).and_then(|v| v.parse::<f64>().ok()).unwrap_or(3.5));
+    // Optional fraction of the target density below which atoms are flagged; default 0.8
+    let density_fraction = args.iter().position(|a| a == "--density-report")
+        .map(|i| args.get(i + 1).and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.8));
+    let contacts_path = args.iter().position(|a| a == "--contacts").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.contacts);
+    let pairs_path = args.iter().position(|a| a == "--pairs").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.pairs);
+    let dot_distances_path = args.iter().position(|a| a == "--dot-distances").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.dot_distances);
+    let residues_path = args.iter().position(|a| a == "--residues").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.residues);
+    let bfactor_path = args.iter().position(|a| a == "--bfactor-pdb").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.bfactor_pdb);
+    let bfactor_value = args.iter().position(|a| a == "--bfactor-value").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.bfactor_value).unwrap_or_else(|| "sc".to_string());
+    if !matches!(bfactor_value.as_str(), "sc" | "area") { return Err(fail(Failure::Usage, format!("unknown --bfactor-value '{bfactor_value}' (expected sc or area)"))); }
+    let zernike_path = args.iter().position(|a| a == "--zernike").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.zernike);
+    let zernike_order = args.iter().position(|a| a == "--zernike-order").and_then(|i| args.get(i + 1)).map(|v| v.parse::<usize>()).transpose()?.or(out_cfg.zernike_order).unwrap_or(DEFAULT_ZERNIKE_ORDER);
+    if bfactor_path.is_some() && pdb.to_ascii_lowercase().ends_with(".pqr") { return Err(fail(Failure::Usage, "--bfactor-pdb needs PDB input; PQR files have no B-factor column")); }
+    let sweep_w = args.iter().position(|a| a == "--sweep-w").and_then(|i| args.get(i + 1)).map(|v| parse_list(v)).transpose()?;
+    let regions = args.iter().position(|a| a == "--regions").and_then(|i| args.get(i + 1))
+        .map(|p| read_regions_from_path(p).map_err(|e| fail(Failure::Parse, format!("cannot read regions {p}: {e}")))).transpose()?;
+    let atom_weights = args.iter().position(|a| a == "--weights").and_then(|i| args.get(i + 1))
+        .map(|p| read_weights_from_path(p).map_err(|e| fail(Failure::Parse, format!("cannot read weights {p}: {e}")))).transpose()?;
+    let profile_args = args.iter().position(|a| a == "--profile").and_then(|i| args.get(i + 1)).map(|v| parse_list(v)).transpose()?;
+    let converge_tol = args.iter().position(|a| a == "--converge").and_then(|i| args.get(i + 1)).map(|v| v.parse::<f64>()).transpose()?;
+    let sweep_band = args.iter().position(|a| a == "--sweep-band").and_then(|i| args.get(i + 1)).map(|v| parse_list(v)).transpose()?;
+    let probe_radii = args.iter().position(|a| a == "--probe-radii").and_then(|i| args.get(i + 1)).map(|v| parse_list(v)).transpose()?;
+    let densities = args.iter().position(|a| a == "--densities").and_then(|i| args.get(i + 1)).map(|v| parse_list(v)).transpose()?;
+    let dot_kinds = args.iter().position(|a| a == "--dot-kinds").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.dot_kinds).map(|v| parse_dot_kinds(&v)).transpose()?;
+    let residue_types = args.iter().position(|a| a == "--residue-types").and_then(|i| args.get(i + 1)).cloned().or(out_cfg.residue_types).map(|v| parse_residue_types(&v));
+
+    let (mol1, mol2, read_warnings) = read_structure_atoms_with_warnings_from_path(pdb, &chain1, &chain2).map_err(|e| fail(Failure::Parse, format!("cannot read {pdb}: {e}")))?;
+    for (atoms, selection) in [(&mol1, &chain1), (&mol2, &chain2)] {
+        if atoms.is_empty() { return Err(SurfaceCalculatorError::EmptySelection { selection: selection.clone(), path: pdb.clone() }.into()); }
+    }
+    // Chains that occlude both surfaces without being scored; chains already selected stay scored
+    let context_chains = args.iter().position(|a| a == "--context").and_then(|i| args.get(i + 1)).cloned();
+    let context = match &context_chains {
+        Some(chains) => {
+            let atoms: Vec<Atom> = read_structure_all_atoms_from_path(pdb).map_err(|e| fail(Failure::Parse, format!("cannot read {pdb}: {e}")))?
+                .into_iter().filter(|a| chain_selected(chains, &a.chain) && !chain_selected(&chain1, &a.chain) && !chain_selected(&chain2, &a.chain)).collect();
+            if atoms.is_empty() { return Err(SurfaceCalculatorError::EmptySelection { selection: chains.clone(), path: pdb.clone() }.into()); }
+            atoms
+        }
+        None => Vec::new(),
+    };
+
+    let mut sc = ScCalculator::new();
+    if let Some(path) = config.radii.path { sc.settings_mut().radii_source = Some(RadiiSource::Path(path)); }
+    cfg.apply(sc.settings_mut())?;
+    // Reject bad parameter values before any work is done
+    sc.settings().validate()?;
+    let bar = (verbosity > 0).then(|| {
+        let (bar, progress) = progress_bar(verbosity > 2);
+        sc.settings_mut().progress = Some(progress);
+        bar
+    });
+    if verbosity > 1 {
+        let st = sc.settings();
+        eprintln!("Read {} + {} atoms from {pdb} (chains {chain1} / {chain2})", mol1.len(), mol2.len());
+        if let Some(chains) = &context_chains { eprintln!("Context: {} occluding atoms (chains {chains})", context.len()); }
+        eprintln!("Settings: probe radius {:.2} A, density {:.1} dots/A^2, band {:.2} A, separation cutoff {:.1} A, w {:.2}, statistic {:?}{}",
+            st.rp, st.dot_density, st.peripheral_band, st.separation_cutoff, st.gaussian_w, st.statistic, if st.enable_parallel { "" } else { ", serial" });
+    }
+    sc.add_warnings(read_warnings);
+    let calpha_model = mol1.iter().chain(&mol2).all(|a| a.atom.trim() == "CA");
+    if calpha_model && verbosity > 0 && !matches!(sc.settings().radii_source, Some(RadiiSource::CalphaOnly)) {
+        eprintln!("Warning: both selections hold CA atoms only; all-atom radii give meaningless surfaces, use --ca-only");
+    }
+    let martini_model = mol1.iter().chain(&mol2).all(|a| a.atom.trim() == "BB" || a.atom.trim().starts_with("SC"));
+    if martini_model && verbosity > 0 && !matches!(sc.settings().radii_source, Some(RadiiSource::Martini)) {
+        eprintln!("Warning: both selections hold MARTINI beads (BB, SC1..) only; all-atom radii do not fit them, use --martini");
+    }
+    let (mol1, mol2, context) = (calpha_filter(mol1, sc.settings()), calpha_filter(mol2, sc.settings()), calpha_filter(context, sc.settings()));
+    sc.add_atoms(0, mol1)?;
+    sc.add_atoms(1, mol2)?;
+    sc.add_context_atoms(context)?;
 
     let t0 = std::time::Instant::now();
-    let results: Results = sc.calc()?;
+    let (results, convergence): (Results, Option<Convergence>) = if let Some(tol) = converge_tol {
+        let conv = sc.calc_converged(tol, 1.5, 6)?;
+        (sc.results().clone(), Some(conv))
+    } else {
+        (sc.calc()?, None)
+    };
+    if let Some(bar) = &bar { bar.finish_and_clear(); }
+    if results.area <= 0.0 { return Err(fail(Failure::NoInterface, "no interface between the selections: no dots remain after trimming")); }
+    if verbosity > 1 {
+        let t = &results.timings;
+        eprintln!("Timings (ms): radii {:.1}, attention {:.1}, neighbors {:.1}, toroidal {:.1}, contact {:.1}, concave {:.1}, burial {:.1}, trimming {:.1}, statistic {:.1}, reports {:.1}",
+            t.radii * 1e3, t.attention * 1e3, t.neighbors * 1e3, t.toroidal * 1e3, t.contact * 1e3, t.concave * 1e3, t.burial * 1e3, t.trimming * 1e3, t.statistic * 1e3, t.reports * 1e3);
+        eprintln!("Dots: {} + {} generated, {} + {} trimmed", results.surfaces[0].n_all_dots, results.surfaces[1].n_all_dots, results.surfaces[0].n_trimmed_dots, results.surfaces[1].n_trimmed_dots);
+        let s = &results.surfaces;
+        eprintln!("Attention (separation cutoff {:.2} A): {} + {} buried, {} + {} far", results.separation_cutoff, s[0].n_buried_atoms, s[1].n_buried_atoms, s[0].n_blocked_atoms, s[1].n_blocked_atoms);
+    }
+    let skipped = &results.skipped_atoms;
+    if !skipped.is_empty() && verbosity > 0 {
+        let names: Vec<String> = skipped.iter().take(10).map(|s| format!("{}:{}{}:{}", s.residue.chain, s.residue.residue, s.residue.resnum, s.atom)).collect();
+        eprintln!("Warning: skipped {} atom(s) without a radius, duplicating another atom or masked: {}{}", skipped.len(), names.join(", "), if skipped.len() > names.len() { ", ..." } else { "" });
+    }
+    let clashes = &results.clashes.clashes;
+    if !clashes.is_empty() && verbosity > 0 {
+        let names: Vec<String> = clashes.iter().take(5).map(|c| format!("{}:{}{}:{}-{}:{}{}:{} ({:.2} A)", c.residue1.chain, c.residue1.residue, c.residue1.resnum, c.atom1, c.residue2.chain, c.residue2.residue, c.residue2.resnum, c.atom2, c.overlap)).collect();
+        eprintln!("Warning: {} atom pair(s) clash across the interface, Sc may not be meaningful: {}{}", clashes.len(), names.join(", "), if clashes.len() > names.len() { ", ..." } else { "" });
+    }
+    // Radius fallbacks and capped arc sampling change the result and are shown by default; records
+    // the reader left out are routine (hydrogens, ligands) and only shown with -v
+    for w in &results.warnings {
+        let affects_result = matches!(w.kind, WarningKind::ElementFallbackRadius | WarningKind::ArcSamplingCapped);
+        if verbosity > 1 || verbosity > 0 && affects_result { eprintln!("Warning: {w}"); }
+    }
+    let water_bridges = match water_cutoff {
+        Some(cutoff) if !pdb.to_ascii_lowercase().ends_with(".pqr") => Some(sc.water_bridges(&read_pdb_waters_from_path(pdb).map_err(|e| fail(Failure::Parse, format!("cannot read waters from {pdb}: {e}")))?, cutoff)),
+        _ => None,
+    };
+    let region_results = regions.map(|r| sc.region_sc(&r));
+    let weighted_sc = atom_weights.map(|w| sc.weighted_sc(&w));
+    let dot_kind_sc = dot_kinds.as_deref().map(|k| sc.dot_kind_sc(k));
+    let residue_type_sc = residue_types.map(|r| sc.residue_type_sc(&r.iter().map(String::as_str).collect::<Vec<_>>()));
+    let dot_density = density_fraction.map(|f| {
+        let report = sc.density_report(f);
+        let undersampled = report.atoms.iter().filter(|a| a.undersampled).map(|a| UndersampledAtom {
+            molecule: a.molecule + 1, atom: sc.base.atoms()[a.atom_index].label(), target: a.target, achieved: a.achieved, n_dots: a.n_dots,
+        }).collect();
+        DensityOutput { min_fraction: f, surfaces: report.surfaces, undersampled }
+    });
+    let profile = profile_args.map(|p| {
+        let width = p.first().copied().unwrap_or(4.0);
+        sc.sc_profile(width, p.get(1).copied().unwrap_or(width / 2.0))
+    });
+    let sweep = if sweep_w.is_some() || sweep_band.is_some() {
+        let ws = sweep_w.unwrap_or_else(|| vec![sc.settings().gaussian_w]);
+        let bands = sweep_band.unwrap_or_else(|| vec![sc.settings().peripheral_band]);
+        Some(sc.sweep(&ws, &bands)?)
+    } else { None };
+    let output_error = |path: &str, e: anyhow::Error| fail(Failure::Output, format!("cannot write {path}: {e}"));
+    if let Some(path) = &contacts_path { write_contacts(path, &results.contacts).map_err(|e| output_error(path, e))?; }
+    if let Some(path) = &pairs_path { write_pairs(path, &sc, dot_kinds.as_deref()).map_err(|e| output_error(path, e))?; }
+    if let Some(path) = &dot_distances_path { write_dot_distances(path, &sc).map_err(|e| output_error(path, e))?; }
+    if let Some(path) = &residues_path { write_interface_residues(path, &results.interface_residues).map_err(|e| output_error(path, e))?; }
+    if let Some(path) = &zernike_path { write_zernike(path, &[0, 1].map(|m| sc.patch_zernike(m, zernike_order))).map_err(|e| output_error(path, e))?; }
+    if let Some(path) = &bfactor_path { write_bfactor_pdb(path, pdb, &results.interface_residues, &bfactor_value).map_err(|e| output_error(path, e))?; }
+    // Last, as each radius or density replaces the surfaces the exports above were written from
+    let probe_radius_scan = probe_radii.map(|radii| sc.probe_radius_scan(&radii)).transpose()?;
+    let density_scan = densities.map(|d| sc.density_scan(&d)).transpose()?;
     let elapsed = t0.elapsed().as_millis();
-    if json {
-        let out = Output { version: env!("CARGO_PKG_VERSION"), sc: results.sc, median_distance: results.distance, trimmed_area: results.area, atoms_mol1: results.surfaces[0].n_atoms, atoms_mol2: results.surfaces[1].n_atoms, elapsed_ms: elapsed };
-        println!("{}", serde_json::to_string_pretty(&out)?);
+    let mut dest: Box<dyn Write> = match &output_path {
+        Some(path) => Box::new(std::io::BufWriter::new(File::create(path).map_err(|e| fail(Failure::Output, format!("cannot create {path}: {e}")))?)),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    if format != Format::Text {
+        let out = Output { version: env!("CARGO_PKG_VERSION"), sc: results.sc, median_distance: results.distance, trimmed_area: results.area, buried_sasa: results.bsa, gap_volume: results.gap_volume, gap_index: results.gap_index, electrostatic_complementarity: results.ec, volumes: results.surfaces[0].volume.zip(results.surfaces[1].volume).map(|(a, b)| [a, b]), atoms_mol1: results.surfaces[0].n_atoms, atoms_mol2: results.surfaces[1].n_atoms, atoms_context: (results.n_context_atoms > 0).then_some(results.n_context_atoms), separation_cutoff: results.separation_cutoff, attention: [0, 1].map(|m| AttentionCounts { buried: results.surfaces[m].n_buried_atoms, far: results.surfaces[m].n_blocked_atoms }), patches: results.patches.clone(), chains: results.chains.clone(), voids: results.voids.clone(), geometry: results.geometry.clone(), curvature: results.curvature.clone(), clashes: results.clashes.clone(), elapsed_ms: elapsed, timings: results.timings.clone(), statistics: results.statistics.clone(), directional: results.directional.clone(), normal_angles: results.normal_angles.clone(), symmetry: results.symmetry.clone(), water_bridges: water_bridges.clone(), sweep, convergence, probe_radius_scan: probe_radius_scan.clone(), density_scan: density_scan.clone(), regions: region_results, weighted_sc: weighted_sc.clone(), profile, dot_kind_sc: dot_kind_sc.clone(), residue_type_sc: residue_type_sc.clone(), dot_density: dot_density.clone(), skipped_atoms: results.skipped_atoms.clone(), warnings: results.warnings.clone() };
+        match format {
+            Format::Yaml => write!(dest, "{}", serde_yaml::to_string(&out)?)?,
+            Format::Csv => write_summary(&mut dest, &out, ",")?,
+            Format::Tsv => write_summary(&mut dest, &out, "\t")?,
+            _ => writeln!(dest, "{}", serde_json::to_string_pretty(&out)?)?,
+        }
     } else {
-        println!("SC: {:.3}", results.sc);
-        println!("Median distance: {:.3}", results.distance);
-        println!("Trimmed area: {:.3}", results.area);
-        println!("Atoms: {} + {}", results.surfaces[0].n_atoms, results.surfaces[1].n_atoms);
-        println!("Elapsed: {} ms", elapsed);
+        writeln!(dest, "SC: {:.3}", results.sc)?;
+        let dir = &results.directional;
+        writeln!(dest, "SC (A->B / B->A): {:.3} / {:.3} (asymmetry {:+.3})", dir.sc_ab, dir.sc_ba, dir.asymmetry)?;
+        for st in results.statistics.iter().skip(1) { writeln!(dest, "SC ({:?}): {:.3}", st.statistic, st.sc)?; }
+        let na = &results.normal_angles;
+        writeln!(dest, "Normal angles: mean {:.1}, median {:.1} deg, {:.1}% within {:.0} deg of antiparallel (orientation {:.3}, distance weight {:.3})",
+            na.mean, na.median, na.fraction_antiparallel * 100.0, sc.settings().antiparallel_tolerance, na.orientation, na.distance_weight)?;
+        if let Some(k) = &dot_kind_sc {
+            let kinds: Vec<String> = k.kinds.iter().map(|k| format!("{k:?}").to_lowercase()).collect();
+            writeln!(dest, "SC ({} dots): {:.3} over {:.3} A^2, {} dots", kinds.join(", "), k.sc, k.area, k.n_dots)?;
+        }
+        if let Some(w) = &weighted_sc {
+            writeln!(dest, "SC (weighted): {:.3} over {:.3} weighted A^2, {} dots", w.sc, w.weighted_area, w.n_dots)?;
+        }
+        if let Some(r) = &residue_type_sc {
+            writeln!(dest, "SC ({}): {:.3} over {:.3} A^2, {} dots", r.residues.join(", "), r.sc, r.area, r.n_dots)?;
+        }
+        writeln!(dest, "Median distance: {:.3}", results.distance)?;
+        writeln!(dest, "Trimmed area: {:.3}", results.area)?;
+        writeln!(dest, "Buried SASA: {:.3}", results.bsa)?;
+        writeln!(dest, "Gap volume: {:.3} (gap index {:.3})", results.gap_volume, results.gap_index)?;
+        if let Some(ec) = results.ec { writeln!(dest, "Electrostatic complementarity: {:.3}", ec)?; }
+        if let (Some(v1), Some(v2)) = (results.surfaces[0].volume, results.surfaces[1].volume) {
+            writeln!(dest, "Solvent-excluded volume: {:.1} + {:.1} A^3", v1, v2)?;
+        }
+        let g = &results.geometry;
+        writeln!(dest, "Interface Rg: {:.3}, planarity: {:.3}, centroid: ({:.3}, {:.3}, {:.3})", g.radius_of_gyration, g.planarity, g.centroid.x, g.centroid.y, g.centroid.z)?;
+        let c = &results.curvature;
+        writeln!(dest, "Curvature complementarity: {:.3} (mismatch {:.3} A^-1, mean curvature {:+.3} / {:+.3} A^-1)", c.complementarity, c.mismatch, c.mean_curvature[0], c.mean_curvature[1])?;
+        let cl = &results.clashes;
+        writeln!(dest, "Clashes: {} atom pairs beyond {:.2} A overlap (overlap volume {:.1} A^3, deepest {:.2} A)", cl.clashes.len(), sc.settings().clash_tolerance, cl.overlap_volume, cl.max_overlap)?;
+        for (mol, patches) in results.patches.iter().enumerate() {
+            let areas: Vec<String> = patches.iter().map(|p| format!("{:.1}", p.area)).collect();
+            writeln!(dest, "Patches mol{}: {} [{}]", mol + 1, patches.len(), areas.join(", "))?;
+        }
+        if let Some(s) = &results.symmetry {
+            writeln!(dest, "Homodimer: {} matched residues ({}), SC deviation {:.3}, area deviation {:.3}, residue area RMSD {:.3}",
+                s.n_matched_residues, if s.equivalent { "same sequence" } else { "matched by number" }, s.sc_deviation, s.area_deviation, s.residue_area_rmsd)?;
+        }
+        let void_volume: f64 = results.voids.iter().map(|v| v.volume).sum();
+        writeln!(dest, "Interfacial voids: {} ({:.1} A^3)", results.voids.len(), void_volume)?;
+        writeln!(dest, "Atoms: {} + {}", results.surfaces[0].n_atoms, results.surfaces[1].n_atoms)?;
+        if results.n_context_atoms > 0 { writeln!(dest, "Context atoms: {}", results.n_context_atoms)?; }
+        if let Some(d) = &dot_density {
+            for (mol, s) in d.surfaces.iter().enumerate() {
+                writeln!(dest, "Dot density mol{}: {:.2} of {:.2} dots/A^2 target, {} of {} atoms below {:.0}%", mol + 1, s.achieved, s.target, s.n_undersampled, s.n_atoms, d.min_fraction * 100.0)?;
+            }
+            for a in &d.undersampled {
+                writeln!(dest, "  mol{} {}: {:.2} of {:.2} dots/A^2 ({} dots)", a.molecule, a.atom, a.achieved, a.target, a.n_dots)?;
+            }
+        }
+        if results.chains.iter().any(|c| c.len() > 1) {
+            writeln!(dest, "{:>4} {:>6} {:>7} {:>7} {:>10} {:>8}", "mol", "chain", "atoms", "buried", "area", "S")?;
+            for (mol, chains) in results.chains.iter().enumerate() {
+                for c in chains {
+                    writeln!(dest, "{:>4} {:>6} {:>7} {:>7} {:>10.3} {:>8.3}", mol + 1, c.chain, c.n_atoms, c.n_buried_atoms, c.trimmed_area, c.s_median)?;
+                }
+            }
+        }
+        writeln!(dest, "Elapsed: {} ms", elapsed)?;
+        if let Some(conv) = &convergence {
+            writeln!(dest, "Density: {:.2} dots/A^2 ({})", conv.density, if conv.converged { "converged" } else { "not converged" })?;
+        }
+        if let Some(bridges) = &water_bridges {
+            writeln!(dest, "Water bridges: {}", bridges.len())?;
+            let label = |r: &ResidueId| format!("{}:{}{}{}", r.chain, r.residue, r.resnum, r.icode.map(String::from).unwrap_or_default());
+            for b in bridges {
+                writeln!(dest, "  {} - {} via {} ({:.2} / {:.2} A)", label(&b.residue1), label(&b.residue2), label(&b.water), b.distance1, b.distance2)?;
+            }
+        }
+        if let Some(rows) = &region_results {
+            writeln!(dest, "{:<12} {:>8} {:>10} {:>10}", "region", "SC", "distance", "area")?;
+            for r in rows {
+                writeln!(dest, "{:<12} {:>8.3} {:>10.3} {:>10.3}", r.name, r.sc, r.distance, r.area)?;
+            }
+        }
+        if let Some(bins) = &profile {
+            writeln!(dest, "{:>10} {:>8} {:>10} {:>8}", "position", "SC", "area", "dots")?;
+            for b in bins {
+                writeln!(dest, "{:>10.2} {:>8.3} {:>10.3} {:>8}", b.position, b.sc, b.area, b.n_dots)?;
+            }
+        }
+        if let Some(points) = &probe_radius_scan {
+            writeln!(dest, "{:>10} {:>8} {:>10} {:>12} {:>12}", "probe", "SC", "distance", "area", "bsa")?;
+            for p in points {
+                writeln!(dest, "{:>10.3} {:>8.3} {:>10.3} {:>12.3} {:>12.3}", p.probe_radius, p.sc, p.distance, p.area, p.bsa)?;
+            }
+        }
+        if let Some(points) = &density_scan {
+            writeln!(dest, "{:>10} {:>8} {:>10} {:>12} {:>8}", "density", "SC", "distance", "area", "dots")?;
+            for p in points {
+                writeln!(dest, "{:>10.2} {:>8.3} {:>10.3} {:>12.3} {:>8}", p.density, p.sc, p.distance, p.area, p.n_dots)?;
+            }
+        }
+        if let Some(points) = &sweep {
+            writeln!(dest, "{:>10} {:>10} {:>8} {:>10} {:>12}", "w", "band", "SC", "distance", "area")?;
+            for p in points {
+                writeln!(dest, "{:>10.3} {:>10.3} {:>8.3} {:>10.3} {:>12.3}", p.gaussian_w, p.peripheral_band, p.sc, p.distance, p.area)?;
+            }
+        }
+    }
+    match dest.flush() {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(fail(Failure::Output, format!("cannot write results: {e}"))),
+        _ => {}
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_csv_round_trips_quoted_cells() {
+        let dir = std::env::temp_dir().join(format!("sc-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("jobs.csv");
+        let row = ["ab", "/tmp/ca.pdb", "A,B", "B", "false"].map(|v| csv_cell(v, ","));
+        std::fs::write(&path, format!("id,file,selection1,selection2,homodimer\n{}\n", row.join(","))).unwrap();
+        let jobs = read_manifest(path.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!((jobs[0].file.as_str(), jobs[0].selection1.as_str(), jobs[0].selection2.as_str()), ("/tmp/ca.pdb", "A,B", "B"));
+        assert_eq!(jobs[0].settings.homodimer, Some(false));
+        assert_eq!(csv_fields(r#"x,"say ""hi""",  "a,b" ,"#, ','), ["x", "say \"hi\"", "a,b", ""]);
+    }
+
+    #[test]
+    fn positionals_skip_only_flag_values() {
+        let args: Vec<String> = ["--json", "x.pdb", "--density", "20", "A", "--streaming", "B", "--reference", "r.log"].map(String::from).into();
+        assert_eq!(positionals(&args, &["--reference"]), ["x.pdb", "A", "B"]);
+    }
+
+    #[test]
+    fn manifest_switch_set_to_false_turns_it_off() {
+        let mut settings = Settings { homodimer: true, radii_source: Some(RadiiSource::CalphaOnly), ..Settings::default() };
+        SettingsConfig { homodimer: Some(false), ca_only: Some(false), ..SettingsConfig::default() }.apply(&mut settings).unwrap();
+        assert!(!settings.homodimer);
+        assert!(settings.radii_source.is_none());
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, Read};
 
 use crate::sc::types::{AtomRadius, ScValue};
 
+#[cfg(feature = "serde")]
 #[derive(serde::Deserialize)]
 struct RadiusRecord { residue: String, atom: String, radius: ScValue }
 
@@ -13,17 +15,68 @@ pub fn read_atomic_radii_from_path(path: &str) -> io::Result<Vec<AtomRadius>> {
 	read_atomic_radii_from_str(&buf)
 }
 
+fn invalid_radii(reason: impl std::fmt::Display) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("invalid radii json: {reason}"))
+}
+
+#[cfg(feature = "serde")]
 pub fn read_atomic_radii_from_str(data: &str) -> io::Result<Vec<AtomRadius>> {
-	let recs: Vec<RadiusRecord> = serde_json::from_str(data)
-		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("invalid radii json: {e}")))?;
+	let recs: Vec<RadiusRecord> = serde_json::from_str(data).map_err(invalid_radii)?;
 	Ok(recs.into_iter().filter(|r| r.radius > 0.0).map(|r| AtomRadius { residue: r.residue, atom: r.atom, radius: r.radius }).collect())
 }
 
+/// Without serde: reads the flat layout of `atomic_radii.json` only, an array of
+/// `{"residue": .., "atom": .., "radius": ..}` objects whose strings hold no escapes, commas or braces
+#[cfg(not(feature = "serde"))]
+pub fn read_atomic_radii_from_str(data: &str) -> io::Result<Vec<AtomRadius>> {
+	let body = data.trim().strip_prefix('[').and_then(|s| s.strip_suffix(']')).ok_or_else(|| invalid_radii("expected an array"))?;
+	let mut radii = Vec::new();
+	let mut rest = body.trim_start();
+	while !rest.is_empty() {
+		let object = rest.strip_prefix('{').ok_or_else(|| invalid_radii("expected an object"))?;
+		let end = object.find('}').ok_or_else(|| invalid_radii("unterminated object"))?;
+		let (mut residue, mut atom, mut radius) = (None, None, None);
+		for field in object[..end].split(',').filter(|f| !f.trim().is_empty()) {
+			let (key, value) = field.split_once(':').ok_or_else(|| invalid_radii(format!("malformed field {}", field.trim())))?;
+			let value = value.trim();
+			let string = || value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).map(str::to_string).ok_or_else(|| invalid_radii(format!("expected a string, got {value}")));
+			match key.trim().trim_matches('"') {
+				"residue" => residue = Some(string()?),
+				"atom" => atom = Some(string()?),
+				"radius" => radius = Some(value.parse::<ScValue>().map_err(|e| invalid_radii(format!("radius {value}: {e}")))?),
+				_ => {}
+			}
+		}
+		match (residue, atom, radius) {
+			(Some(residue), Some(atom), Some(radius)) => if radius > 0.0 { radii.push(AtomRadius { residue, atom, radius }); },
+			_ => return Err(invalid_radii("each record needs residue, atom and radius")),
+		}
+		rest = object[end + 1..].trim_start();
+		rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+	}
+	Ok(radii)
+}
+
 pub fn embedded_atomic_radii() -> Vec<AtomRadius> {
 	let data: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/atomic_radii.json"));
 	read_atomic_radii_from_str(data).unwrap_or_default()
 }
 
+/// Effective radii for Cα-only models (`RadiiSource::CalphaOnly`): each CA stands in for its whole
+/// residue, as a sphere of the residue's volume (Zamyatnin 1972); other atoms have no radius
+pub fn embedded_calpha_radii() -> Vec<AtomRadius> {
+	let data: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/calpha_radii.json"));
+	read_atomic_radii_from_str(data).unwrap_or_default()
+}
+
+/// Bead radii for MARTINI coarse-grained models (`RadiiSource::Martini`): the backbone bead BB and
+/// side-chain beads SC1.. of each residue get the radius of their Martini 3 bead size (regular,
+/// small or tiny, 2^(1/6) σ/2); any other bead is taken as a regular one
+pub fn embedded_martini_radii() -> Vec<AtomRadius> {
+	let data: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/martini_radii.json"));
+	read_atomic_radii_from_str(data).unwrap_or_default()
+}
+
 pub fn wildcard_match(query: &str, pattern: &str) -> bool {
 	fn rtrim_spaces(s: &str) -> &str {
 		let mut end = s.len();
@@ -47,3 +100,45 @@ pub fn wildcard_match(query: &str, pattern: &str) -> bool {
 	q == p
 }
 
+
+fn rtrim_spaces(s: &str) -> &str { s.trim_end_matches(' ') }
+
+/// Lookup table over a radii list that returns the same pattern as a first-match linear scan with
+/// `wildcard_match`. Literal (residue, atom) pairs are hashed; patterns with a wildcard atom are
+/// bucketed by their literal residue, and wildcard-residue patterns are kept in one ordered list.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RadiusIndex {
+	exact: HashMap<(String, String), usize>,
+	by_residue: HashMap<String, Vec<usize>>,
+	any_residue: Vec<usize>,
+	/// Element fallback: first `***` pattern whose atom name is exactly the element symbol
+	elements: HashMap<String, usize>,
+}
+
+impl RadiusIndex {
+	pub(crate) fn new(radii: &[AtomRadius]) -> Self {
+		let mut index = Self::default();
+		for (i, r) in radii.iter().enumerate() {
+			let (residue, atom) = (rtrim_spaces(&r.residue), rtrim_spaces(&r.atom));
+			if residue.contains('*') { index.any_residue.push(i); }
+			else if atom.contains('*') { index.by_residue.entry(residue.to_string()).or_default().push(i); }
+			else { index.exact.entry((residue.to_string(), atom.to_string())).or_insert(i); }
+			if r.residue.trim().starts_with("***") { index.elements.entry(r.atom.trim().to_string()).or_insert(i); }
+		}
+		index
+	}
+
+	/// Position in `radii` (the list the index was built from) of the first pattern matching the atom
+	pub(crate) fn find(&self, radii: &[AtomRadius], residue: &str, atom: &str) -> Option<usize> {
+		let key = (rtrim_spaces(residue).to_string(), rtrim_spaces(atom).to_string());
+		let mut best = self.exact.get(&key).copied();
+		let first_below = |candidates: &[usize], best: Option<usize>, residue_too: bool| candidates.iter().copied()
+			.take_while(|&i| best.is_none_or(|b| i < b))
+			.find(|&i| (!residue_too || wildcard_match(residue, &radii[i].residue)) && wildcard_match(atom, &radii[i].atom));
+		if let Some(bucket) = self.by_residue.get(&key.0) { best = first_below(bucket, best, false).or(best); }
+		first_below(&self.any_residue, best, true).or(best)
+	}
+
+	/// Position of the generic radius for an element symbol (e.g. `***:C`)
+	pub(crate) fn element(&self, element: &str) -> Option<usize> { self.elements.get(element).copied() }
+}
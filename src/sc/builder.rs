@@ -0,0 +1,86 @@
+use crate::sc::sc_calculator::ScCalculator;
+use crate::sc::settings::{Phase, Progress, RadiiSource, ScStatistic, Settings, TrimMode, WeightFunction};
+use crate::sc::surface_generator::SurfaceCalculatorError;
+use crate::sc::types::AtomRadius;
+
+/// Configures an `ScCalculator` in one expression and checks the parameters before any atom is
+/// added: `ScCalculator::builder().probe_radius(1.4).density(15.0).build()?`
+#[derive(Clone, Debug, Default)]
+pub struct ScCalculatorBuilder {
+	settings: Settings,
+	/// First molecule index outside 0 and 1 passed to a per-molecule setter, reported by `build`
+	bad_molecule: Option<usize>,
+}
+
+impl ScCalculatorBuilder {
+	pub fn new() -> Self { Self::default() }
+	/// Start from an existing configuration instead of the defaults
+	pub fn settings(mut self, settings: Settings) -> Self { self.settings = settings; self }
+	/// Probe radius in Å for both molecules
+	pub fn probe_radius(mut self, rp: f64) -> Self { self.settings.rp = rp; self }
+	/// Probe radius in Å for one molecule (0 or 1; any other index fails in `build`)
+	pub fn molecule_probe_radius(mut self, molecule: usize, rp: f64) -> Self {
+		if let Some(slot) = self.molecule_slot(molecule) { self.settings.rp_molecule[slot] = Some(rp); }
+		self
+	}
+	/// Dot density per Å² for both molecules
+	pub fn density(mut self, density: f64) -> Self { self.settings.dot_density = density; self }
+	/// Dot density per Å² for one molecule (0 or 1; any other index fails in `build`)
+	pub fn molecule_density(mut self, molecule: usize, density: f64) -> Self {
+		if let Some(slot) = self.molecule_slot(molecule) { self.settings.dot_density_molecule[slot] = Some(density); }
+		self
+	}
+	pub fn peripheral_band(mut self, band: f64) -> Self { self.settings.peripheral_band = band; self }
+	pub fn trim_mode(mut self, mode: TrimMode) -> Self { self.settings.trim_mode = mode; self }
+	pub fn separation_cutoff(mut self, cutoff: f64) -> Self { self.settings.separation_cutoff = cutoff; self }
+	/// Derive the separation cutoff from the largest radius and probe radius (`Settings::auto_separation_cutoff`)
+	pub fn auto_separation_cutoff(mut self, enabled: bool) -> Self { self.settings.auto_separation_cutoff = enabled; self }
+	pub fn gaussian_w(mut self, w: f64) -> Self { self.settings.gaussian_w = w; self }
+	pub fn statistic(mut self, statistic: ScStatistic) -> Self { self.settings.statistic = statistic; self }
+	pub fn weight(mut self, weight: WeightFunction) -> Self { self.settings.weight = weight; self }
+	pub fn parallel(mut self, enabled: bool) -> Self { self.settings.enable_parallel = enabled; self }
+	pub fn num_threads(mut self, n: usize) -> Self { self.settings.num_threads = Some(n); self }
+	#[cfg(feature = "parallel")]
+	pub fn thread_pool(mut self, pool: std::sync::Arc<rayon::ThreadPool>) -> Self { self.settings.thread_pool = Some(pool); self }
+	pub fn streaming(mut self, enabled: bool) -> Self { self.settings.streaming = enabled; self }
+	pub fn progress(mut self, f: impl Fn(Phase, usize, usize) + Send + Sync + 'static) -> Self { self.settings.progress = Some(Progress::new(f)); self }
+	/// Where radii come from (embedded table, JSON file or inline table)
+	pub fn radii_source(mut self, source: RadiiSource) -> Self { self.settings.radii_source = Some(source); self }
+	/// Radii table replacing the embedded one (see `atomic_radii` for loaders)
+	pub fn radii_set(self, radii: Vec<AtomRadius>) -> Self { self.radii_source(RadiiSource::Inline(radii)) }
+
+	/// `molecule` when it is 0 or 1; otherwise None, and the index is kept for `build` to report
+	fn molecule_slot(&mut self, molecule: usize) -> Option<usize> {
+		if molecule <= 1 { return Some(molecule); }
+		self.bad_molecule.get_or_insert(molecule);
+		None
+	}
+
+	/// Validate the configuration and create the calculator
+	pub fn build(self) -> Result<ScCalculator, SurfaceCalculatorError> {
+		if let Some(molecule) = self.bad_molecule {
+			return Err(SurfaceCalculatorError::InvalidSettings(format!("molecule index {molecule} is out of range (0 or 1)")));
+		}
+		self.settings.validate()?;
+		let mut sc = ScCalculator::new();
+		*sc.settings_mut() = self.settings;
+		// Resolve the radii now so a missing radii file fails here rather than on the first atom
+		sc.base.init()?;
+		Ok(sc)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn per_molecule_setters_reject_bad_index() {
+		let sc = ScCalculatorBuilder::new().molecule_probe_radius(1, 1.2).molecule_density(0, 20.0).build().unwrap();
+		assert_eq!((sc.settings().rp_molecule, sc.settings().dot_density_molecule), ([None, Some(1.2)], [Some(20.0), None]));
+		let built = ScCalculatorBuilder::new().molecule_probe_radius(2, 1.2).build();
+		assert!(matches!(built, Err(SurfaceCalculatorError::InvalidSettings(m)) if m.contains("index 2")));
+		let built = ScCalculatorBuilder::new().molecule_density(5, 20.0).molecule_density(1, 20.0).build();
+		assert!(matches!(built, Err(SurfaceCalculatorError::InvalidSettings(m)) if m.contains("index 5")));
+	}
+}
@@ -0,0 +1,178 @@
+// C API for linking sc-rs into C/C++ docking codes. A calculator is an opaque handle; calls return
+// 0 on success and -1 on failure, with the message kept on the handle for `sc_last_error`. The
+// header is include/sc_rs.h, generated by cbindgen from this file (see cbindgen.toml).
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use crate::sc::sc_calculator::ScCalculator;
+use crate::sc::types::Atom;
+use crate::sc::vector3::Vec3;
+
+/// Opaque calculator handle
+pub struct ScHandle {
+	sc: ScCalculator,
+	error: CString,
+}
+
+impl ScHandle {
+	fn fail(&mut self, message: impl ToString) -> c_int {
+		self.error = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+		-1
+	}
+}
+
+/// Headline values of the last `sc_calc`; per-molecule arrays are indexed by molecule (0 or 1)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScResults {
+	/// Shape complementarity (median S by default)
+	pub sc: f64,
+	/// Median nearest-neighbor distance between the trimmed surfaces (Å)
+	pub distance: f64,
+	/// Trimmed interface area of both surfaces (Å²)
+	pub area: f64,
+	/// Buried SASA of both molecules (Å²)
+	pub bsa: f64,
+	pub gap_volume: f64,
+	pub gap_index: f64,
+	pub n_atoms: [usize; 2],
+	pub n_trimmed_dots: [usize; 2],
+}
+
+/// New calculator with default settings; release with `sc_free`
+#[no_mangle]
+pub extern "C" fn sc_new() -> *mut ScHandle {
+	Box::into_raw(Box::new(ScHandle { sc: ScCalculator::new(), error: CString::default() }))
+}
+
+/// # Safety
+/// `sc` must come from `sc_new` and not be used afterwards; null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn sc_free(sc: *mut ScHandle) {
+	if !sc.is_null() { drop(Box::from_raw(sc)); }
+}
+
+/// Add one atom to `molecule` (0 or 1; any other value fails). A `radius` ≤ 0 is looked up in the
+/// embedded radii table from the residue and atom names.
+///
+/// # Safety
+/// `sc` must be a live handle; `residue` and `atom` must be NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn sc_add_atom(sc: *mut ScHandle, molecule: c_int, residue: *const c_char, atom: *const c_char, x: f64, y: f64, z: f64, radius: f64) -> c_int {
+	let Some(handle) = sc.as_mut() else { return -1 };
+	if !matches!(molecule, 0 | 1) { return handle.fail(format!("molecule must be 0 or 1, not {molecule}")); }
+	if residue.is_null() || atom.is_null() { return handle.fail("residue and atom names must not be null"); }
+	let mut a = Atom::new();
+	a.residue = CStr::from_ptr(residue).to_string_lossy().into_owned();
+	a.atom = CStr::from_ptr(atom).to_string_lossy().into_owned();
+	a.coor = Vec3::new(x, y, z);
+	a.radius = radius.max(0.0);
+	match catch_unwind(AssertUnwindSafe(|| handle.sc.add_atom(molecule, a))) {
+		Ok(Ok(())) => 0,
+		Ok(Err(e)) => handle.fail(e),
+		Err(_) => handle.fail("internal error (panic) while adding an atom"),
+	}
+}
+
+/// Compute Sc for the atoms added so far
+///
+/// # Safety
+/// `sc` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn sc_calc(sc: *mut ScHandle) -> c_int {
+	let Some(handle) = sc.as_mut() else { return -1 };
+	match catch_unwind(AssertUnwindSafe(|| handle.sc.calc())) {
+		Ok(Ok(_)) => 0,
+		Ok(Err(e)) => handle.fail(e),
+		Err(_) => handle.fail("internal error (panic) during calculation"),
+	}
+}
+
+/// Copy the results of the last successful `sc_calc` into `out`; fails before the first one
+///
+/// # Safety
+/// `sc` must be a live handle and `out` must point to writable `ScResults`.
+#[no_mangle]
+pub unsafe extern "C" fn sc_results(sc: *mut ScHandle, out: *mut ScResults) -> c_int {
+	let Some(handle) = sc.as_mut() else { return -1 };
+	if out.is_null() { return handle.fail("results pointer must not be null"); }
+	let r = handle.sc.results();
+	if r.valid != 1 { return handle.fail("no results: sc_calc has not succeeded"); }
+	*out = ScResults {
+		sc: r.sc,
+		distance: r.distance,
+		area: r.area,
+		bsa: r.bsa,
+		gap_volume: r.gap_volume,
+		gap_index: r.gap_index,
+		n_atoms: [r.surfaces[0].n_atoms, r.surfaces[1].n_atoms],
+		n_trimmed_dots: [r.surfaces[0].n_trimmed_dots, r.surfaces[1].n_trimmed_dots],
+	};
+	0
+}
+
+/// Message of the last failed call on `sc`, valid until the next call; empty when none failed
+///
+/// # Safety
+/// `sc` must be a live handle or null.
+#[no_mangle]
+pub unsafe extern "C" fn sc_last_error(sc: *const ScHandle) -> *const c_char {
+	match sc.as_ref() {
+		Some(handle) => handle.error.as_ptr(),
+		None => ptr::null(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sc::sc_calculator::tests::complex;
+
+	fn last_error(sc: *const ScHandle) -> String {
+		unsafe { CStr::from_ptr(sc_last_error(sc)).to_string_lossy().into_owned() }
+	}
+
+	#[test]
+	fn handle_round_trip_matches_calculator() {
+		let (mol1, mol2) = complex();
+		let expected = ScCalculator::new().calc_atoms(&mol1, &mol2).unwrap();
+		let sc = sc_new();
+		let mut out = ScResults::default();
+		unsafe {
+			assert_eq!(sc_results(sc, &mut out), -1);
+			assert!(last_error(sc).contains("sc_calc has not succeeded"));
+			for (molecule, atoms) in [(0, &mol1), (1, &mol2)] {
+				for a in atoms {
+					let (residue, atom) = (CString::new(a.residue.as_str()).unwrap(), CString::new(a.atom.as_str()).unwrap());
+					assert_eq!(sc_add_atom(sc, molecule, residue.as_ptr(), atom.as_ptr(), a.coor.x, a.coor.y, a.coor.z, a.radius), 0);
+				}
+			}
+			let (residue, atom) = (CString::new("ALA").unwrap(), CString::new("CB").unwrap());
+			assert_eq!(sc_add_atom(sc, 2, residue.as_ptr(), atom.as_ptr(), 0.0, 0.0, 0.0, 1.8), -1);
+			assert!(last_error(sc).contains("molecule must be 0 or 1"));
+			assert_eq!(sc_add_atom(sc, 0, ptr::null(), atom.as_ptr(), 0.0, 0.0, 0.0, 1.8), -1);
+			assert_eq!(sc_calc(sc), 0);
+			assert_eq!(sc_results(sc, &mut out), 0);
+			assert_eq!(sc_results(sc, ptr::null_mut()), -1);
+			sc_free(sc);
+			assert!(sc_last_error(ptr::null()).is_null());
+			assert_eq!(sc_calc(ptr::null_mut()), -1);
+		}
+		assert!((out.sc - expected.sc).abs() < 1e-12);
+		assert!((out.area - expected.area).abs() < 1e-9);
+		assert_eq!(out.n_atoms, [expected.surfaces[0].n_atoms, expected.surfaces[1].n_atoms]);
+		assert_eq!(out.n_trimmed_dots, [expected.surfaces[0].n_trimmed_dots, expected.surfaces[1].n_trimmed_dots]);
+	}
+
+	#[test]
+	fn failed_calc_reports_its_error() {
+		let sc = sc_new();
+		unsafe {
+			assert_eq!(sc_calc(sc), -1);
+			assert!(!last_error(sc).is_empty());
+			sc_free(sc);
+		}
+	}
+}
@@ -0,0 +1,67 @@
+use crate::sc::electrostatics::pearson;
+use crate::sc::grid::SpatialGrid;
+use crate::sc::sc_calculator::median;
+use crate::sc::surface_generator::RunState;
+use crate::sc::types::*;
+use crate::sc::par::*;
+
+/// Mean curvature at dot `idx` in Å^-1 (positive convex, negative concave), from the turning of
+/// the normals across its neighborhood: each dot j within `radius` gives the normal curvature
+/// (n_j - n_i)·(x_j - x_i) / |x_j - x_i|^2 along its direction, and their area-weighted mean over
+/// all directions approximates the mean curvature (1/r on an atom sphere, -1/r_p on a probe).
+fn dot_curvature(dots: &[Dot], grid: &SpatialGrid, idx: usize, radius: ScValue) -> ScValue {
+	let dot = &dots[idx];
+	let n_i = dot.outnml.normalized();
+	let mut candidates = Vec::new();
+	grid.candidates_into(dot.coor, 1, &mut candidates);
+	let (mut sum, mut weight) = (0.0, 0.0);
+	for &j in &candidates {
+		let d = dots[j].coor - dot.coor;
+		let d2 = d.magnitude_squared();
+		if d2 <= 1e-12 || d2 > radius * radius { continue; }
+		sum += dots[j].area * (dots[j].outnml.normalized() - n_i).dot(d) / d2;
+		weight += dots[j].area;
+	}
+	if weight > 0.0 { sum / weight } else { 0.0 }
+}
+
+/// Curvature of the dots `indices` of one surface, in that order
+pub(crate) fn curvatures(dots: &[Dot], indices: &[usize], radius: ScValue, parallel: bool) -> Vec<ScValue> {
+	let grid = SpatialGrid::new(dots.iter().map(|d| d.coor), radius);
+	let eval = |&idx: &usize| dot_curvature(dots, &grid, idx, radius);
+	if parallel { indices.par_iter().map(eval).collect() } else { indices.iter().map(eval).collect() }
+}
+
+/// Curvature complementarity of the trimmed interface: the curvature of each paired dot against
+/// that of its partner. Complementary surfaces fit a convex bump into a concave groove of the same
+/// curvature, so H_A ≈ -H_B.
+pub(crate) fn curvature_stats(run: &RunState, radius: ScValue, parallel: bool) -> CurvatureStats {
+	// Curvature is needed at each surface's trimmed dots and at the partner dots paired with them
+	let per_surface: [Vec<ScValue>; 2] = [0, 1].map(|m| {
+		let mut needed: Vec<usize> = run.pairs[m].iter().map(|p| p.dot).chain(run.pairs[1 - m].iter().map(|p| p.neighbor)).collect();
+		needed.sort_unstable();
+		needed.dedup();
+		let mut curvature = vec![0.0; run.dots[m].len()];
+		for (idx, h) in needed.iter().zip(curvatures(&run.dots[m], &needed, radius, parallel)) { curvature[*idx] = h; }
+		curvature
+	});
+	let mut stats = CurvatureStats::default();
+	let (mut complementarity, mut mismatch) = (Vec::new(), Vec::new());
+	for my in 0..2 {
+		let pairs = &run.pairs[my];
+		if pairs.is_empty() { continue; }
+		let own: Vec<ScValue> = pairs.iter().map(|p| per_surface[my][p.dot]).collect();
+		let opposed: Vec<ScValue> = pairs.iter().map(|p| -per_surface[1 - my][p.neighbor]).collect();
+		let area: ScValue = pairs.iter().map(|p| run.dots[my][p.dot].area).sum();
+		if area > 0.0 { stats.mean_curvature[my] = pairs.iter().zip(&own).map(|(p, h)| run.dots[my][p.dot].area * h).sum::<ScValue>() / area; }
+		complementarity.push(pearson(&own, &opposed).unwrap_or(0.0));
+		let mut gaps: Vec<ScValue> = own.iter().zip(&opposed).map(|(a, b)| (a - b).abs()).collect();
+		mismatch.push(median(&mut gaps));
+		stats.n_pairs += pairs.len();
+	}
+	if !complementarity.is_empty() {
+		stats.complementarity = complementarity.iter().sum::<ScValue>() / complementarity.len() as ScValue;
+		stats.mismatch = mismatch.iter().sum::<ScValue>() / mismatch.len() as ScValue;
+	}
+	stats
+}
@@ -0,0 +1,54 @@
+use crate::sc::surface_generator::RunState;
+use crate::sc::types::*;
+use crate::sc::vector3::Vec3;
+use crate::sc::par::*;
+
+/// Coulomb constant in kcal·Å/(mol·e^2)
+const COULOMB: ScValue = 332.0636;
+/// Closest approach used to keep the potential finite at dots lying on an atom's own sphere
+const MIN_DISTANCE: ScValue = 0.5;
+
+/// Potential of one molecule's charges at a point, with a distance-dependent dielectric ε = 4r
+fn potential(atoms: &[Atom], molecule: usize, point: Vec3) -> ScValue {
+	atoms.iter()
+		.filter(|a| a.molecule == molecule && !a.context)
+		.filter_map(|a| a.charge.map(|q| {
+			let r = point.distance(a.coor).max(MIN_DISTANCE);
+			COULOMB * q / (4.0 * r * r)
+		}))
+		.sum()
+}
+
+pub(crate) fn pearson(x: &[ScValue], y: &[ScValue]) -> Option<ScValue> {
+	let n = x.len() as ScValue;
+	if x.len() < 2 { return None; }
+	let mx = x.iter().sum::<ScValue>() / n;
+	let my = y.iter().sum::<ScValue>() / n;
+	let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+	for (a, b) in x.iter().zip(y) {
+		sxy += (a - mx) * (b - my);
+		sxx += (a - mx) * (a - mx);
+		syy += (b - my) * (b - my);
+	}
+	if sxx <= 0.0 || syy <= 0.0 { return None; }
+	Some(sxy / (sxx.sqrt() * syy.sqrt()))
+}
+
+/// Electrostatic complementarity of surface `my` (McCoy, Epa & Colman 1997): the negated
+/// correlation between the potentials generated by each molecule at the trimmed interface dots.
+/// Returns None when no atom carries a charge or the potentials are constant.
+pub(crate) fn surface_ec(run: &RunState, my: usize, parallel: bool) -> Option<ScValue> {
+	if !run.atoms.iter().any(|a| a.charge.is_some()) { return None; }
+	let their = 1 - my;
+	let eval = |&idx: &usize| {
+		let p = run.dots[my][idx].coor;
+		(potential(&run.atoms, my, p), potential(&run.atoms, their, p))
+	};
+	let values: Vec<(ScValue, ScValue)> = if parallel {
+		run.trimmed_dots[my].par_iter().map(eval).collect()
+	} else {
+		run.trimmed_dots[my].iter().map(eval).collect()
+	};
+	let (own, partner): (Vec<ScValue>, Vec<ScValue>) = values.into_iter().unzip();
+	pearson(&own, &partner).map(|r| -r)
+}
@@ -0,0 +1,313 @@
+// wgpu compute backend for contact-dot sampling and dot burial classification, both in f32.
+// Sampling: the CPU lays out the latitude circles of each atom's contact surface (a few per atom),
+// and one invocation per candidate point places it on its circle and drops it when its probe
+// center falls inside a same-molecule neighbor's expanded sphere; the caller rebuilds the kept
+// points in f64. Burial: a dot is buried when its probe center lies inside an expanded sphere
+// (radius + rp) of the opposite molecule, tested per dot against the spheres of the 27 burial-grid
+// cells around it, uploaded as a dense box of cells (`grid::DenseBins`). The device is created once
+// per process; without an adapter every call returns None and the caller falls back to the CPU.
+
+use std::sync::OnceLock;
+
+use wgpu::util::DeviceExt;
+
+use crate::sc::grid::DenseBins;
+
+const WORKGROUP: u32 = 64;
+const MAX_GROUPS_X: u32 = 65535;
+
+/// Candidate contact points per sampling dispatch, bounding the flag buffer to 16 MB
+const MAX_POINTS_PER_PASS: u32 = 1 << 22;
+
+const BURIAL_SHADER: &str = r#"
+struct Params { n_points: u32, rp: f32, row: u32, nx: u32, ny: u32, nz: u32, pad0: u32, pad1: u32 }
+@group(0) @binding(0) var<storage, read> points: array<vec4<f32>>;
+@group(0) @binding(1) var<storage, read> cells: array<u32>;
+@group(0) @binding(2) var<storage, read> ranges: array<vec2<u32>>;
+@group(0) @binding(3) var<storage, read> spheres: array<vec4<f32>>;
+@group(0) @binding(4) var<storage, read_write> flags: array<u32>;
+@group(0) @binding(5) var<uniform> params: Params;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+	let i = id.x + id.y * params.row;
+	if (i >= params.n_points) { return; }
+	let cell = cells[i];
+	var hit = 0u;
+	if (cell != 0xffffffffu) {
+		let p = points[i].xyz;
+		let dims = vec3<i32>(i32(params.nx), i32(params.ny), i32(params.nz));
+		let c = vec3<i32>(i32(cell % params.nx), i32((cell / params.nx) % params.ny), i32(cell / (params.nx * params.ny)));
+		for (var dz = -1; dz <= 1 && hit == 0u; dz = dz + 1) {
+			for (var dy = -1; dy <= 1 && hit == 0u; dy = dy + 1) {
+				for (var dx = -1; dx <= 1 && hit == 0u; dx = dx + 1) {
+					let n = c + vec3<i32>(dx, dy, dz);
+					if (any(n < vec3<i32>(0)) || any(n >= dims)) { continue; }
+					let range = ranges[u32(n.x + dims.x * (n.y + dims.y * n.z))];
+					for (var j = range.x; j < range.x + range.y; j = j + 1u) {
+						let s = spheres[j];
+						let d = p - s.xyz;
+						let r = s.w + params.rp;
+						if (dot(d, d) <= r * r) { hit = 1u; break; }
+					}
+				}
+			}
+		}
+	}
+	flags[i] = hit;
+}
+"#;
+
+const CONTACT_SHADER: &str = r#"
+struct Circle { center: vec4<f32>, x: vec4<f32>, y: vec4<f32>, atom: u32, first: u32, count: u32, pad: u32 }
+struct Params { n_points: u32, n_circles: u32, row: u32, pad: u32 }
+@group(0) @binding(0) var<storage, read> circles: array<Circle>;
+@group(0) @binding(1) var<storage, read> atoms: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read> ranges: array<vec2<u32>>;
+@group(0) @binding(3) var<storage, read> neighbors: array<u32>;
+@group(0) @binding(4) var<storage, read_write> flags: array<u32>;
+@group(0) @binding(5) var<uniform> params: Params;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+	let i = id.x + id.y * params.row;
+	if (i >= params.n_points) { return; }
+	// Last circle starting at or before point i
+	var lo = 0u;
+	var hi = params.n_circles - 1u;
+	while (lo < hi) {
+		let mid = (lo + hi + 1u) / 2u;
+		if (circles[mid].first <= i) { lo = mid; } else { hi = mid - 1u; }
+	}
+	let c = circles[lo];
+	let a = (f32(i - c.first) + 0.5) * c.x.w;
+	let point = c.center.xyz + c.x.xyz * (c.center.w * cos(a)) + c.y.xyz * (c.center.w * sin(a));
+	let owner = atoms[c.atom];
+	let rp = c.y.w;
+	let pcen = owner.xyz + (point - owner.xyz) * ((owner.w + rp) / owner.w);
+	// The nearest neighbor (first in the row) already bounds the sampled band
+	let range = ranges[c.atom];
+	var keep = 1u;
+	for (var j = range.x + 1u; j < range.x + range.y; j = j + 1u) {
+		let n = atoms[neighbors[j]];
+		if (distance(pcen, n.xyz) <= n.w + rp) { keep = 0u; break; }
+	}
+	flags[i] = keep;
+}
+"#;
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct BurialParams { n_points: u32, rp: f32, row: u32, nx: u32, ny: u32, nz: u32, pad0: u32, pad1: u32 }
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ContactParams { n_points: u32, n_circles: u32, row: u32, pad: u32 }
+
+/// One latitude circle of an atom's contact surface: point k lies at angle (k + 0.5)·`x[3]` from
+/// `x` towards `y` on the circle of center and radius `center`; `y[3]` is the owner's probe radius.
+/// `first` numbers the circle's first point among all the circles' points.
+#[repr(C)]
+#[derive(Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct ContactCircle {
+	pub center: [f32; 4],
+	pub x: [f32; 4],
+	pub y: [f32; 4],
+	pub atom: u32,
+	pub first: u32,
+	pub count: u32,
+	pub pad: u32,
+}
+
+struct Gpu {
+	device: wgpu::Device,
+	queue: wgpu::Queue,
+	burial: wgpu::ComputePipeline,
+	contact: wgpu::ComputePipeline,
+}
+
+fn gpu() -> Option<&'static Gpu> {
+	static GPU: OnceLock<Option<Gpu>> = OnceLock::new();
+	GPU.get_or_init(|| {
+		let instance = wgpu::Instance::default();
+		let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).ok()?;
+		let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).ok()?;
+		let pipeline = |label, source: &str| {
+			let module = device.create_shader_module(wgpu::ShaderModuleDescriptor { label: Some(label), source: wgpu::ShaderSource::Wgsl(source.into()) });
+			device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+				label: Some(label),
+				layout: None,
+				module: &module,
+				entry_point: Some("main"),
+				compilation_options: Default::default(),
+				cache: None,
+			})
+		};
+		let burial = pipeline("sc burial", BURIAL_SHADER);
+		let contact = pipeline("sc contact", CONTACT_SHADER);
+		Some(Gpu { device, queue, burial, contact })
+	}).as_ref()
+}
+
+/// Whether a GPU adapter is available to this process
+pub fn available() -> bool { gpu().is_some() }
+
+/// For each point (probe center), whether it lies within `sphere.w + rp` of a sphere of `bins`;
+/// `cells[i]` is the `DenseBins::cell_of` index of point i. Returns None when no GPU is available
+/// or the readback fails.
+pub(crate) fn buried_flags(points: &[[f32; 4]], cells: &[u32], bins: &DenseBins, rp: f32) -> Option<Vec<bool>> {
+	if points.is_empty() { return Some(Vec::new()); }
+	if bins.spheres.is_empty() { return Some(vec![false; points.len()]); }
+	let g = gpu()?;
+	let [nx, ny, nz] = bins.dims;
+	let storage: [&[u8]; 4] = [bytemuck::cast_slice(points), bytemuck::cast_slice(cells), bytemuck::cast_slice(&bins.ranges), bytemuck::cast_slice(&bins.spheres)];
+	let flags = dispatch(g, &g.burial, &storage, points.len() as u32, |row| BurialParams { n_points: points.len() as u32, rp, row, nx, ny, nz, pad0: 0, pad1: 0 })?;
+	Some(flags.into_iter().map(|f| f != 0).collect())
+}
+
+/// For every point of `circles` (in circle order), whether it is kept: its probe center lies outside
+/// the expanded sphere of every neighbor but the first in the owner's row of `ranges` (a
+/// `(start, count)` slice of `neighbors` per atom). `atoms` holds each atom's center and radius.
+/// Returns None when no GPU is available or the readback fails.
+pub(crate) fn contact_flags(circles: &[ContactCircle], atoms: &[[f32; 4]], ranges: &[[u32; 2]], neighbors: &[u32]) -> Option<Vec<bool>> {
+	let total: u64 = circles.iter().map(|c| c.count as u64).sum();
+	let mut kept = Vec::with_capacity(total as usize);
+	let g = gpu()?;
+	let mut rest = circles;
+	while !rest.is_empty() {
+		// Whole circles per pass, at least one however large
+		let mut n_points = 0u32;
+		let take = rest.iter().take_while(|c| {
+			let fits = n_points == 0 || n_points + c.count <= MAX_POINTS_PER_PASS;
+			if fits { n_points += c.count; }
+			fits
+		}).count();
+		let (pass, tail) = rest.split_at(take);
+		rest = tail;
+		if n_points == 0 { continue; }
+		let base = pass[0].first;
+		let rebased: Vec<ContactCircle> = pass.iter().map(|c| ContactCircle { first: c.first - base, ..*c }).collect();
+		let storage: [&[u8]; 4] = [bytemuck::cast_slice(&rebased), bytemuck::cast_slice(atoms), bytemuck::cast_slice(ranges), bytemuck::cast_slice(neighbors)];
+		let flags = dispatch(g, &g.contact, &storage, n_points, |row| ContactParams { n_points, n_circles: rebased.len() as u32, row, pad: 0 })?;
+		kept.extend(flags.into_iter().map(|f| f != 0));
+	}
+	Some(kept)
+}
+
+/// Run `pipeline` once per item over `n` items with `storage` bound read-only at bindings 0.., the
+/// per-item u32 output after them and the uniform `params` (given the row stride of the 2D
+/// dispatch) last; returns the output
+fn dispatch<P: bytemuck::Pod>(g: &Gpu, pipeline: &wgpu::ComputePipeline, storage: &[&[u8]], n: u32, params: impl FnOnce(u32) -> P) -> Option<Vec<u32>> {
+	let groups = n.div_ceil(WORKGROUP);
+	let (groups_x, groups_y) = (groups.min(MAX_GROUPS_X), groups.div_ceil(MAX_GROUPS_X));
+	let params = params(groups_x * WORKGROUP);
+	// Bindings cannot be empty; a zeroed vec4 stands in for an empty table
+	let buffers: Vec<wgpu::Buffer> = storage.iter().map(|data| {
+		let contents = if data.is_empty() { &[0u8; 16][..] } else { data };
+		g.device.create_buffer_init(&wgpu::util::BufferInitDescriptor { label: None, contents, usage: wgpu::BufferUsages::STORAGE })
+	}).collect();
+	let size = (n as usize * std::mem::size_of::<u32>()) as u64;
+	let flags_buf = g.device.create_buffer(&wgpu::BufferDescriptor { label: Some("flags"), size, usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC, mapped_at_creation: false });
+	let readback = g.device.create_buffer(&wgpu::BufferDescriptor { label: Some("readback"), size, usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST, mapped_at_creation: false });
+	let params_buf = g.device.create_buffer_init(&wgpu::util::BufferInitDescriptor { label: Some("params"), contents: bytemuck::bytes_of(&params), usage: wgpu::BufferUsages::UNIFORM });
+	let mut entries: Vec<wgpu::BindGroupEntry> = buffers.iter().enumerate().map(|(i, b)| wgpu::BindGroupEntry { binding: i as u32, resource: b.as_entire_binding() }).collect();
+	entries.push(wgpu::BindGroupEntry { binding: buffers.len() as u32, resource: flags_buf.as_entire_binding() });
+	entries.push(wgpu::BindGroupEntry { binding: buffers.len() as u32 + 1, resource: params_buf.as_entire_binding() });
+	let bind_group = g.device.create_bind_group(&wgpu::BindGroupDescriptor { label: None, layout: &pipeline.get_bind_group_layout(0), entries: &entries });
+	let mut encoder = g.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+	{
+		let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None, timestamp_writes: None });
+		pass.set_pipeline(pipeline);
+		pass.set_bind_group(0, &bind_group, &[]);
+		pass.dispatch_workgroups(groups_x, groups_y, 1);
+	}
+	encoder.copy_buffer_to_buffer(&flags_buf, 0, &readback, 0, size);
+	g.queue.submit(Some(encoder.finish()));
+	let slice = readback.slice(..);
+	let (tx, rx) = std::sync::mpsc::channel();
+	slice.map_async(wgpu::MapMode::Read, move |r| { let _ = tx.send(r); });
+	g.device.poll(wgpu::PollType::wait_indefinitely()).ok()?;
+	rx.recv().ok()?.ok()?;
+	let flags = {
+		let view = slice.get_mapped_range().ok()?;
+		bytemuck::cast_slice::<u8, u32>(&view).to_vec()
+	};
+	readback.unmap();
+	Some(flags)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sc::grid::BurialGrid;
+	use crate::sc::sc_calculator::tests::complex;
+	use crate::sc::surface_generator::AtomArrays;
+	use crate::sc::types::Atom;
+	use crate::sc::vector3::Vec3;
+
+	/// The shader's 27-cell search, on the CPU
+	fn buried_in_bins(bins: &DenseBins, p: [f32; 4], cell: u32, rp: f32) -> bool {
+		if cell == u32::MAX { return false; }
+		let [nx, ny, nz] = bins.dims.map(|d| d as i64);
+		let c = [cell as i64 % nx, (cell as i64 / nx) % ny, cell as i64 / (nx * ny)];
+		(-1..=1).flat_map(|dz| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| [c[0] + dx, c[1] + dy, c[2] + dz])))
+			.filter(|n| n.iter().zip([nx, ny, nz]).all(|(&v, d)| v >= 0 && v < d))
+			.any(|n| {
+				let [start, count] = bins.ranges[(n[0] + nx * (n[1] + ny * n[2])) as usize];
+				bins.spheres[start as usize..(start + count) as usize].iter().any(|s| {
+					let d = [p[0] - s[0], p[1] - s[1], p[2] - s[2]];
+					d[0] * d[0] + d[1] * d[1] + d[2] * d[2] <= (s[3] + rp) * (s[3] + rp)
+				})
+			})
+	}
+
+	#[test]
+	fn dense_bins_match_burial_grid() {
+		let (mol1, mol2) = complex();
+		let atoms: Vec<Atom> = mol1.into_iter().chain(mol2.into_iter().map(|mut a| { a.molecule = 1; a })).collect();
+		let rp = 1.4;
+		let grid = BurialGrid::new(&AtomArrays::from_atoms(&atoms), 1.8 + rp);
+		let bins = grid.dense_bins(1).unwrap();
+		let centers: Vec<Vec3> = (0..4000).map(|i| {
+			let t = i as f64;
+			Vec3::new(4.0 + 14.0 * (t * 0.618).fract(), -4.0 + 17.0 * (t * 0.414).fract(), -4.0 + 17.0 * (t * 0.732).fract())
+		}).collect();
+		let points: Vec<[f32; 4]> = centers.iter().map(|p| [p.x as f32, p.y as f32, p.z as f32, 0.0]).collect();
+		let cells: Vec<u32> = centers.iter().map(|&p| bins.cell_of(p)).collect();
+		let expected: Vec<bool> = centers.iter().map(|&p| grid.is_buried_by(1, p, rp)).collect();
+		assert!(expected.iter().any(|&b| b) && expected.iter().any(|&b| !b));
+		let binned: Vec<bool> = points.iter().zip(&cells).map(|(&p, &c)| buried_in_bins(&bins, p, c, rp as f32)).collect();
+		let mismatches = binned.iter().zip(&expected).filter(|(a, b)| a != b).count();
+		assert!(mismatches <= 2, "{mismatches} points classified differently");
+		// On machines with an adapter, the shader itself
+		if let Some(flags) = buried_flags(&points, &cells, &bins, rp as f32) {
+			let mismatches = flags.iter().zip(&expected).filter(|(a, b)| a != b).count();
+			assert!(mismatches <= 2, "{mismatches} points classified differently on the GPU");
+		}
+	}
+
+	#[test]
+	fn gpu_run_matches_cpu_run() {
+		let (mol1, mol2) = complex();
+		let mut sc = crate::sc::ScCalculator::new();
+		sc.add_atoms(0, mol1).unwrap();
+		sc.add_atoms(1, mol2).unwrap();
+		let cpu = sc.calc().unwrap();
+		let cpu_contact: Vec<usize> = (0..2).map(|m| sc.base.dots(m).iter().filter(|d| d.kind == crate::sc::types::DotKind::Contact).count()).collect();
+		sc.settings_mut().use_gpu = true;
+		let gpu = sc.calc().unwrap();
+		assert!((gpu.sc - cpu.sc).abs() < 1e-3, "gpu {} vs cpu {}", gpu.sc, cpu.sc);
+		assert!((gpu.area - cpu.area).abs() < 0.01 * cpu.area);
+		if available() {
+			// Contact dots came from the sampling shader; f32 can flip points within rounding of a
+			// neighbor's sphere
+			for (m, &expected) in cpu_contact.iter().enumerate() {
+				let n = sc.base.dots(m).iter().filter(|d| d.kind == crate::sc::types::DotKind::Contact).count();
+				assert!(n.abs_diff(expected) <= expected / 1000 + 2, "molecule {m}: {n} contact dots on the GPU vs {expected} on the CPU");
+			}
+		}
+		sc.settings_mut().enable_parallel = false;
+		let serial = sc.calc().unwrap();
+		assert!((serial.sc - gpu.sc).abs() < 1e-3);
+	}
+}
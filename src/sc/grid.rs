@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::sc::simd;
+use crate::sc::surface_generator::AtomArrays;
+use crate::sc::types::{GeomValue, ScValue};
+use crate::sc::vector3::Vec3;
+
+type CellKey = (i64, i64, i64);
+
+fn cell_key(p: Vec3, cell: ScValue) -> CellKey {
+	((p.x / cell).floor() as i64, (p.y / cell).floor() as i64, (p.z / cell).floor() as i64)
+}
+
+/// Uniform hash grid over a point set. Points are binned into cubic cells of edge `cell`, so every
+/// point within distance `reach * cell` of a query lies in the (2·reach + 1)^3 cells around it.
+#[derive(Clone, Default)]
+pub(crate) struct SpatialGrid {
+	cell: ScValue,
+	cells: HashMap<CellKey, Vec<usize>>,
+	len: usize,
+}
+
+impl SpatialGrid {
+	pub(crate) fn new<I: IntoIterator<Item = Vec3>>(points: I, cell: ScValue) -> Self {
+		let mut grid = Self { cell: cell.max(1e-6), cells: HashMap::new(), len: 0 };
+		for (i, p) in points.into_iter().enumerate() { grid.cells.entry(grid.key(p)).or_default().push(i); grid.len = i + 1; }
+		grid
+	}
+
+	fn key(&self, p: Vec3) -> CellKey { cell_key(p, self.cell) }
+
+	/// Fill `out` with the indices of the points in the cells within `reach` cells of `p`, in ascending
+	/// order (a superset of the points within `reach * cell`; callers apply their own distance test)
+	pub(crate) fn candidates_into(&self, p: Vec3, reach: i64, out: &mut Vec<usize>) {
+		let (cx, cy, cz) = self.key(p);
+		out.clear();
+		for dx in -reach..=reach { for dy in -reach..=reach { for dz in -reach..=reach {
+			if let Some(members) = self.cells.get(&(cx + dx, cy + dy, cz + dz)) { out.extend_from_slice(members); }
+		}}}
+		out.sort_unstable();
+	}
+
+	/// Call `f` with the index of every point in the cells within `reach` cells of `p`, unordered
+	pub(crate) fn for_each_candidate(&self, p: Vec3, reach: i64, mut f: impl FnMut(usize)) {
+		let (cx, cy, cz) = self.key(p);
+		for dx in -reach..=reach { for dy in -reach..=reach { for dz in -reach..=reach {
+			if let Some(members) = self.cells.get(&(cx + dx, cy + dy, cz + dz)) { members.iter().for_each(|&i| f(i)); }
+		}}}
+	}
+
+	/// The point minimizing `dist2` with its value, ties going to the highest index as in an
+	/// ascending scan with `<=`. The search widens until the best value is within the searched
+	/// reach, so the result is the exact nearest point rather than the nearest in some neighborhood.
+	pub(crate) fn nearest(&self, p: Vec3, mut dist2: impl FnMut(usize) -> ScValue) -> Option<(usize, ScValue)> {
+		let mut reach = 1;
+		loop {
+			let (mut best, mut seen): (Option<(usize, ScValue)>, usize) = (None, 0);
+			self.for_each_candidate(p, reach, |i| {
+				seen += 1;
+				let d2 = dist2(i);
+				if best.is_none_or(|(j, b)| d2 < b || (d2 == b && i > j)) { best = Some((i, d2)); }
+			});
+			let searched = reach as ScValue * self.cell;
+			if seen == self.len || best.is_some_and(|(_, d2)| d2 <= searched * searched) { return best; }
+			reach *= 2;
+		}
+	}
+}
+
+/// Compressed sparse row adjacency: row i holds `indices[offsets[i]..offsets[i + 1]]`. Rows are
+/// appended in order, so a whole per-atom neighbor table lives in two flat allocations.
+#[derive(Clone, Debug)]
+pub(crate) struct Csr {
+	offsets: Vec<usize>,
+	indices: Vec<usize>,
+}
+
+impl Default for Csr {
+	fn default() -> Self { Self { offsets: vec![0], indices: Vec::new() } }
+}
+
+impl Csr {
+	pub(crate) fn n_rows(&self) -> usize { self.offsets.len() - 1 }
+
+	/// Entries of row `i`; empty for rows that were never pushed
+	pub(crate) fn row(&self, i: usize) -> &[usize] {
+		if i >= self.n_rows() { return &[]; }
+		&self.indices[self.offsets[i]..self.offsets[i + 1]]
+	}
+
+	/// Append an entry to the row under construction
+	pub(crate) fn push(&mut self, j: usize) { self.indices.push(j); }
+
+	/// Entries pushed since the last `end_row`
+	pub(crate) fn open_row_mut(&mut self) -> &mut [usize] {
+		let start = self.offsets[self.offsets.len() - 1];
+		&mut self.indices[start..]
+	}
+
+	pub(crate) fn end_row(&mut self) { self.offsets.push(self.indices.len()); }
+
+	/// Append the rows of `other` after this table's rows
+	#[cfg(feature = "parallel")]
+	pub(crate) fn append(&mut self, other: Csr) {
+		let base = self.indices.len();
+		self.indices.extend(other.indices);
+		self.offsets.extend(other.offsets[1..].iter().map(|o| o + base));
+	}
+}
+
+/// Expanded-sphere centers and radii of one grid cell, stored contiguously for the SIMD kernels
+#[derive(Clone, Default)]
+struct SphereBlock { x: Vec<GeomValue>, y: Vec<GeomValue>, z: Vec<GeomValue>, radius: Vec<GeomValue> }
+
+/// Largest dense box `BurialGrid::dense_bins` builds (8 bytes of ranges per cell, 128 MB)
+#[cfg(feature = "gpu")]
+const MAX_DENSE_CELLS: u64 = 1 << 24;
+
+/// One molecule's burial cells flattened for upload: `ranges` holds a `(start, count)` slice of
+/// `spheres` (center and radius) per cell of a `dims` box starting at cell `origin`, x fastest
+#[cfg(feature = "gpu")]
+pub(crate) struct DenseBins {
+	cell: ScValue,
+	origin: CellKey,
+	pub(crate) dims: [u32; 3],
+	pub(crate) ranges: Vec<[u32; 2]>,
+	pub(crate) spheres: Vec<[f32; 4]>,
+}
+
+#[cfg(feature = "gpu")]
+impl DenseBins {
+	fn index(&self, (x, y, z): CellKey) -> Option<u32> {
+		let local = [x - self.origin.0, y - self.origin.1, z - self.origin.2];
+		if local.iter().zip(self.dims).any(|(&v, d)| v < 0 || v >= d as i64) { return None; }
+		Some((local[0] + self.dims[0] as i64 * (local[1] + self.dims[1] as i64 * local[2])) as u32)
+	}
+
+	/// Index of the cell holding `p`, computed in f64 as on the CPU; `u32::MAX` outside the box,
+	/// where nothing can bury it
+	pub(crate) fn cell_of(&self, p: Vec3) -> u32 { self.index(cell_key(p, self.cell)).unwrap_or(u32::MAX) }
+}
+
+/// Atoms of each molecule (context atoms aside) binned by cell, for testing whether a probe position
+/// is buried by the opposite molecule. The cell edge must bound every expanded radius (radius + rp).
+#[derive(Clone, Default)]
+pub(crate) struct BurialGrid {
+	cell: ScValue,
+	blocks: [HashMap<CellKey, SphereBlock>; 2],
+}
+
+impl BurialGrid {
+	pub(crate) fn new(atoms: &AtomArrays, cell: ScValue) -> Self {
+		let cell = cell.max(1e-6);
+		let mut blocks: [HashMap<CellKey, SphereBlock>; 2] = Default::default();
+		for i in (0..atoms.len()).filter(|&i| !atoms.context[i]) {
+			let b = blocks[atoms.molecule[i].min(1)].entry(cell_key(atoms.coor(i), cell)).or_default();
+			b.x.push(atoms.x[i]);
+			b.y.push(atoms.y[i]);
+			b.z.push(atoms.z[i]);
+			b.radius.push(atoms.radius[i]);
+		}
+		Self { cell, blocks }
+	}
+
+	/// The cells of `molecule` as a dense box with one empty cell of margin on every side, so any
+	/// point that can be buried falls inside it; None for a box over `MAX_DENSE_CELLS` cells
+	#[cfg(feature = "gpu")]
+	// GeomValue is already f32 under the `f32` feature
+	#[allow(clippy::unnecessary_cast)]
+	pub(crate) fn dense_bins(&self, molecule: usize) -> Option<DenseBins> {
+		let blocks = &self.blocks[molecule];
+		if blocks.is_empty() { return Some(DenseBins { cell: self.cell, origin: (0, 0, 0), dims: [0; 3], ranges: Vec::new(), spheres: Vec::new() }); }
+		let (mut lo, mut hi) = ([i64::MAX; 3], [i64::MIN; 3]);
+		for &(x, y, z) in blocks.keys() {
+			for (axis, v) in [x, y, z].into_iter().enumerate() { lo[axis] = lo[axis].min(v - 1); hi[axis] = hi[axis].max(v + 1); }
+		}
+		let dims = [0, 1, 2].map(|axis| (hi[axis] - lo[axis] + 1) as u64);
+		if dims.iter().product::<u64>() > MAX_DENSE_CELLS { return None; }
+		let dims = dims.map(|d| d as u32);
+		let mut bins = DenseBins { cell: self.cell, origin: (lo[0], lo[1], lo[2]), dims, ranges: vec![[0, 0]; dims.iter().product::<u32>() as usize], spheres: Vec::new() };
+		for (&key, b) in blocks {
+			let Some(index) = bins.index(key) else { continue };
+			bins.ranges[index as usize] = [bins.spheres.len() as u32, b.x.len() as u32];
+			for k in 0..b.x.len() { bins.spheres.push([b.x[k] as f32, b.y[k] as f32, b.z[k] as f32, b.radius[k] as f32]); }
+		}
+		Some(bins)
+	}
+
+	/// Whether `p` lies within radius + `rp` of any atom of `molecule`
+	pub(crate) fn is_buried_by(&self, molecule: usize, p: Vec3, rp: ScValue) -> bool {
+		// The empty default grid (deferred GPU burial) has no cell size to key by
+		if self.blocks[molecule].is_empty() { return false; }
+		let (cx, cy, cz) = cell_key(p, self.cell);
+		let q = [p.x as GeomValue, p.y as GeomValue, p.z as GeomValue];
+		for dx in -1..=1 { for dy in -1..=1 { for dz in -1..=1 {
+			let Some(b) = self.blocks[molecule].get(&(cx + dx, cy + dy, cz + dz)) else { continue };
+			if simd::any_within(&b.x, &b.y, &b.z, &b.radius, q, rp as GeomValue) { return true; }
+		}}}
+		false
+	}
+}
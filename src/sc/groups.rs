@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use crate::sc::grid::SpatialGrid;
+use crate::sc::sc_calculator::ScCalculator;
+use crate::sc::settings::Settings;
+use crate::sc::surface_generator::{SurfaceGenerator, SurfaceCalculatorError};
+use crate::sc::types::*;
+
+/// Named set of atoms taking part in a multi-entity analysis (e.g. one chain of a trimer)
+#[derive(Clone, Debug)]
+pub struct AtomGroup {
+	pub name: String,
+	/// Atoms with radii already assigned
+	pub atoms: Vec<Atom>,
+}
+
+/// Sc between one pair of groups
+#[derive(Clone, Debug)]
+pub struct GroupPairSc {
+	pub group1: usize,
+	pub group2: usize,
+	pub results: Results,
+}
+
+/// Shape complementarity between any pair of N atom groups. Radii are assigned once per group when
+/// it is added and pair results are cached, so requesting A–B, B–C and A–C from a trimer assigns
+/// each chain's radii once. The complete surface of a group scored as molecule 1 is frozen and
+/// reused for each of its partners (see `SurfaceGenerator::freeze_molecule1`); only the partner's
+/// surface and the burial of the frozen dots are built per pair.
+#[derive(Default)]
+pub struct GroupScCalculator {
+	settings: Settings,
+	radii: Vec<AtomRadius>,
+	groups: Vec<AtomGroup>,
+	cache: BTreeMap<(usize, usize), Results>,
+	/// Calculator per group that has been molecule 1, holding its frozen surface
+	frozen: BTreeMap<usize, ScCalculator>,
+}
+
+impl GroupScCalculator {
+	pub fn new() -> Self { Self::default() }
+	pub fn settings(&self) -> &Settings { &self.settings }
+	pub fn settings_mut(&mut self) -> &mut Settings { self.clear_surfaces(); &mut self.settings }
+	pub fn set_radii(&mut self, radii: Vec<AtomRadius>) { self.radii = radii; self.clear_surfaces(); }
+	pub fn groups(&self) -> &[AtomGroup] { &self.groups }
+
+	/// Add a group of atoms, assigning radii where missing; returns the group index
+	pub fn add_group(&mut self, name: &str, atoms: Vec<Atom>) -> Result<usize, SurfaceCalculatorError> {
+		let mut generator = SurfaceGenerator::new();
+		generator.settings = self.settings.clone();
+		generator.set_radii(self.radii.clone());
+		generator.init()?;
+		generator.add_atoms(0, atoms)?;
+		self.groups.push(AtomGroup { name: name.to_string(), atoms: std::mem::take(&mut generator.run.atoms) });
+		Ok(self.groups.len() - 1)
+	}
+
+	/// Sc between groups `i` (molecule 1) and `j` (molecule 2); cached until settings or radii change.
+	/// Group `i`'s surface is frozen on first use and shared by all its pairs.
+	pub fn pair_sc(&mut self, i: usize, j: usize) -> Result<Results, SurfaceCalculatorError> {
+		if i >= self.groups.len() || j >= self.groups.len() || i == j { return Err(SurfaceCalculatorError::InvalidGroup(i, j)); }
+		if let Some(r) = self.cache.get(&(i, j)) { return Ok(r.clone()); }
+		if !self.frozen.contains_key(&i) {
+			let mut sc = ScCalculator::new();
+			*sc.settings_mut() = self.settings.clone();
+			sc.set_radii(self.radii.clone());
+			sc.add_atoms(0, self.groups[i].atoms.iter().cloned())?;
+			sc.freeze_molecule1()?;
+			self.frozen.insert(i, sc);
+		}
+		let sc = self.frozen.get_mut(&i).expect("frozen above");
+		let results = sc.score_molecule2(self.groups[j].atoms.clone())?;
+		self.cache.insert((i, j), results.clone());
+		Ok(results)
+	}
+
+	/// Drop cached results and frozen surfaces, which were built under the old settings or radii
+	fn clear_surfaces(&mut self) {
+		self.cache.clear();
+		self.frozen.clear();
+	}
+
+	/// Whether any atom of group `i` lies within probe-bridging distance of an atom of group `j`
+	pub fn in_contact(&self, i: usize, j: usize) -> bool {
+		let (a, b) = (&self.groups[i].atoms, &self.groups[j].atoms);
+		let bridge = 2.0 * self.settings.max_probe_radius();
+		let radmax = a.iter().chain(b).map(|atom| atom.radius).fold(0.0, ScValue::max);
+		// Every contact is shorter than one cell, so each atom of `i` checks its neighboring cells only
+		let grid = SpatialGrid::new(b.iter().map(|atom| atom.coor), 2.0 * radmax + bridge);
+		let mut candidates = Vec::new();
+		a.iter().any(|atom| {
+			grid.candidates_into(atom.coor, 1, &mut candidates);
+			candidates.iter().any(|&k| {
+				let reach = atom.radius + b[k].radius + bridge;
+				atom.distance_squared(&b[k]) <= reach * reach
+			})
+		})
+	}
+
+	/// Sc for every pair of groups in contact (i < j), in index order
+	pub fn all_pairs(&mut self) -> Result<Vec<GroupPairSc>, SurfaceCalculatorError> {
+		let mut out = Vec::new();
+		for i in 0..self.groups.len() {
+			for j in (i + 1)..self.groups.len() {
+				if !self.in_contact(i, j) { continue; }
+				out.push(GroupPairSc { group1: i, group2: j, results: self.pair_sc(i, j)? });
+			}
+		}
+		Ok(out)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sc::sc_calculator::tests::complex;
+
+	#[test]
+	fn pair_sc_reuses_frozen_surface() {
+		let (mol1, mol2) = complex();
+		let mut sc = ScCalculator::new();
+		sc.add_atoms(0, mol1.clone()).unwrap();
+		sc.add_atoms(1, mol2.clone()).unwrap();
+		let direct = sc.calc().unwrap();
+		let mut groups = GroupScCalculator::new();
+		let a = groups.add_group("A", mol1).unwrap();
+		let b = groups.add_group("B", mol2).unwrap();
+		let pair = groups.pair_sc(a, b).unwrap();
+		assert!(groups.frozen.contains_key(&a));
+		assert!((pair.sc - direct.sc).abs() < 0.01, "{} vs {}", pair.sc, direct.sc);
+		assert!(matches!(groups.pair_sc(a, a), Err(SurfaceCalculatorError::InvalidGroup(0, 0))));
+		assert!(matches!(groups.pair_sc(a, 2), Err(SurfaceCalculatorError::InvalidGroup(0, 2))));
+	}
+
+	#[test]
+	fn in_contact_finds_only_touching_groups() {
+		let (mol1, mol2) = complex();
+		let mut far = mol2.clone();
+		for a in &mut far { a.coor.x += 6.0; }
+		let mut groups = GroupScCalculator::new();
+		let a = groups.add_group("A", mol1).unwrap();
+		let b = groups.add_group("B", mol2).unwrap();
+		let c = groups.add_group("C", far).unwrap();
+		assert!(groups.in_contact(a, b) && groups.in_contact(b, a));
+		assert!(!groups.in_contact(a, c) && !groups.in_contact(c, a));
+		assert!(groups.in_contact(b, c));
+	}
+}
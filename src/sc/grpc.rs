@@ -0,0 +1,143 @@
+// gRPC service for high-throughput pipelines: one long-lived server answers unary and streaming
+// batch requests, so callers skip per-job process startup. The messages, service trait and server
+// are generated from proto/sc.proto by build.rs.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::sc::sc_calculator::ScCalculator;
+use crate::sc::surface_generator::SurfaceCalculatorError;
+use crate::sc::vector3::Vec3;
+
+mod generated {
+	tonic::include_proto!("sc");
+}
+
+pub use generated::sc_service_server::{ScService, ScServiceServer};
+pub use generated::{Atom, ComputeRequest, ComputeResponse, Settings};
+
+impl From<&Atom> for crate::sc::types::Atom {
+	fn from(a: &Atom) -> Self {
+		let mut atom = crate::sc::types::Atom::new();
+		atom.atom = a.atom.clone();
+		atom.residue = a.residue.clone();
+		atom.chain = a.chain.clone();
+		atom.resnum = a.resnum;
+		atom.coor = Vec3::new(a.x, a.y, a.z);
+		atom.radius = a.radius.max(0.0);
+		atom
+	}
+}
+
+/// Run one job on `sc`, which keeps its radii table and buffers between jobs
+fn compute(sc: &mut ScCalculator, request: &ComputeRequest) -> Result<ComputeResponse, SurfaceCalculatorError> {
+	*sc.settings_mut() = crate::sc::settings::Settings::default();
+	if let Some(overrides) = &request.settings {
+		let s = sc.settings_mut();
+		if let Some(rp) = overrides.probe_radius { s.rp = rp; }
+		if let Some(density) = overrides.dot_density { s.dot_density = density; }
+		if let Some(band) = overrides.peripheral_band { s.peripheral_band = band; }
+		if let Some(cutoff) = overrides.separation_cutoff { s.separation_cutoff = cutoff; }
+		if let Some(w) = overrides.gaussian_w { s.gaussian_w = w; }
+	}
+	let mol1: Vec<_> = request.molecule1.iter().map(Into::into).collect();
+	let mol2: Vec<_> = request.molecule2.iter().map(Into::into).collect();
+	let r = sc.calc_atoms(&mol1, &mol2)?;
+	Ok(ComputeResponse { id: request.id.clone(), error: String::new(), sc: r.sc, distance: r.distance, area: r.area, bsa: r.bsa, gap_volume: r.gap_volume, gap_index: r.gap_index })
+}
+
+/// `ScService` backed by the calculator; each job runs on Tokio's blocking pool
+#[derive(Debug, Default)]
+pub struct ScGrpcService;
+
+#[tonic::async_trait]
+impl ScService for ScGrpcService {
+	async fn compute(&self, request: Request<ComputeRequest>) -> Result<Response<ComputeResponse>, Status> {
+		let request = request.into_inner();
+		let result = tokio::task::spawn_blocking(move || compute(&mut ScCalculator::new(), &request)).await
+			.map_err(|e| Status::internal(e.to_string()))?;
+		result.map(Response::new).map_err(|e| Status::invalid_argument(e.to_string()))
+	}
+
+	type ComputeBatchStream = Pin<Box<dyn Stream<Item = Result<ComputeResponse, Status>> + Send>>;
+
+	async fn compute_batch(&self, request: Request<Streaming<ComputeRequest>>) -> Result<Response<Self::ComputeBatchStream>, Status> {
+		Ok(Response::new(Box::pin(batch_responses(request.into_inner()))))
+	}
+}
+
+/// Responses to a stream of batch requests, in order. One calculator serves the whole stream; a
+/// failed job is reported in its response's `error` and the stream continues, while a transport
+/// error ends it.
+fn batch_responses(mut requests: impl Stream<Item = Result<ComputeRequest, Status>> + Send + Unpin + 'static) -> ReceiverStream<Result<ComputeResponse, Status>> {
+	let (tx, rx) = mpsc::channel(16);
+	tokio::spawn(async move {
+		let mut sc = ScCalculator::new();
+		while let Some(next) = requests.next().await {
+			let request = match next {
+				Ok(request) => request,
+				Err(status) => { let _ = tx.send(Err(status)).await; return; }
+			};
+			let job = tokio::task::spawn_blocking(move || { let result = compute(&mut sc, &request); (sc, request.id, result) });
+			let (calculator, id, result) = match job.await {
+				Ok(done) => done,
+				Err(e) => { let _ = tx.send(Err(Status::internal(e.to_string()))).await; return; }
+			};
+			sc = calculator;
+			let reply = result.unwrap_or_else(|e| ComputeResponse { id, error: e.to_string(), ..Default::default() });
+			if tx.send(Ok(reply)).await.is_err() { return; }
+		}
+	});
+	ReceiverStream::new(rx)
+}
+
+/// Serve `ScService` on `addr` until the process is stopped
+pub async fn serve(addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+	tonic::transport::Server::builder().add_service(ScServiceServer::new(ScGrpcService)).serve(addr).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sc::sc_calculator::tests::complex;
+
+	fn request(id: &str) -> ComputeRequest {
+		let (mol1, mol2) = complex();
+		let message = |a: &crate::sc::types::Atom| Atom {
+			atom: a.atom.clone(), residue: a.residue.clone(), chain: a.chain.clone(), resnum: a.resnum,
+			x: a.coor.x, y: a.coor.y, z: a.coor.z, radius: a.radius,
+		};
+		ComputeRequest { id: id.to_string(), molecule1: mol1.iter().map(message).collect(), molecule2: mol2.iter().map(message).collect(), settings: None }
+	}
+
+	#[test]
+	fn compute_matches_calculator() {
+		let (mol1, mol2) = complex();
+		let expected = ScCalculator::new().calc_atoms(&mol1, &mol2).unwrap();
+		let got = compute(&mut ScCalculator::new(), &request("pose")).unwrap();
+		assert_eq!(got.id, "pose");
+		assert!(got.error.is_empty());
+		assert!((got.sc - expected.sc).abs() < 1e-12 && (got.area - expected.area).abs() < 1e-9);
+		let mut coarse = request("coarse");
+		coarse.settings = Some(Settings { dot_density: Some(5.0), ..Default::default() });
+		assert!((compute(&mut ScCalculator::new(), &coarse).unwrap().sc - got.sc).abs() > 1e-9);
+	}
+
+	#[tokio::test]
+	async fn batch_reports_failed_jobs_and_continues() {
+		let mut empty = request("empty");
+		empty.molecule2.clear();
+		let requests = tokio_stream::iter([request("a"), empty, request("b")].map(Ok));
+		let responses: Vec<ComputeResponse> = batch_responses(requests).map(|r| r.unwrap()).collect().await;
+		assert_eq!(responses.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), ["a", "empty", "b"]);
+		assert!(responses[0].error.is_empty() && responses[2].error.is_empty());
+		assert!(!responses[1].error.is_empty());
+		assert_eq!(responses[1].sc, 0.0);
+		assert_eq!(responses[0].sc, responses[2].sc);
+	}
+}
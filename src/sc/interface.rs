@@ -0,0 +1,536 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::sc::grid::SpatialGrid;
+use crate::sc::pockets::sphere_directions;
+use crate::sc::sc_calculator::median;
+use crate::sc::settings::{Settings, WeightFunction};
+use crate::sc::surface_generator::RunState;
+use crate::sc::types::*;
+use crate::sc::vector3::Vec3;
+
+/// Map each trimmed dot's area onto the residue pair formed with its nearest partner dot,
+/// accumulating both directions (1→2 and 2→1) into one entry per residue pair.
+pub(crate) fn contact_map(run: &RunState) -> Vec<ResidueContact> {
+	let mut map: BTreeMap<(ResidueId, ResidueId), ResidueContact> = BTreeMap::new();
+	for my in 0..2 {
+		let their = 1 - my;
+		for pair in &run.pairs[my] {
+			let dot = &run.dots[my][pair.dot];
+			let partner = &run.dots[their][pair.neighbor];
+			let r_my = run.atoms[dot.atom_index].residue_id();
+			let r_their = run.atoms[partner.atom_index].residue_id();
+			let key = if my == 0 { (r_my, r_their) } else { (r_their, r_my) };
+			let entry = map.entry(key.clone()).or_insert_with(|| ResidueContact {
+				residue1: key.0, residue2: key.1, area: 0.0, min_distance: f64::INFINITY, n_dots: 0,
+			});
+			entry.area += dot.area;
+			entry.n_dots += 1;
+			if pair.distance < entry.min_distance { entry.min_distance = pair.distance; }
+		}
+	}
+	map.into_values().collect()
+}
+
+/// Collect, per molecule, the residues owning buried dots with their buried and trimmed areas and
+/// the median S of their paired dots.
+pub(crate) fn interface_residues(run: &RunState, molecule: usize) -> Vec<InterfaceResidue> {
+	let mut map: BTreeMap<ResidueId, InterfaceResidue> = BTreeMap::new();
+	for dot in run.dots[molecule].iter().filter(|d| d.buried) {
+		let id = run.atoms[dot.atom_index].residue_id();
+		let entry = map.entry(id.clone()).or_insert_with(|| InterfaceResidue { residue: id, buried_area: 0.0, n_buried_dots: 0, trimmed_area: 0.0, sc: None });
+		entry.buried_area += dot.area;
+		entry.n_buried_dots += 1;
+	}
+	for &idx in &run.trimmed_dots[molecule] {
+		let dot = &run.dots[molecule][idx];
+		if let Some(entry) = map.get_mut(&run.atoms[dot.atom_index].residue_id()) { entry.trimmed_area += dot.area; }
+	}
+	let mut scores: HashMap<ResidueId, Vec<ScValue>> = HashMap::new();
+	for pair in &run.pairs[molecule] {
+		scores.entry(run.atoms[run.dots[molecule][pair.dot].atom_index].residue_id()).or_default().push(pair.score);
+	}
+	for (id, mut s) in scores {
+		if let Some(entry) = map.get_mut(&id) { entry.sc = Some(median(&mut s)); }
+	}
+	map.into_values().collect()
+}
+
+/// Break the statistics of `molecule` down by chain: atom counts, the chain's trimmed dots and the
+/// medians of their pairings with the other surface
+pub(crate) fn chain_stats(run: &RunState, molecule: usize) -> Vec<ChainStats> {
+	let mut map: BTreeMap<&str, ChainStats> = BTreeMap::new();
+	for a in run.atoms.iter().filter(|a| a.molecule == molecule && !a.context) {
+		let entry = map.entry(a.chain.as_str()).or_insert_with(|| ChainStats { chain: a.chain.clone(), ..Default::default() });
+		entry.n_atoms += 1;
+		if a.attention == Attention::Buried { entry.n_buried_atoms += 1; }
+	}
+	for &idx in &run.trimmed_dots[molecule] {
+		let dot = &run.dots[molecule][idx];
+		if let Some(entry) = map.get_mut(run.atoms[dot.atom_index].chain.as_str()) { entry.trimmed_area += dot.area; entry.n_trimmed_dots += 1; }
+	}
+	let mut pairs: HashMap<&str, (Vec<ScValue>, Vec<ScValue>)> = HashMap::new();
+	for pair in &run.pairs[molecule] {
+		let (distances, scores) = pairs.entry(run.atoms[run.dots[molecule][pair.dot].atom_index].chain.as_str()).or_default();
+		distances.push(pair.distance);
+		scores.push(pair.score);
+	}
+	for (chain, (mut distances, mut scores)) in pairs {
+		if let Some(entry) = map.get_mut(chain) { entry.d_median = median(&mut distances); entry.s_median = median(&mut scores); }
+	}
+	map.into_values().collect()
+}
+
+/// Atoms of `molecule` owning buried dots, ordered by atom index, with their buried and trimmed areas
+pub(crate) fn interface_atoms(run: &RunState, molecule: usize) -> Vec<InterfaceAtom> {
+	let mut map: BTreeMap<usize, InterfaceAtom> = BTreeMap::new();
+	for dot in run.dots[molecule].iter().filter(|d| d.buried) {
+		let entry = map.entry(dot.atom_index).or_insert_with(|| InterfaceAtom { atom_index: dot.atom_index, buried_area: 0.0, n_buried_dots: 0, trimmed_area: 0.0, n_trimmed_dots: 0 });
+		entry.buried_area += dot.area;
+		entry.n_buried_dots += 1;
+	}
+	for &idx in &run.trimmed_dots[molecule] {
+		let dot = &run.dots[molecule][idx];
+		if let Some(entry) = map.get_mut(&dot.atom_index) { entry.trimmed_area += dot.area; entry.n_trimmed_dots += 1; }
+	}
+	map.into_values().collect()
+}
+
+/// Gap volume between the trimmed surfaces: each paired dot spans a column of its own area and
+/// height equal to the distance to its nearest partner dot; the two directions are averaged.
+pub(crate) fn gap_volume(run: &RunState) -> ScValue {
+	let mut volume = 0.0;
+	for my in 0..2 {
+		volume += run.pairs[my].iter().map(|p| run.dots[my][p.dot].area * p.distance).sum::<ScValue>();
+	}
+	volume / 2.0
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+	while parent[i] != i { parent[i] = parent[parent[i]]; i = parent[i]; }
+	i
+}
+
+/// Single-linkage clustering of points: points closer than `link` share a cluster. Points are
+/// binned into cubic cells of edge `link` so only adjacent cells are compared. Returns each
+/// point's cluster label (the smallest member index).
+pub(crate) fn cluster_points(points: &[Vec3], link: ScValue) -> Vec<usize> {
+	let cell = |p: Vec3| ((p.x / link).floor() as i64, (p.y / link).floor() as i64, (p.z / link).floor() as i64);
+	let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+	for (i, p) in points.iter().enumerate() { grid.entry(cell(*p)).or_default().push(i); }
+	let link2 = link * link;
+	let mut parent: Vec<usize> = (0..points.len()).collect();
+	for (i, p) in points.iter().enumerate() {
+		let (cx, cy, cz) = cell(*p);
+		for dx in -1..=1 { for dy in -1..=1 { for dz in -1..=1 {
+			let Some(members) = grid.get(&(cx + dx, cy + dy, cz + dz)) else { continue };
+			for &j in members {
+				if j <= i || p.distance_squared(points[j]) > link2 { continue; }
+				let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+				if ri != rj { parent[ri.max(rj)] = ri.min(rj); }
+			}
+		}}}
+	}
+	(0..points.len()).map(|i| find(&mut parent, i)).collect()
+}
+
+/// Single-linkage clustering of the buried dots of one surface into patches (dots closer than
+/// `link` share a patch), returned by decreasing area.
+pub(crate) fn buried_patches(run: &RunState, molecule: usize, link: ScValue) -> Vec<Patch> {
+	let dots: Vec<&Dot> = run.dots[molecule].iter().filter(|d| d.buried).collect();
+	if dots.is_empty() || link <= 0.0 { return Vec::new(); }
+	let labels = cluster_points(&dots.iter().map(|d| d.coor).collect::<Vec<_>>(), link);
+	let mut by_root: BTreeMap<usize, Patch> = BTreeMap::new();
+	for (d, &root) in dots.iter().zip(&labels) {
+		let patch = by_root.entry(root).or_insert_with(|| Patch { area: 0.0, n_dots: 0, centroid: Vec3::zero() });
+		patch.area += d.area;
+		patch.n_dots += 1;
+		patch.centroid += d.coor * d.area;
+	}
+	let mut patches: Vec<Patch> = by_root.into_values().map(|mut p| {
+		if p.area > 0.0 { p.centroid = p.centroid / p.area; }
+		p
+	}).collect();
+	patches.sort_by(|a, b| b.area.partial_cmp(&a.area).unwrap_or(std::cmp::Ordering::Equal));
+	patches
+}
+
+/// Enclosed voids between the trimmed surfaces: paired dots whose gap to the partner surface is at
+/// least `min_gap` are clustered across both surfaces (single linkage at `link`). Because trimmed dots
+/// lie inside the buried region, such clusters are gaps surrounded by tight contact. Each void's
+/// volume is its gap-column volume (area times gap) averaged over the two sides. Largest first.
+pub(crate) fn interface_voids(run: &RunState, min_gap: ScValue, link: ScValue) -> Vec<Void> {
+	if link <= 0.0 { return Vec::new(); }
+	let wide: Vec<(&Dot, &DotPair)> = (0..2).flat_map(|my| run.pairs[my].iter().filter(|p| p.distance >= min_gap).map(move |p| (&run.dots[my][p.dot], p))).collect();
+	if wide.is_empty() { return Vec::new(); }
+	let labels = cluster_points(&wide.iter().map(|(d, _)| d.coor).collect::<Vec<_>>(), link);
+	let mut by_root: BTreeMap<usize, Void> = BTreeMap::new();
+	for ((d, p), &root) in wide.iter().zip(&labels) {
+		let void = by_root.entry(root).or_insert_with(|| Void { volume: 0.0, area: 0.0, max_gap: 0.0, n_dots: 0, centroid: Vec3::zero() });
+		void.volume += d.area * p.distance / 2.0;
+		void.area += d.area;
+		void.max_gap = void.max_gap.max(p.distance);
+		void.n_dots += 1;
+		void.centroid += d.coor * d.area;
+	}
+	let mut voids: Vec<Void> = by_root.into_values().map(|mut v| {
+		if v.area > 0.0 { v.centroid = v.centroid / v.area; }
+		v
+	}).collect();
+	voids.sort_by(|a, b| b.volume.partial_cmp(&a.volume).unwrap_or(std::cmp::Ordering::Equal));
+	voids
+}
+
+/// Nitrogen and oxygen atoms, judged from the PDB atom name (leading digits ignored)
+fn is_polar(atom: &Atom) -> bool {
+	matches!(atom.atom.trim_start_matches(|c: char| c.is_ascii_digit()).chars().next(), Some('N') | Some('O'))
+}
+
+/// Residue pairs bridged by a water: each water within `cutoff` of a polar atom on both molecules
+/// bridges every such residue of molecule 1 with every such residue of molecule 2. Waters are
+/// not part of the surfaces; only their oxygen positions are used.
+pub(crate) fn water_bridges(atoms: &[Atom], waters: &[Atom], cutoff: ScValue) -> Vec<WaterBridge> {
+	let cutoff2 = cutoff * cutoff;
+	let mut bridges = Vec::new();
+	for water in waters {
+		let mut near: [BTreeMap<ResidueId, ScValue>; 2] = [BTreeMap::new(), BTreeMap::new()];
+		for atom in atoms.iter().filter(|a| is_polar(a)) {
+			let d2 = atom.coor.distance_squared(water.coor);
+			if d2 > cutoff2 { continue; }
+			let d = d2.sqrt();
+			let entry = near[atom.molecule.min(1)].entry(atom.residue_id()).or_insert(d);
+			if d < *entry { *entry = d; }
+		}
+		for (r1, &d1) in &near[0] {
+			for (r2, &d2) in &near[1] {
+				bridges.push(WaterBridge { water: water.residue_id(), residue1: r1.clone(), distance1: d1, residue2: r2.clone(), distance2: d2 });
+			}
+		}
+	}
+	bridges.sort_by(|a, b| (&a.residue1, &a.residue2, &a.water).cmp(&(&b.residue1, &b.residue2, &b.water)));
+	bridges
+}
+
+/// Eigen-decomposition of a symmetric 3x3 matrix by cyclic Jacobi rotations.
+/// Returns eigenvalues in decreasing order with their unit eigenvectors.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn symmetric_eigen3(m: [[ScValue; 3]; 3]) -> ([ScValue; 3], [Vec3; 3]) {
+	let mut a = m;
+	let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+	for _ in 0..50 {
+		let off = a[0][1].abs() + a[0][2].abs() + a[1][2].abs();
+		if off < 1e-12 { break; }
+		for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+			if a[p][q].abs() < 1e-15 { continue; }
+			let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+			let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+			let t = if theta == 0.0 { 1.0 } else { t };
+			let c = 1.0 / (t * t + 1.0).sqrt();
+			let s = t * c;
+			for k in 0..3 {
+				let (akp, akq) = (a[k][p], a[k][q]);
+				a[k][p] = c * akp - s * akq;
+				a[k][q] = s * akp + c * akq;
+			}
+			for k in 0..3 {
+				let (apk, aqk) = (a[p][k], a[q][k]);
+				a[p][k] = c * apk - s * aqk;
+				a[q][k] = s * apk + c * aqk;
+			}
+			for k in 0..3 {
+				let (vkp, vkq) = (v[k][p], v[k][q]);
+				v[k][p] = c * vkp - s * vkq;
+				v[k][q] = s * vkp + c * vkq;
+			}
+		}
+	}
+	let mut order = [0usize, 1, 2];
+	order.sort_by(|&i, &j| a[j][j].partial_cmp(&a[i][i]).unwrap_or(std::cmp::Ordering::Equal));
+	let values = [a[order[0]][order[0]], a[order[1]][order[1]], a[order[2]][order[2]]];
+	let vectors = order.map(|i| Vec3::new(v[0][i], v[1][i], v[2][i]).normalized());
+	(values, vectors)
+}
+
+/// Area-weighted shape descriptors of the buried dot cloud of both surfaces
+pub(crate) fn interface_geometry(run: &RunState) -> InterfaceGeometry {
+	let dots: Vec<&Dot> = run.dots.iter().flat_map(|d| d.iter()).filter(|d| d.buried).collect();
+	let total: ScValue = dots.iter().map(|d| d.area).sum();
+	if dots.is_empty() || total <= 0.0 { return InterfaceGeometry::default(); }
+	let mut centroid = Vec3::zero();
+	for d in &dots { centroid += d.coor * d.area; }
+	centroid = centroid / total;
+	let mut cov = [[0.0; 3]; 3];
+	for d in &dots {
+		let r = d.coor - centroid;
+		let c = [r.x, r.y, r.z];
+		for i in 0..3 { for j in 0..3 { cov[i][j] += d.area * c[i] * c[j]; } }
+	}
+	for row in cov.iter_mut() { for v in row.iter_mut() { *v /= total; } }
+	let (variances, axes) = symmetric_eigen3(cov);
+	let variances = variances.map(|v| v.max(0.0));
+	InterfaceGeometry {
+		centroid,
+		axes,
+		variances,
+		radius_of_gyration: variances.iter().sum::<ScValue>().sqrt(),
+		// RMS distance of the dots from their least-squares plane (normal = minor axis)
+		planarity: variances[2].sqrt(),
+	}
+}
+
+/// Angle distribution of the paired normals of both surfaces and the per-surface medians of the two
+/// factors of S: orientation −(n_A · n_B) and distance weight
+pub(crate) fn normal_angle_stats(run: &RunState, tolerance: ScValue, weight: &WeightFunction, gaussian_w: ScValue) -> NormalAngleStats {
+	let mut angles: Vec<ScValue> = run.pairs.iter().flatten().map(|p| p.normal_angle()).collect();
+	if angles.is_empty() { return NormalAngleStats::default(); }
+	let n = angles.len();
+	let mean = angles.iter().sum::<ScValue>() / n as ScValue;
+	let antiparallel = angles.iter().filter(|&&a| a >= 180.0 - tolerance).count();
+	let surfaces: Vec<&Vec<DotPair>> = run.pairs.iter().filter(|p| !p.is_empty()).collect();
+	let factor_median = |f: &dyn Fn(&DotPair) -> ScValue| {
+		surfaces.iter().map(|pairs| median(&mut pairs.iter().map(f).collect::<Vec<_>>())).sum::<ScValue>() / surfaces.len() as ScValue
+	};
+	NormalAngleStats {
+		mean,
+		median: median(&mut angles),
+		fraction_antiparallel: antiparallel as ScValue / n as ScValue,
+		orientation: factor_median(&|p| -p.normal_dot),
+		distance_weight: factor_median(&|p| weight.weight(p.distance, gaussian_w)),
+		n_pairs: n,
+	}
+}
+
+/// Volume of the lens shared by two spheres of radii `r1` and `r2` whose centers are `d` apart
+fn lens_volume(r1: ScValue, r2: ScValue, d: ScValue) -> ScValue {
+	if d >= r1 + r2 { return 0.0; }
+	if d <= (r1 - r2).abs() { return 4.0 / 3.0 * std::f64::consts::PI * r1.min(r2).powi(3); }
+	let h = r1 + r2 - d;
+	std::f64::consts::PI * h * h * (d * d + 2.0 * d * (r1 + r2) - 3.0 * (r1 - r2) * (r1 - r2)) / (12.0 * d)
+}
+
+/// Atom pairs of the two molecules overlapping by more than `tolerance` Å. Every atom is checked,
+/// including atoms outside the focus, the atom subset or the separation cutoff, which get no dots;
+/// context atoms belong to neither molecule and are left out.
+pub(crate) fn clash_report(run: &RunState, tolerance: ScValue) -> ClashReport {
+	let partners: Vec<usize> = (0..run.atoms.len()).filter(|&i| run.atoms[i].molecule == 1 && !run.atoms[i].context).collect();
+	let radmax = run.atoms.iter().map(|a| a.radius).fold(0.0, ScValue::max);
+	if partners.is_empty() || radmax <= 0.0 { return ClashReport::default(); }
+	// Overlapping spheres are closer than 2·r_max, one cell
+	let grid = SpatialGrid::new(partners.iter().map(|&j| run.atoms[j].coor), 2.0 * radmax);
+	let mut report = ClashReport::default();
+	let mut candidates = Vec::new();
+	for (i, a) in run.atoms.iter().enumerate().filter(|(_, a)| a.molecule == 0 && !a.context) {
+		grid.candidates_into(a.coor, 1, &mut candidates);
+		for &k in &candidates {
+			let (j, b) = (partners[k], &run.atoms[partners[k]]);
+			let distance = a.distance(b);
+			let overlap = a.radius + b.radius - distance;
+			if overlap <= tolerance { continue; }
+			let volume = lens_volume(a.radius, b.radius, distance);
+			report.overlap_volume += volume;
+			report.max_overlap = report.max_overlap.max(overlap);
+			report.clashes.push(Clash { atom_indices: [i, j], residue1: a.residue_id(), atom1: a.atom.clone(), residue2: b.residue_id(), atom2: b.atom.clone(), distance, overlap, volume });
+		}
+	}
+	report.clashes.sort_by(|a, b| b.overlap.total_cmp(&a.overlap).then(a.atom_indices.cmp(&b.atom_indices)));
+	report
+}
+
+/// Local Sc in slabs of `width` Å sliding by `step` Å along the interface major axis. Each slab
+/// collects the S values of paired trimmed dots whose position projects into it, per surface,
+/// and averages the two medians as for the global Sc.
+pub(crate) fn sc_profile(run: &RunState, geometry: &InterfaceGeometry, width: ScValue, step: ScValue) -> Vec<ProfileBin> {
+	if width <= 0.0 || step <= 0.0 { return Vec::new(); }
+	let axis = geometry.axes[0];
+	let projected: [Vec<(ScValue, &DotPair)>; 2] = [0, 1].map(|my| {
+		run.pairs[my].iter().map(|p| ((run.dots[my][p.dot].coor - geometry.centroid).dot(axis), p)).collect()
+	});
+	let (lo, hi) = projected.iter().flatten().fold((ScValue::INFINITY, ScValue::NEG_INFINITY), |(lo, hi), (t, _)| (lo.min(*t), hi.max(*t)));
+	if lo > hi { return Vec::new(); }
+	let mut bins = Vec::new();
+	let mut start = lo;
+	loop {
+		let end = start + width;
+		// The last slab also takes the dots at its far edge, the extreme of the interface
+		let last = end >= hi;
+		let mut medians = Vec::new();
+		let mut area = 0.0;
+		let mut n_dots = 0;
+		for (my, projected) in projected.iter().enumerate() {
+			let mut scores: Vec<ScValue> = projected.iter().filter(|(t, _)| *t >= start && (*t < end || last && *t <= end)).map(|(_, p)| {
+				area += run.dots[my][p.dot].area;
+				p.score
+			}).collect();
+			n_dots += scores.len();
+			if !scores.is_empty() { medians.push(median(&mut scores)); }
+		}
+		if !medians.is_empty() {
+			bins.push(ProfileBin { position: start + width / 2.0, sc: medians.iter().sum::<ScValue>() / medians.len() as ScValue, area, n_dots });
+		}
+		if last { break; }
+		start += step;
+	}
+	bins
+}
+
+/// Hydrophobic residues (aliphatic, aromatic and sulfur-containing) for `residue_type_sc`
+pub const HYDROPHOBIC_RESIDUES: &[&str] = &["ALA", "VAL", "LEU", "ILE", "MET", "PHE", "TRP", "PRO", "CYS"];
+/// Aromatic residues for `residue_type_sc`
+pub const AROMATIC_RESIDUES: &[&str] = &["PHE", "TYR", "TRP", "HIS"];
+
+/// (sc, distance, area, n_dots) from the pairs whose own dot passes `keep`, each surface's median
+/// taken separately and surfaces without such dots left out of the average
+fn filtered_sc(run: &RunState, keep: impl Fn(&Dot) -> bool) -> (ScValue, ScValue, ScValue, usize) {
+	let mut medians = Vec::new();
+	let mut area = 0.0;
+	let mut n_dots = 0;
+	for my in 0..2 {
+		let mut scores = Vec::new();
+		let mut distances = Vec::new();
+		for p in &run.pairs[my] {
+			let dot = &run.dots[my][p.dot];
+			if !keep(dot) { continue; }
+			scores.push(p.score);
+			distances.push(p.distance);
+			area += dot.area;
+		}
+		n_dots += scores.len();
+		if !scores.is_empty() { medians.push((median(&mut scores), median(&mut distances))); }
+	}
+	if medians.is_empty() { return (0.0, 0.0, area, n_dots); }
+	let n = medians.len() as ScValue;
+	(medians.iter().map(|m| m.0).sum::<ScValue>() / n, medians.iter().map(|m| m.1).sum::<ScValue>() / n, area, n_dots)
+}
+
+/// Sc from the pairs whose own dot is one of `kinds`
+pub(crate) fn dot_kind_sc(run: &RunState, kinds: &[DotKind]) -> DotKindSc {
+	let (sc, distance, area, n_dots) = filtered_sc(run, |dot| kinds.contains(&dot.kind));
+	DotKindSc { kinds: kinds.to_vec(), sc, distance, area, n_dots }
+}
+
+/// Sc from the pairs whose own dot belongs to an atom of one of the `residues` names (case-insensitive)
+pub(crate) fn residue_type_sc(run: &RunState, residues: &[&str]) -> ResidueTypeSc {
+	let names: Vec<String> = residues.iter().map(|r| r.trim().to_ascii_uppercase()).collect();
+	let (sc, distance, area, n_dots) = filtered_sc(run, |dot| names.iter().any(|n| run.atoms[dot.atom_index].residue.trim().eq_ignore_ascii_case(n)));
+	ResidueTypeSc { residues: names, sc, distance, area, n_dots }
+}
+
+/// Directions sampled over each atom's probe-expanded sphere to measure its accessible fraction
+const ACCESSIBILITY_DIRECTIONS: usize = 512;
+
+/// Contact dots per Å² of accessible contact area for each atom and surface. The area is measured
+/// geometrically, independently of the dots: 4πr² times the fraction of the atom's probe-expanded
+/// sphere that no same-molecule neighbor covers, sampled over `ACCESSIBILITY_DIRECTIONS` points.
+/// Every atom the last generation surfaced and that has accessible area is reported, including
+/// those that got no dots at all. Only contact dots are counted: they are sampled at their own
+/// atom's density, while reentrant dots blend the densities of their parent atoms. Atoms below
+/// `min_fraction` of their target are flagged.
+pub(crate) fn density_report(run: &RunState, settings: &Settings, min_fraction: ScValue) -> DensityReport {
+	let mut per_atom = vec![0usize; run.atoms.len()];
+	for dot in run.dots.iter().flatten().filter(|d| d.kind == DotKind::Contact) { per_atom[dot.atom_index] += 1; }
+	let directions = sphere_directions(ACCESSIBILITY_DIRECTIONS);
+	let mut report = DensityReport::default();
+	let mut weighted_target = [0.0; 2];
+	for (atom_index, atom) in run.atoms.iter().enumerate() {
+		let surfaced = match atom.attention {
+			Attention::Far => false,
+			Attention::Consider => run.buried_by.n_rows() > atom_index && !run.buried_by.row(atom_index).is_empty(),
+			Attention::Buried => true,
+		};
+		if atom.context || !surfaced || atom_index >= run.neighbors.n_rows() { continue; }
+		let rp = settings.probe_radius(atom.molecule);
+		let expanded = atom.radius + rp;
+		let neighbors = run.neighbors.row(atom_index);
+		let open = directions.iter().filter(|&&d| {
+			let p = atom.coor + d * expanded;
+			neighbors.iter().all(|&j| {
+				let reach = run.atoms[j].radius + rp;
+				p.distance_squared(run.atoms[j].coor) >= reach * reach
+			})
+		}).count();
+		if open == 0 { continue; }
+		let area = 4.0 * std::f64::consts::PI * atom.radius * atom.radius * open as ScValue / ACCESSIBILITY_DIRECTIONS as ScValue;
+		let n_dots = per_atom[atom_index];
+		let achieved = n_dots as ScValue / area;
+		let undersampled = achieved < min_fraction * atom.density;
+		let s = &mut report.surfaces[atom.molecule];
+		s.n_dots += n_dots;
+		s.area += area;
+		s.n_atoms += 1;
+		if undersampled { s.n_undersampled += 1; }
+		weighted_target[atom.molecule] += atom.density * area;
+		report.atoms.push(AtomDensity { atom_index, molecule: atom.molecule, target: atom.density, achieved, n_dots, area, undersampled });
+	}
+	for (s, weighted) in report.surfaces.iter_mut().zip(weighted_target) {
+		if s.area > 0.0 { s.target = weighted / s.area; s.achieved = s.n_dots as ScValue / s.area; }
+	}
+	report
+}
+
+/// Residues of one molecule in input order, without repeats
+fn residue_sequence(run: &RunState, molecule: usize) -> Vec<ResidueId> {
+	let mut seq: Vec<ResidueId> = Vec::new();
+	for atom in run.atoms.iter().filter(|a| a.molecule == molecule && !a.context) {
+		let id = atom.residue_id();
+		if seq.last() != Some(&id) { seq.push(id); }
+	}
+	seq
+}
+
+/// Compare the two half-interfaces of a homodimer using the per-surface statistics and
+/// interface residues already stored in `results`.
+pub(crate) fn symmetry_stats(run: &RunState, results: &Results) -> SymmetryStats {
+	let seqs = [residue_sequence(run, 0), residue_sequence(run, 1)];
+	let equivalent = seqs[0].len() == seqs[1].len() && seqs[0].iter().zip(&seqs[1]).all(|(a, b)| a.residue == b.residue);
+	let matched: Vec<(&ResidueId, &ResidueId)> = if equivalent {
+		seqs[0].iter().zip(&seqs[1]).collect()
+	} else {
+		let by_number: HashMap<(i32, Option<char>, &str), &ResidueId> = seqs[1].iter().map(|r| ((r.resnum, r.icode, r.residue.as_str()), r)).collect();
+		seqs[0].iter().filter_map(|r| by_number.get(&(r.resnum, r.icode, r.residue.as_str())).map(|m| (r, *m))).collect()
+	};
+	let buried = [0, 1].map(|mol| results.interface_residues[mol].iter().map(|r| (&r.residue, r.buried_area)).collect::<HashMap<&ResidueId, ScValue>>());
+	let (mut sum2, mut n) = (0.0, 0usize);
+	for (a, b) in &matched {
+		let (x, y) = (buried[0].get(a).copied(), buried[1].get(b).copied());
+		if x.is_none() && y.is_none() { continue; }
+		let d = x.unwrap_or(0.0) - y.unwrap_or(0.0);
+		sum2 += d * d;
+		n += 1;
+	}
+	let surfaces = &results.surfaces;
+	let sc = results.statistics.first().map(|s| s.surfaces).unwrap_or([surfaces[0].s_median, surfaces[1].s_median]);
+	SymmetryStats {
+		equivalent,
+		n_matched_residues: matched.len(),
+		sc: (sc[0] + sc[1]) / 2.0,
+		sc_deviation: (sc[0] - sc[1]).abs(),
+		distance: (surfaces[0].d_median + surfaces[1].d_median) / 2.0,
+		distance_deviation: (surfaces[0].d_median - surfaces[1].d_median).abs(),
+		half_area: (surfaces[0].trimmed_area + surfaces[1].trimmed_area) / 2.0,
+		area_deviation: (surfaces[0].trimmed_area - surfaces[1].trimmed_area).abs(),
+		half_bsa: (surfaces[0].bsa + surfaces[1].bsa) / 2.0,
+		bsa_deviation: (surfaces[0].bsa - surfaces[1].bsa).abs(),
+		residue_area_rmsd: if n > 0 { (sum2 / n as ScValue).sqrt() } else { 0.0 },
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lens_volume_limits() {
+		let sphere = |r: ScValue| 4.0 / 3.0 * std::f64::consts::PI * r.powi(3);
+		// Apart or touching: nothing shared
+		assert_eq!(lens_volume(1.8, 1.5, 3.3), 0.0);
+		assert_eq!(lens_volume(1.8, 1.5, 5.0), 0.0);
+		assert!(lens_volume(1.8, 1.5, 3.3 - 1e-6) < 1e-9);
+		// Nested or concentric: the smaller sphere
+		assert_eq!(lens_volume(1.8, 1.5, 0.0), sphere(1.5));
+		assert_eq!(lens_volume(1.5, 1.8, 0.2), sphere(1.5));
+		assert!((lens_volume(1.8, 1.5, 0.3 + 1e-9) - sphere(1.5)).abs() < 1e-6);
+		// Equal radii: π(4r + d)(2r − d)²/12
+		let (r, d) = (1.7, 2.1);
+		let expected = std::f64::consts::PI * (4.0 * r + d) * (2.0 * r - d).powi(2) / 12.0;
+		assert!((lens_volume(r, r, d) - expected).abs() < 1e-12);
+	}
+}
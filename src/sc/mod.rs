@@ -4,7 +4,44 @@ pub mod settings;
 pub mod atomic_radii;
 pub mod surface_generator;
 pub mod sc_calculator;
+pub mod interface;
+pub mod sasa;
+pub mod pockets;
+pub mod volume;
+pub mod regions;
+pub mod weights;
+pub mod electrostatics;
+pub mod curvature;
+pub mod zernike;
+pub mod groups;
+pub mod pdb;
+pub mod builder;
+pub mod view;
+pub(crate) mod grid;
+pub(crate) mod par;
+pub(crate) mod simd;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "pdbtbx")]
+pub mod pdbtbx;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 
 pub use sc_calculator::ScCalculator;
-pub use settings::Settings;
-pub use types::{Atom, Dot, Probe, Results, SurfaceStats};
+pub use builder::ScCalculatorBuilder;
+pub use groups::{AtomGroup, GroupPairSc, GroupScCalculator};
+pub use regions::{Region, RegionSc, ResidueRange};
+pub use weights::{AtomWeight, WeightedSc};
+pub use sasa::{sasa, SasaResult};
+pub use pockets::{pockets, Pocket, PocketSettings};
+pub use volume::excluded_volume;
+pub use zernike::{zernike_descriptor, ZernikeDescriptor};
+pub use surface_generator::{DotVisitor, SurfaceCalculatorError};
+pub use settings::{AdaptiveDensity, AtomSubset, Focus, FocusCenter, Phase, Progress, RadiiSource, ScStatistic, Settings, TrimMode, WeightFunction};
+pub use vector3::RigidTransform;
+pub use view::{DotFilter, DotView, ProbeSphere};
+pub use types::{Atom, AtomDensity, Attention, ChainStats, Clash, ClashReport, Convergence, CurvatureStats, DensityPoint, DensityReport, DirectionalSc, Dot, DotKind, DotKindSc, DotPair, InterfaceAtom, InterfaceGeometry, InterfaceResidue, NormalAngleStats, Patch, Prescreen, Probe, ProbeRadiusPoint, ProfileBin, ResidueContact, ResidueId, ResidueTypeSc, Results, SkippedAtom, StatisticValue, SurfaceDensity, SurfaceStats, SweepPoint, SymmetryStats, Timings, Void, Warning, WarningKind, WaterBridge};
@@ -0,0 +1,51 @@
+//! Rayon with the `parallel` feature; without it, serial stand-ins under the same names so the
+//! parallel branches compile unchanged and run on the calling thread.
+
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::prelude::*;
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::{ThreadPool, ThreadPoolBuilder};
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) use serial::*;
+
+#[cfg(not(feature = "parallel"))]
+mod serial {
+	/// Never built; `SurfaceGenerator::thread_pool` always returns None
+	pub(crate) struct ThreadPool;
+
+	impl ThreadPool {
+		pub(crate) fn install<R>(&self, f: impl FnOnce() -> R) -> R { f() }
+	}
+
+	pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+		fn into_par_iter(self) -> Self::IntoIter { self.into_iter() }
+	}
+	impl<I: IntoIterator> IntoParallelIterator for I {}
+
+	pub(crate) trait ParallelSlice<T> {
+		fn par_iter(&self) -> std::slice::Iter<'_, T>;
+	}
+	impl<T> ParallelSlice<T> for [T] {
+		fn par_iter(&self) -> std::slice::Iter<'_, T> { self.iter() }
+	}
+
+	pub(crate) trait ParallelSliceMut<T> {
+		fn par_iter_mut(&mut self) -> std::slice::IterMut<'_, T>;
+		fn par_sort_by_key<K: Ord>(&mut self, f: impl FnMut(&T) -> K);
+	}
+	impl<T> ParallelSliceMut<T> for [T] {
+		fn par_iter_mut(&mut self) -> std::slice::IterMut<'_, T> { self.iter_mut() }
+		fn par_sort_by_key<K: Ord>(&mut self, f: impl FnMut(&T) -> K) { self.sort_by_key(f) }
+	}
+
+	pub(crate) trait ParallelIterator: Iterator + Sized {
+		fn with_max_len(self, _max: usize) -> Self { self }
+		/// One state from `init` shared by every item
+		fn map_init<S, R>(self, init: impl FnOnce() -> S, mut f: impl FnMut(&mut S, Self::Item) -> R) -> impl Iterator<Item = R> {
+			let mut state = init();
+			self.map(move |item| f(&mut state, item))
+		}
+	}
+	impl<I: Iterator> ParallelIterator for I {}
+}
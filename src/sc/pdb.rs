@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+
+use crate::sc::types::{Atom, ResidueId, ScValue, Warning, WarningKind};
+use crate::sc::vector3::Vec3;
+
+/// Whether `chain` is named by `selection`: one chain ID, or several separated by commas (`H,L`)
+pub fn chain_selected(selection: &str, chain: &str) -> bool {
+	selection == chain || (selection.contains(',') && selection.split(',').any(|s| s.trim() == chain.trim()))
+}
+
+/// Split atoms from any source (another parser, a trajectory frame) into two chain selections by
+/// `Atom::chain`, dropping the rest
+pub fn split_by_chain(atoms: impl IntoIterator<Item = Atom>, selection1: &str, selection2: &str) -> (Vec<Atom>, Vec<Atom>) {
+	let mut mol1 = Vec::new();
+	let mut mol2 = Vec::new();
+	for a in atoms {
+		if chain_selected(selection1, &a.chain) { mol1.push(a); }
+		else if chain_selected(selection2, &a.chain) { mol2.push(a); }
+	}
+	(mol1, mol2)
+}
+
+/// Records a reader left out, tallied per selection into `Results::warnings` entries; records of
+/// chains in neither selection are not reported
+fn selection_warnings(dropped: &[(WarningKind, Atom)], selection1: &str, selection2: &str) -> Vec<Warning> {
+	let mut warnings = Vec::new();
+	for (kind, a) in dropped {
+		let molecule = if chain_selected(selection1, &a.chain) { 0 } else if chain_selected(selection2, &a.chain) { 1 } else { continue };
+		Warning::tally(&mut warnings, *kind, Some(molecule), || a.label());
+	}
+	warnings
+}
+
+/// Trimmed fixed-width field `range` of a PDB record, None when the line is too short or it is blank
+fn column(l: &str, range: std::ops::Range<usize>) -> Option<&str> {
+	l.get(range.start..range.end.min(l.len())).map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Occupancy, B-factor, segment ID and formal charge (`2+`, `1-`) columns of a PDB record
+fn read_pdb_metadata(l: &str, a: &mut Atom) {
+	a.occupancy = column(l, 54..60).and_then(|s| s.parse().ok());
+	a.bfactor = column(l, 60..66).and_then(|s| s.parse().ok());
+	a.segid = column(l, 72..76).map(str::to_string);
+	a.formal_charge = column(l, 78..80).and_then(|s| {
+		let (magnitude, sign) = if s.len() > 1 { s.split_at(s.len() - 1) } else { (s, "+") };
+		let q: i32 = magnitude.parse().ok()?;
+		match sign { "+" => Some(q), "-" => Some(-q), _ => None }
+	});
+}
+
+fn read_to_string(path: &str) -> io::Result<String> {
+	let mut f = File::open(path)?;
+	let mut buf = String::new();
+	f.read_to_string(&mut buf)?;
+	Ok(buf)
+}
+
+pub fn read_pdb_atoms_from_path(path: &str, selection1: &str, selection2: &str) -> io::Result<(Vec<Atom>, Vec<Atom>)> {
+	Ok(read_pdb_atoms_from_str(&read_to_string(path)?, selection1, selection2))
+}
+
+/// Heavy atoms of standard `ATOM` records, split into the two chain selections. `HETATM` records
+/// (ions, solvent, ligands), hydrogens and alternate locations other than ' ' or 'A' are skipped.
+/// Occupancy, B-factor, segment ID and formal charge are kept when their columns are present.
+pub fn read_pdb_atoms_from_str(data: &str, selection1: &str, selection2: &str) -> (Vec<Atom>, Vec<Atom>) {
+	split_by_chain(read_pdb_all_atoms_from_str(data), selection1, selection2)
+}
+
+/// As `read_pdb_atoms_from_str`, also reporting the skipped records of the two selections
+pub fn read_pdb_atoms_with_warnings_from_str(data: &str, selection1: &str, selection2: &str) -> (Vec<Atom>, Vec<Atom>, Vec<Warning>) {
+	let (atoms, dropped) = parse_pdb(data);
+	let (mol1, mol2) = split_by_chain(atoms, selection1, selection2);
+	(mol1, mol2, selection_warnings(&dropped, selection1, selection2))
+}
+
+/// Heavy atoms of every chain, in file order, filtered as in `read_pdb_atoms_from_str`
+pub fn read_pdb_all_atoms_from_str(data: &str) -> Vec<Atom> { parse_pdb(data).0 }
+
+/// Kept atoms and the records left out with the reason
+fn parse_pdb(data: &str) -> (Vec<Atom>, Vec<(WarningKind, Atom)>) {
+	let mut atoms = Vec::new();
+	let mut dropped = Vec::new();
+	for l in data.lines() {
+		let het = l.starts_with("HETATM");
+		if !(het || l.starts_with("ATOM")) || l.len() < 54 { continue; }
+		let atom_name = l[12..16].trim().to_string();
+		let element = if l.len() >= 78 { l[76..78].trim().to_string() } else { String::new() };
+		let chain_id = l[21..22].to_string();
+		let mut a = Atom::new();
+		a.coor = Vec3::new(l[30..38].trim().parse().unwrap_or(0.0), l[38..46].trim().parse().unwrap_or(0.0), l[46..54].trim().parse().unwrap_or(0.0));
+		a.residue = l[17..20].trim().to_string();
+		a.chain = chain_id;
+		a.resnum = l[22..26].trim().parse().unwrap_or(0);
+		a.icode = l[26..27].chars().next().filter(|c| *c != ' ');
+		let alt = l[16..17].chars().next().unwrap_or(' ');
+		let hydrogen = element.eq_ignore_ascii_case("H") || atom_name.starts_with('H') || atom_name.ends_with('H') || atom_name.contains("H") && atom_name.chars().next().unwrap_or(' ').is_ascii_digit();
+		a.atom = atom_name;
+		let skipped = if het { Some(WarningKind::DroppedHetatm) }
+			else if alt != ' ' && alt != 'A' { Some(WarningKind::SkippedAltloc) }
+			else if hydrogen { Some(WarningKind::HydrogenRemoved) }
+			else { None };
+		if let Some(kind) = skipped { dropped.push((kind, a)); continue; }
+		read_pdb_metadata(l, &mut a);
+		atoms.push(a);
+	}
+	(atoms, dropped)
+}
+
+/// Copy of the PDB text `data` with the B-factor column of every `ATOM`/`HETATM` record set to its
+/// residue's entry in `values` (e.g. local Sc or buried area per `InterfaceResidue`), or to
+/// `default` for residues without one, for coloring by B-factor in a viewer. Other records are kept.
+pub fn write_pdb_bfactors(data: &str, values: &HashMap<ResidueId, ScValue>, default: ScValue) -> String {
+	let mut out = String::with_capacity(data.len());
+	for l in data.lines() {
+		if (l.starts_with("ATOM") || l.starts_with("HETATM")) && l.len() >= 54 && l.is_ascii() {
+			let id = ResidueId {
+				chain: l[21..22].to_string(),
+				resnum: l[22..26].trim().parse().unwrap_or(0),
+				icode: l[26..27].chars().next().filter(|c| *c != ' '),
+				residue: l[17..20].trim().to_string(),
+			};
+			let value = values.get(&id).copied().unwrap_or(default);
+			out.push_str(&format!("{:<60}{:>6.2}", &l[..l.len().min(60)], value));
+			if l.len() > 66 { out.push_str(&l[66..]); }
+		} else {
+			out.push_str(l);
+		}
+		out.push('\n');
+	}
+	out
+}
+
+pub fn read_pdb_waters_from_path(path: &str) -> io::Result<Vec<Atom>> { Ok(read_pdb_waters_from_str(&read_to_string(path)?)) }
+
+/// Crystallographic water oxygens (HOH/WAT/DOD ATOM or HETATM records) from any chain
+pub fn read_pdb_waters_from_str(data: &str) -> Vec<Atom> {
+	let mut waters = Vec::new();
+	for l in data.lines() {
+		if !(l.starts_with("HETATM") || l.starts_with("ATOM")) || l.len() < 54 { continue; }
+		let res_name = l[17..20].trim();
+		if !matches!(res_name, "HOH" | "WAT" | "DOD") || !l[12..16].trim().starts_with('O') { continue; }
+		let mut w = Atom::new();
+		w.atom = l[12..16].trim().to_string();
+		w.residue = res_name.to_string();
+		w.chain = l[21..22].to_string();
+		w.resnum = l[22..26].trim().parse().unwrap_or(0);
+		w.icode = l[26..27].chars().next().filter(|c| *c != ' ');
+		w.coor = Vec3::new(l[30..38].trim().parse().unwrap_or(0.0), l[38..46].trim().parse().unwrap_or(0.0), l[46..54].trim().parse().unwrap_or(0.0));
+		read_pdb_metadata(l, &mut w);
+		waters.push(w);
+	}
+	waters
+}
+
+pub fn read_pqr_atoms_from_path(path: &str, selection1: &str, selection2: &str) -> io::Result<(Vec<Atom>, Vec<Atom>)> {
+	Ok(read_pqr_atoms_from_str(&read_to_string(path)?, selection1, selection2))
+}
+
+/// PQR records are whitespace-delimited: ATOM serial name resName [chain] resSeq x y z charge radius.
+/// The charge and radius columns fill `Atom::charge` and `Atom::atom_type_radius`.
+pub fn read_pqr_atoms_from_str(data: &str, selection1: &str, selection2: &str) -> (Vec<Atom>, Vec<Atom>) {
+	split_by_chain(read_pqr_all_atoms_from_str(data), selection1, selection2)
+}
+
+/// As `read_pqr_atoms_from_str`, also reporting the skipped records of the two selections
+pub fn read_pqr_atoms_with_warnings_from_str(data: &str, selection1: &str, selection2: &str) -> (Vec<Atom>, Vec<Atom>, Vec<Warning>) {
+	let (atoms, dropped) = parse_pqr(data);
+	let (mol1, mol2) = split_by_chain(atoms, selection1, selection2);
+	(mol1, mol2, selection_warnings(&dropped, selection1, selection2))
+}
+
+/// Heavy atoms of every chain of a PQR file, in file order
+pub fn read_pqr_all_atoms_from_str(data: &str) -> Vec<Atom> { parse_pqr(data).0 }
+
+/// Kept atoms and the records left out (hydrogens, `HETATM` records) with the reason
+fn parse_pqr(data: &str) -> (Vec<Atom>, Vec<(WarningKind, Atom)>) {
+	let mut atoms = Vec::new();
+	let mut dropped = Vec::new();
+	for l in data.lines() {
+		let het = l.starts_with("HETATM");
+		if !(het || l.starts_with("ATOM")) { continue; }
+		let f: Vec<&str> = l.split_whitespace().collect();
+		let (chain_id, rest) = match f.len() {
+			11 => (f[4], &f[5..]),
+			10 => (" ", &f[4..]),
+			_ => continue,
+		};
+		let atom_name = f[2].to_string();
+		let hydrogen = atom_name.starts_with('H') || atom_name.chars().next().unwrap_or(' ').is_ascii_digit() && atom_name.contains('H');
+		let digits_end = rest[0].find(|c: char| !(c.is_ascii_digit() || c == '-')).unwrap_or(rest[0].len());
+		let mut a = Atom::new();
+		a.atom = atom_name;
+		a.residue = f[3].to_string();
+		a.chain = chain_id.to_string();
+		a.resnum = rest[0][..digits_end].parse().unwrap_or(0);
+		a.icode = rest[0][digits_end..].chars().next();
+		if het { dropped.push((WarningKind::DroppedHetatm, a)); continue; }
+		if hydrogen { dropped.push((WarningKind::HydrogenRemoved, a)); continue; }
+		a.coor = Vec3::new(rest[1].parse().unwrap_or(0.0), rest[2].parse().unwrap_or(0.0), rest[3].parse().unwrap_or(0.0));
+		a.charge = rest[4].parse().ok();
+		a.atom_type_radius = rest[5].parse().unwrap_or(0.0);
+		atoms.push(a);
+	}
+	(atoms, dropped)
+}
+
+/// Two chain selections from a PDB or (by `.pqr` extension) PQR file
+pub fn read_structure_atoms_from_path(path: &str, selection1: &str, selection2: &str) -> io::Result<(Vec<Atom>, Vec<Atom>)> {
+	if path.to_ascii_lowercase().ends_with(".pqr") { read_pqr_atoms_from_path(path, selection1, selection2) } else { read_pdb_atoms_from_path(path, selection1, selection2) }
+}
+
+/// As `read_structure_atoms_from_path`, also reporting the records left out of the two selections
+/// (alternate locations, hydrogens, `HETATM` records) as `Results::warnings` entries
+pub fn read_structure_atoms_with_warnings_from_path(path: &str, selection1: &str, selection2: &str) -> io::Result<(Vec<Atom>, Vec<Atom>, Vec<Warning>)> {
+	let data = read_to_string(path)?;
+	Ok(if path.to_ascii_lowercase().ends_with(".pqr") { read_pqr_atoms_with_warnings_from_str(&data, selection1, selection2) } else { read_pdb_atoms_with_warnings_from_str(&data, selection1, selection2) })
+}
+
+/// Every chain of a PDB or (by `.pqr` extension) PQR file, for inspecting a structure before
+/// choosing selections
+pub fn read_structure_all_atoms_from_path(path: &str) -> io::Result<Vec<Atom>> {
+	let data = read_to_string(path)?;
+	Ok(if path.to_ascii_lowercase().ends_with(".pqr") { read_pqr_all_atoms_from_str(&data) } else { read_pdb_all_atoms_from_str(&data) })
+}
@@ -0,0 +1,70 @@
+//! Structures parsed with [pdbtbx](https://crates.io/crates/pdbtbx) as the two molecules of a
+//! calculation (feature "pdbtbx")
+
+use ::pdbtbx::{ContainsAtomConformer, ContainsAtomConformerResidue, ContainsAtomConformerResidueChain, Element, PDB};
+
+use crate::sc::pdb::split_by_chain;
+use crate::sc::types::{Atom, ScValue};
+
+/// Atoms of the first model of `pdb`, filtered like the built-in reader: `HETATM` records,
+/// hydrogens and alternate locations other than the first (blank or `A`) are left out
+pub fn atoms_from_pdbtbx(pdb: &PDB) -> Vec<Atom> {
+	let Some(model) = pdb.model(0) else { return Vec::new() };
+	model.atoms_with_hierarchy().filter_map(|h| {
+		let atom = h.atom();
+		if atom.hetero() || atom.element() == Some(&Element::H) { return None; }
+		if !matches!(h.conformer().alternative_location(), None | Some("A")) { return None; }
+		let mut a = Atom::new();
+		a.coor = atom.pos().into();
+		a.atom = atom.name().to_string();
+		a.residue = h.conformer().name().to_string();
+		a.chain = h.chain().id().to_string();
+		a.resnum = h.residue().serial_number() as i32;
+		a.icode = h.residue().insertion_code().and_then(|c| c.chars().next());
+		a.occupancy = Some(atom.occupancy() as ScValue);
+		a.bfactor = Some(atom.b_factor() as ScValue);
+		a.formal_charge = (atom.charge() != 0).then_some(atom.charge() as i32);
+		Some(a)
+	}).collect()
+}
+
+/// Split the first model of `pdb` into two chain selections (one chain ID or a comma-separated
+/// list, as in `pdb::chain_selected`)
+pub fn read_pdbtbx_atoms(pdb: &PDB, selection1: &str, selection2: &str) -> (Vec<Atom>, Vec<Atom>) {
+	split_by_chain(atoms_from_pdbtbx(pdb), selection1, selection2)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sc::pdb::read_pdb_atoms_from_str;
+
+	const PDB_TEXT: &str = "\
+ATOM      1  N   ALA A   1      11.104   6.134  -6.504  1.00 20.00           N
+ATOM      2  CA  ALA A   1      11.639   6.071  -5.147  1.00 21.00           C
+ATOM      3  H   ALA A   1      10.500   6.000  -6.900  1.00 20.00           H
+ATOM      4  CB AALA A   2      12.000   7.000  -4.000  0.60 22.00           C
+ATOM      5  CB BALA A   2      12.100   7.100  -4.100  0.40 22.00           C
+HETATM    6  O   HOH A 101      15.000   8.000  -3.000  1.00 30.00           O
+ATOM      7  N   GLY B   5      14.000   9.000  -2.000  1.00 25.00           N
+ATOM      8  CA  GLY C   6      16.000  10.000  -1.000  1.00 25.00           C
+END
+";
+
+	#[test]
+	fn matches_built_in_reader() {
+		let (pdb, _) = ::pdbtbx::ReadOptions::default()
+			.set_format(::pdbtbx::Format::Pdb)
+			.set_level(::pdbtbx::StrictnessLevel::Loose)
+			.read_raw(std::io::BufReader::new(PDB_TEXT.as_bytes()))
+			.expect("parses");
+		let (mol1, mol2) = read_pdbtbx_atoms(&pdb, "A", "B,C");
+		let (ref1, ref2) = read_pdb_atoms_from_str(PDB_TEXT, "A", "B,C");
+		let key = |a: &Atom| (a.chain.clone(), a.resnum, a.atom.clone(), a.residue.clone(), a.occupancy);
+		assert_eq!(mol1.iter().map(key).collect::<Vec<_>>(), ref1.iter().map(key).collect::<Vec<_>>());
+		assert_eq!(mol2.iter().map(key).collect::<Vec<_>>(), ref2.iter().map(key).collect::<Vec<_>>());
+		assert_eq!(mol1.len(), 3);
+		assert_eq!(mol2.len(), 2);
+		assert!((mol1[2].coor.x - 12.0).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use crate::sc::grid::SpatialGrid;
+use crate::sc::interface::cluster_points;
+use crate::sc::par::*;
+use crate::sc::settings::Settings;
+use crate::sc::surface_generator::{SurfaceGenerator, SurfaceCalculatorError};
+use crate::sc::types::*;
+use crate::sc::vector3::Vec3;
+
+/// Spacing of the grid the pocket volumes are sampled on, in Å
+const VOLUME_SPACING: ScValue = 0.5;
+
+/// Parameters of pocket detection
+#[derive(Clone, Debug)]
+pub struct PocketSettings {
+	/// Rays cast from each probe center to measure its burial
+	pub n_rays: usize,
+	/// Length of each ray in Å; atoms farther away do not enclose the probe
+	pub ray_length: ScValue,
+	/// Fraction of rays that must hit an atom for a probe to line a pocket (a flat surface blocks
+	/// about half)
+	pub min_buriedness: ScValue,
+	/// Buried probes whose centers are closer than this (Å) share a pocket
+	pub link_distance: ScValue,
+	/// Smaller clusters of buried probes are not reported
+	pub min_probes: usize,
+}
+
+impl Default for PocketSettings {
+	fn default() -> Self { Self { n_rays: 30, ray_length: 10.0, min_buriedness: 0.65, link_distance: 3.0, min_probes: 5 } }
+}
+
+/// Surface pocket: a cluster of probe spheres enclosed by the molecule
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Pocket {
+	/// Mean position of the pocket's probe centers
+	pub center: Vec3,
+	/// Volume in Å^3 of the union of the pocket's probe spheres
+	pub volume: ScValue,
+	/// Mean fraction of blocked rays over the pocket's probes
+	pub buriedness: ScValue,
+	pub n_probes: usize,
+	/// Residues with an atom touched by one of the pocket's probes, sorted by residue identity
+	pub residues: Vec<ResidueId>,
+}
+
+/// `n` directions spread evenly over the unit sphere (Fibonacci lattice)
+pub(crate) fn sphere_directions(n: usize) -> Vec<Vec3> {
+	let golden = std::f64::consts::PI * (3.0 - (5.0 as ScValue).sqrt());
+	(0..n).map(|i| {
+		let z = 1.0 - (2.0 * i as ScValue + 1.0) / n as ScValue;
+		let r = (1.0 - z * z).sqrt();
+		let phi = golden * i as ScValue;
+		Vec3::new(r * phi.cos(), r * phi.sin(), z)
+	}).collect()
+}
+
+/// Whether the ray from `p` along unit `d` meets the sphere at `c` of radius `r` within `length`
+fn ray_hits(p: Vec3, d: Vec3, c: Vec3, r: ScValue, length: ScValue) -> bool {
+	let v = c - p;
+	let t = v.dot(d);
+	if t <= 0.0 { return false; }
+	let h2 = r * r - (v.dot(v) - t * t);
+	h2 > 0.0 && t - h2.sqrt() <= length
+}
+
+/// Detect surface pockets of one molecule from the probe spheres of its molecular surface, in the
+/// manner of alpha-sphere pocket finders: every probe touching three atoms is scored by the
+/// fraction of `n_rays` rays from its center that hit an atom within `ray_length`, the buried ones
+/// are clustered by single linkage at `link_distance`, and clusters of at least `min_probes`
+/// probes are reported, largest volume first. Atoms without a radius are assigned one as in `sasa`.
+pub fn pockets(atoms: &[Atom], settings: &Settings, params: &PocketSettings) -> Result<Vec<Pocket>, SurfaceCalculatorError> {
+	pockets_with_radii(atoms, settings, params, Vec::new())
+}
+
+/// As [`pockets`], with an explicit radii table (an empty table selects the defaults).
+pub fn pockets_with_radii(atoms: &[Atom], settings: &Settings, params: &PocketSettings, radii: Vec<AtomRadius>) -> Result<Vec<Pocket>, SurfaceCalculatorError> {
+	let mut generator = SurfaceGenerator::new();
+	generator.settings = settings.clone();
+	generator.set_radii(radii);
+	generator.init()?;
+	generator.add_atoms(0, atoms.iter().cloned())?;
+	if generator.run.atoms.is_empty() { return Err(SurfaceCalculatorError::NoAtoms); }
+	for a in &mut generator.run.atoms { a.attention = Attention::Buried; }
+	generator.in_pool(|g| g.generate_molecular_surfaces())?;
+	let rp = generator.settings.probe_radius(0);
+	let parallel = generator.settings.enable_parallel;
+	let pool = generator.thread_pool();
+	let (atoms, probes) = (&generator.run.atoms, &generator.run.probes);
+	if probes.is_empty() || params.link_distance <= 0.0 { return Ok(Vec::new()); }
+
+	// Burial of each probe against the atoms within reach of its rays
+	let radmax = atoms.iter().map(|a| a.radius).fold(0.0, ScValue::max);
+	let reach = params.ray_length + radmax;
+	let grid = SpatialGrid::new(atoms.iter().map(|a| a.coor), reach);
+	let directions = sphere_directions(params.n_rays.max(1));
+	let burial = |p: &Probe| {
+		let mut candidates = Vec::new();
+		grid.candidates_into(p.point, 1, &mut candidates);
+		candidates.retain(|&j| atoms[j].coor.distance_squared(p.point) <= reach * reach);
+		let blocked = directions.iter().filter(|&&d| candidates.iter().any(|&j| ray_hits(p.point, d, atoms[j].coor, atoms[j].radius, params.ray_length))).count();
+		blocked as ScValue / directions.len() as ScValue
+	};
+	let buriedness: Vec<ScValue> = if parallel {
+		let score = || probes.par_iter().map(burial).collect();
+		match pool { Some(pool) => pool.install(score), None => score() }
+	} else {
+		probes.iter().map(burial).collect()
+	};
+
+	let buried: Vec<usize> = (0..probes.len()).filter(|&i| buriedness[i] >= params.min_buriedness).collect();
+	let labels = cluster_points(&buried.iter().map(|&i| probes[i].point).collect::<Vec<_>>(), params.link_distance);
+	let mut clusters: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+	for (&i, &root) in buried.iter().zip(&labels) { clusters.entry(root).or_default().push(i); }
+	let mut pockets: Vec<Pocket> = clusters.into_values().filter(|members| members.len() >= params.min_probes.max(1)).map(|members| {
+		let n = members.len() as ScValue;
+		let center = members.iter().fold(Vec3::zero(), |acc, &i| acc + probes[i].point) / n;
+		let mut residues: Vec<ResidueId> = members.iter().flat_map(|&i| probes[i].atom_indices).map(|j| atoms[j].residue_id()).collect();
+		residues.sort();
+		residues.dedup();
+		Pocket {
+			center,
+			volume: union_volume(&members.iter().map(|&i| probes[i].point).collect::<Vec<_>>(), rp),
+			buriedness: members.iter().map(|&i| buriedness[i]).sum::<ScValue>() / n,
+			n_probes: members.len(),
+			residues,
+		}
+	}).collect();
+	pockets.sort_by(|a, b| b.volume.partial_cmp(&a.volume).unwrap_or(std::cmp::Ordering::Equal));
+	Ok(pockets)
+}
+
+/// Volume of a union of spheres of radius `r`, counting the points of a `VOLUME_SPACING` grid
+/// over their bounding box that lie inside one
+fn union_volume(centers: &[Vec3], r: ScValue) -> ScValue {
+	if centers.is_empty() || r <= 0.0 { return 0.0; }
+	let lo = centers.iter().fold(Vec3::new(ScValue::INFINITY, ScValue::INFINITY, ScValue::INFINITY), |m, c| Vec3::new(m.x.min(c.x), m.y.min(c.y), m.z.min(c.z)));
+	let hi = centers.iter().fold(Vec3::new(ScValue::NEG_INFINITY, ScValue::NEG_INFINITY, ScValue::NEG_INFINITY), |m, c| Vec3::new(m.x.max(c.x), m.y.max(c.y), m.z.max(c.z)));
+	let steps = |a: ScValue, b: ScValue| ((b - a + 2.0 * r) / VOLUME_SPACING).ceil() as usize + 1;
+	let grid = SpatialGrid::new(centers.iter().copied(), r);
+	let mut near = Vec::new();
+	let mut inside = 0usize;
+	for ix in 0..steps(lo.x, hi.x) { for iy in 0..steps(lo.y, hi.y) { for iz in 0..steps(lo.z, hi.z) {
+		let p = Vec3::new(lo.x - r + ix as ScValue * VOLUME_SPACING, lo.y - r + iy as ScValue * VOLUME_SPACING, lo.z - r + iz as ScValue * VOLUME_SPACING);
+		grid.candidates_into(p, 1, &mut near);
+		if near.iter().any(|&i| centers[i].distance_squared(p) <= r * r) { inside += 1; }
+	}}}
+	inside as ScValue * VOLUME_SPACING.powi(3)
+}
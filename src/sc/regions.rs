@@ -0,0 +1,103 @@
+use std::fs::File;
+use std::io::{self, Read};
+
+use crate::sc::sc_calculator::median;
+use crate::sc::surface_generator::RunState;
+use crate::sc::types::*;
+
+/// Inclusive residue-number range on one chain (insertion codes fall inside their number)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResidueRange { pub chain: String, pub start: i32, pub end: i32 }
+
+impl ResidueRange {
+	pub fn contains(&self, atom: &Atom) -> bool { self.chain == atom.chain && atom.resnum >= self.start && atom.resnum <= self.end }
+}
+
+/// Named set of residue ranges, e.g. a CDR loop
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region { pub name: String, pub ranges: Vec<ResidueRange> }
+
+impl Region {
+	pub fn contains(&self, atom: &Atom) -> bool {
+		self.ranges.iter().any(|r| r.contains(atom))
+	}
+}
+
+/// Sc restricted to one region: medians of the S values of the region's trimmed dots and of the
+/// partner dots whose nearest neighbor lies in the region, averaged as for the global Sc
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegionSc {
+	pub name: String,
+	pub sc: ScValue,
+	/// Median nearest-neighbor distance over both directions
+	pub distance: ScValue,
+	/// Trimmed dot area of the region itself
+	pub area: ScValue,
+	pub n_dots: usize,
+}
+
+pub fn read_regions_from_path(path: &str) -> io::Result<Vec<Region>> {
+	let mut f = File::open(path)?;
+	let mut buf = String::new();
+	f.read_to_string(&mut buf)?;
+	read_regions_from_str(&buf)
+}
+
+/// Parse region definitions, one per line: `NAME CHAIN START-END [CHAIN START-END ...]`.
+/// A single residue may be written as `START`; blank lines and `#` comments are ignored.
+pub fn read_regions_from_str(data: &str) -> io::Result<Vec<Region>> {
+	let invalid = |line: usize, msg: String| io::Error::new(io::ErrorKind::InvalidData, format!("invalid region definition on line {line}: {msg}"));
+	let mut regions = Vec::new();
+	for (n, raw) in data.lines().enumerate() {
+		let line = raw.split('#').next().unwrap_or("").trim();
+		if line.is_empty() { continue; }
+		let fields: Vec<&str> = line.split_whitespace().collect();
+		if fields.len() < 3 || fields.len().is_multiple_of(2) { return Err(invalid(n + 1, "expected NAME followed by CHAIN START-END pairs".into())); }
+		let mut ranges = Vec::new();
+		for pair in fields[1..].chunks(2) {
+			let (start, end) = match pair[1].split_once('-') {
+				Some((a, b)) => (a, b),
+				None => (pair[1], pair[1]),
+			};
+			let start: i32 = start.trim().parse().map_err(|_| invalid(n + 1, format!("bad residue number '{}'", pair[1])))?;
+			let end: i32 = end.trim().parse().map_err(|_| invalid(n + 1, format!("bad residue number '{}'", pair[1])))?;
+			ranges.push(ResidueRange { chain: pair[0].to_string(), start: start.min(end), end: start.max(end) });
+		}
+		regions.push(Region { name: fields[0].to_string(), ranges });
+	}
+	Ok(regions)
+}
+
+pub(crate) fn region_sc(run: &RunState, region: &Region) -> RegionSc {
+	let in_region = |mol: usize, dot: usize| region.contains(&run.atoms[run.dots[mol][dot].atom_index]);
+	let mut scores = Vec::new();
+	let mut distances = Vec::new();
+	let mut forward = (Vec::new(), Vec::new());
+	let mut area = 0.0;
+	let mut n_dots = 0;
+	for my in 0..2 {
+		let their = 1 - my;
+		for pair in &run.pairs[my] {
+			if in_region(my, pair.dot) {
+				forward.0.push(pair.score);
+				forward.1.push(pair.distance);
+				area += run.dots[my][pair.dot].area;
+				n_dots += 1;
+			}
+			if in_region(their, pair.neighbor) {
+				scores.push(pair.score);
+				distances.push(pair.distance);
+			}
+		}
+	}
+	let (mut f_scores, mut f_distances) = forward;
+	let halves = [(median(&mut f_scores), median(&mut f_distances)), (median(&mut scores), median(&mut distances))];
+	RegionSc {
+		name: region.name.clone(),
+		sc: (halves[0].0 + halves[1].0) / 2.0,
+		distance: (halves[0].1 + halves[1].1) / 2.0,
+		area,
+		n_dots,
+	}
+}
@@ -1,7 +1,28 @@
-use crate::sc::surface_generator::{SurfaceGenerator, SurfaceCalculatorError};
-use crate::sc::settings::Settings;
+use crate::sc::builder::ScCalculatorBuilder;
+use crate::sc::surface_generator::{AtomArrays, SurfaceGenerator, SurfaceCalculatorError};
+use crate::sc::grid::{BurialGrid, SpatialGrid};
+use crate::sc::settings::{AtomSubset, MAX_DOT_DENSITY, Phase, ScStatistic, Settings, TrimMode, WeightFunction};
 use crate::sc::types::*;
-use rayon::prelude::*;
+use crate::sc::electrostatics;
+use crate::sc::curvature;
+use crate::sc::zernike::{self, ZernikeDescriptor};
+use crate::sc::interface;
+use crate::sc::pdb;
+use crate::sc::sasa;
+use crate::sc::volume;
+use crate::sc::regions::{self, Region, RegionSc};
+use crate::sc::weights::{self, AtomWeight, WeightedSc};
+use crate::sc::vector3::{RigidTransform, Vec3};
+use crate::sc::view::DotView;
+use crate::sc::par::*;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+// std's clock panics on wasm32-unknown-unknown
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// Cell edge of the grid over partner dots in nearest-dot pairing, about the typical gap
+const PAIR_GRID_CELL: ScValue = 2.0;
 
 pub struct ScCalculator { pub base: SurfaceGenerator }
 
@@ -9,27 +30,62 @@ impl Default for ScCalculator { fn default() -> Self { Self::new() } }
 
 impl ScCalculator {
 	pub fn new() -> Self { Self { base: SurfaceGenerator::new() } }
+	/// Validated configuration in one expression; see `ScCalculatorBuilder`
+	pub fn builder() -> ScCalculatorBuilder { ScCalculatorBuilder::new() }
 	pub fn settings_mut(&mut self) -> &mut Settings { &mut self.base.settings }
 	pub fn settings(&self) -> &Settings { &self.base.settings }
 	pub fn set_radii(&mut self, radii: Vec<AtomRadius>) { self.base.set_radii(radii); }
+	/// Stream every generated dot to `visitor` (see `SurfaceGenerator::set_dot_visitor`)
+	pub fn set_dot_visitor(&mut self, visitor: impl FnMut(usize, &Dot) + Send + Sync + 'static, keep_dots: bool) { self.base.set_dot_visitor(visitor, keep_dots); }
+	pub fn clear_dot_visitor(&mut self) { self.base.clear_dot_visitor(); }
+
+	pub fn calc(&mut self) -> Result<Results, SurfaceCalculatorError> { self.calc_with(true) }
+
+	/// `calc()`, with the interface reports (gap volume, electrostatics, contacts and the rest) only
+	/// when `reports` is set; otherwise their fields are left empty
+	fn calc_with(&mut self, reports: bool) -> Result<Results, SurfaceCalculatorError> {
+		self.base.settings.validate()?;
+		match self.base.thread_pool() {
+			Some(pool) => pool.install(|| self.calc_in_pool(reports)),
+			None => self.calc_in_pool(reports),
+		}
+	}
 
-	pub fn calc(&mut self) -> Result<Results, SurfaceCalculatorError> {
+	fn calc_in_pool(&mut self, reports: bool) -> Result<Results, SurfaceCalculatorError> {
 		self.base.init()?;
+		self.base.remove_masked_atoms();
+		self.base.remove_coincident_atoms();
 		self.base.run.results.valid = 0;
 		if self.base.run.atoms.is_empty() { return Err(SurfaceCalculatorError::NoAtoms); }
-		if self.base.run.results.surfaces[0].n_atoms == 0 { return Err(SurfaceCalculatorError::Io(std::io::Error::other("No atoms for molecule 1"))); }
-		if self.base.run.results.surfaces[1].n_atoms == 0 { return Err(SurfaceCalculatorError::Io(std::io::Error::other("No atoms for molecule 2"))); }
+		if self.base.run.results.surfaces[0].n_atoms == 0 { return Err(SurfaceCalculatorError::EmptyMolecule(1)); }
+		if self.base.run.results.surfaces[1].n_atoms == 0 { return Err(SurfaceCalculatorError::EmptyMolecule(2)); }
+		self.base.refresh_frozen_surface()?;
+		self.base.clear_surfaces();
 		self.base.assign_attention_numbers();
 		self.base.generate_molecular_surfaces()?;
-		if self.base.run.dots[0].is_empty() || self.base.run.dots[1].is_empty() { return Err(SurfaceCalculatorError::Io(std::io::Error::other("No molecular dots generated"))); }
+		self.score_surfaces(reports)
+	}
+
+	/// Trimming, pairing and statistics over the generated surfaces, then the reports if `reports`
+	fn score_surfaces(&mut self, reports: bool) -> Result<Results, SurfaceCalculatorError> {
+		self.base.check_cancelled()?;
+		let t = Instant::now();
+		if self.base.run.dots[0].is_empty() || self.base.run.dots[1].is_empty() { return Err(SurfaceCalculatorError::NoDots); }
+		self.base.settings.report_progress(Phase::Trimming, 0, 2);
 		for i in 0..2 {
 			let area = self.trim_peripheral_band(i)?;
 			self.base.run.results.surfaces[i].trimmed_area = area;
 			self.base.run.results.surfaces[i].n_trimmed_dots = self.base.run.trimmed_dots[i].len();
-			self.base.run.results.surfaces[i].n_all_dots = self.base.run.dots[i].len();
+			self.base.run.results.surfaces[i].n_all_dots = self.base.run.dots[i].len() + self.base.run.dropped[i];
+			self.base.run.results.surfaces[i].bsa = self.buried_sasa(i);
+			self.base.settings.report_progress(Phase::Trimming, i + 1, 2);
 		}
+		self.base.run.results.timings.trimming = t.elapsed().as_secs_f64();
+		let t = Instant::now();
+		self.base.settings.report_progress(Phase::Statistic, 0, 1);
 		self.calc_neighbor_distance(0, 1);
 		self.calc_neighbor_distance(1, 0);
+		self.base.check_cancelled()?;
 		self.base.run.results.combined.d_mean = (self.base.run.results.surfaces[0].d_mean + self.base.run.results.surfaces[1].d_mean) / 2.0;
 		self.base.run.results.combined.d_median = (self.base.run.results.surfaces[0].d_median + self.base.run.results.surfaces[1].d_median) / 2.0;
 		self.base.run.results.combined.s_mean = (self.base.run.results.surfaces[0].s_mean + self.base.run.results.surfaces[1].s_mean) / 2.0;
@@ -40,37 +96,178 @@ impl ScCalculator {
 		self.base.run.results.combined.n_all_dots = self.base.run.results.surfaces[0].n_all_dots + self.base.run.results.surfaces[1].n_all_dots;
 		self.base.run.results.combined.n_trimmed_dots = self.base.run.results.surfaces[0].n_trimmed_dots + self.base.run.results.surfaces[1].n_trimmed_dots;
 		self.base.run.results.combined.trimmed_area = self.base.run.results.surfaces[0].trimmed_area + self.base.run.results.surfaces[1].trimmed_area;
-		self.base.run.results.sc = self.base.run.results.combined.s_median;
+		self.base.run.results.combined.bsa = self.base.run.results.surfaces[0].bsa + self.base.run.results.surfaces[1].bsa;
+		let selected: Vec<ScStatistic> = std::iter::once(self.base.settings.statistic).chain(self.base.settings.extra_statistics.iter().copied()).collect();
+		self.base.run.results.statistics = selected.into_iter().map(|stat| {
+			let surfaces = [0, 1].map(|i| {
+				let mut scores: Vec<f64> = self.base.run.pairs[i].iter().map(|p| p.score).collect();
+				statistic(&mut scores, stat)
+			});
+			StatisticValue { statistic: stat, sc: (surfaces[0] + surfaces[1]) / 2.0, surfaces }
+		}).collect();
+		self.base.run.results.sc = self.base.run.results.statistics[0].sc;
+		let [sc_ab, sc_ba] = self.base.run.results.statistics[0].surfaces;
+		self.base.run.results.directional = DirectionalSc {
+			sc_ab,
+			sc_ba,
+			distance_ab: self.base.run.results.surfaces[0].d_median,
+			distance_ba: self.base.run.results.surfaces[1].d_median,
+			asymmetry: sc_ab - sc_ba,
+		};
+		self.base.run.results.normal_angles = interface::normal_angle_stats(&self.base.run, self.base.settings.antiparallel_tolerance, &self.base.settings.weight, self.base.settings.gaussian_w);
 		self.base.run.results.distance = self.base.run.results.combined.d_median;
 		self.base.run.results.area = self.base.run.results.combined.trimmed_area;
+		self.base.run.results.bsa = self.base.run.results.combined.bsa;
+		self.base.run.results.timings.statistic = t.elapsed().as_secs_f64();
+		self.base.settings.report_progress(Phase::Statistic, 1, 1);
+		if reports { self.interface_reports()?; } else { self.clear_reports(); }
 		self.base.run.results.valid = 1;
+		self.base.run.scored_with = Some(self.base.settings.surface_fingerprint());
 		Ok(self.base.run.results.clone())
 	}
 
+	/// The interface reports of `Results` over the scored surfaces
+	fn interface_reports(&mut self) -> Result<(), SurfaceCalculatorError> {
+		let t = Instant::now();
+		self.base.settings.report_progress(Phase::Reports, 0, 1);
+		self.base.run.results.gap_volume = interface::gap_volume(&self.base.run);
+		let side_area = self.base.run.results.area / 2.0;
+		self.base.run.results.gap_index = if side_area > 0.0 { self.base.run.results.gap_volume / side_area } else { 0.0 };
+		let parallel = self.base.settings.enable_parallel;
+		self.base.run.results.surfaces[0].ec = electrostatics::surface_ec(&self.base.run, 0, parallel);
+		self.base.run.results.surfaces[1].ec = electrostatics::surface_ec(&self.base.run, 1, parallel);
+		self.base.run.results.ec = match (self.base.run.results.surfaces[0].ec, self.base.run.results.surfaces[1].ec) {
+			(Some(a), Some(b)) => Some((a + b) / 2.0),
+			_ => None,
+		};
+		self.base.run.results.contacts = interface::contact_map(&self.base.run);
+		self.base.run.results.geometry = interface::interface_geometry(&self.base.run);
+		self.base.run.results.curvature = curvature::curvature_stats(&self.base.run, self.base.settings.curvature_radius, parallel);
+		self.base.run.results.clashes = interface::clash_report(&self.base.run, self.base.settings.clash_tolerance);
+		let link = self.base.settings.patch_link_distance;
+		self.base.run.results.patches = [interface::buried_patches(&self.base.run, 0, link), interface::buried_patches(&self.base.run, 1, link)];
+		self.base.run.results.voids = interface::interface_voids(&self.base.run, self.base.settings.void_min_gap, link);
+		self.base.run.results.interface_residues = [interface::interface_residues(&self.base.run, 0), interface::interface_residues(&self.base.run, 1)];
+		self.base.run.results.chains = [interface::chain_stats(&self.base.run, 0), interface::chain_stats(&self.base.run, 1)];
+		if self.base.settings.molecular_volume {
+			let volumes = [self.molecule_volume(0)?, self.molecule_volume(1)?];
+			self.base.run.results.surfaces[0].volume = Some(volumes[0]);
+			self.base.run.results.surfaces[1].volume = Some(volumes[1]);
+			self.base.run.results.combined.volume = Some(volumes[0] + volumes[1]);
+		} else {
+			self.base.run.results.surfaces[0].volume = None;
+			self.base.run.results.surfaces[1].volume = None;
+			self.base.run.results.combined.volume = None;
+		}
+		self.base.run.results.symmetry = if self.base.settings.homodimer { Some(interface::symmetry_stats(&self.base.run, &self.base.run.results)) } else { None };
+		self.base.run.results.timings.reports = t.elapsed().as_secs_f64();
+		self.base.settings.report_progress(Phase::Reports, 1, 1);
+		Ok(())
+	}
+
+	/// Empty the report fields, so a lean run does not carry over those of an earlier full one
+	fn clear_reports(&mut self) {
+		let r = &mut self.base.run.results;
+		r.gap_volume = 0.0;
+		r.gap_index = 0.0;
+		r.ec = None;
+		r.contacts.clear();
+		r.geometry = InterfaceGeometry::default();
+		r.curvature = CurvatureStats::default();
+		r.clashes = ClashReport::default();
+		r.patches = Default::default();
+		r.voids.clear();
+		r.interface_residues = Default::default();
+		r.chains = Default::default();
+		r.symmetry = None;
+		for s in r.surfaces.iter_mut().chain([&mut r.combined]) {
+			s.ec = None;
+			s.volume = None;
+		}
+		r.timings.reports = 0.0;
+	}
+
+	/// Solvent-excluded volume of molecule i alone, from a complete surface at its own probe radius
+	/// and density; context atoms are left out and masked atoms that occlude are kept.
+	fn molecule_volume(&self, i: usize) -> Result<ScValue, SurfaceCalculatorError> {
+		let s = &self.base.settings;
+		let settings = Settings {
+			rp: s.probe_radius(i),
+			rp_molecule: [None, None],
+			dot_density: s.molecule_density(i),
+			dot_density_molecule: [None, None],
+			adaptive_density: None,
+			focus: None,
+			mask: Vec::new(),
+			atom_subset: [AtomSubset::All; 2],
+			streaming: false,
+			progress: None,
+			..s.clone()
+		};
+		let atoms: Vec<Atom> = self.base.run.atoms.iter().filter(|a| a.molecule == i && !a.context).cloned().collect();
+		volume::excluded_volume(&atoms, &settings)
+	}
+
+	/// Buried SASA of surface i: contact dots whose probe-center projection falls inside the
+	/// partner's expanded spheres, scaled to the accessible sphere.
+	fn buried_sasa(&self, i: usize) -> ScValue {
+		let rp = self.base.settings.probe_radius(i);
+		self.base.run.dots[i].iter()
+			.filter(|d| d.buried && d.kind == DotKind::Contact)
+			.map(|d| sasa::accessible_area(d, &self.base.run.atoms[d.atom_index], rp))
+			.sum()
+	}
+
 	fn trim_peripheral_band(&mut self, i: usize) -> Result<ScValue, SurfaceCalculatorError> {
-		let (indices, area) = if self.base.settings.enable_parallel {
-			let sdots = &self.base.run.dots[i];
-			let indices: Vec<usize> = (0..sdots.len()).into_par_iter()
-				.filter(|&idx| sdots[idx].buried && self.trim_peripheral_band_check_dot(idx, sdots))
-				.collect();
-			let area: f64 = indices.par_iter().map(|&idx| sdots[idx].area).sum();
+		let (indices, area) = self.select_trimmed_dots(i, self.base.settings.peripheral_band);
+		self.base.check_cancelled()?;
+		self.base.run.trimmed_dots[i].clear();
+		self.base.run.trimmed_dots[i] = indices;
+		Ok(area)
+	}
+
+	/// Buried dots of surface i kept by the trimming criterion, with their total area. In the default
+	/// mode these are the dots farther than `band` from every non-buried dot.
+	fn select_trimmed_dots(&self, i: usize, band: ScValue) -> (Vec<usize>, ScValue) {
+		let sdots = &self.base.run.dots[i];
+		let partner = &self.base.run.dots[1 - i];
+		// Partner dots on a grid, so the distance test looks at nearby cells only
+		let partner_grid = match self.base.settings.trim_mode {
+			TrimMode::PartnerDistance(cutoff) => SpatialGrid::new(partner.iter().map(|d| d.coor), cutoff),
+			TrimMode::PeripheralBand => SpatialGrid::default(),
+		};
+		let keep = |idx: usize| -> bool {
+			if !sdots[idx].buried || self.base.settings.cancelled() { return false; }
+			match (self.base.settings.trim_mode, &self.base.run.peripheral) {
+				(TrimMode::PeripheralBand, Some(marked)) => !marked[i][idx],
+				(TrimMode::PeripheralBand, None) => self.trim_peripheral_band_check_dot(idx, sdots, band),
+				(TrimMode::PartnerDistance(cutoff), _) => self.trim_partner_distance_check_dot(&sdots[idx], partner, &partner_grid, cutoff),
+			}
+		};
+		if self.base.settings.enable_parallel {
+			let indices: Vec<usize> = (0..sdots.len()).into_par_iter().filter(|&idx| keep(idx)).collect();
+			// Summed in index order so the area does not depend on the thread count
+			let area: f64 = indices.iter().map(|&idx| sdots[idx].area).sum();
 			(indices, area)
 		} else {
-			let sdots = &self.base.run.dots[i];
 			let mut indices: Vec<usize> = Vec::new();
 			let mut area = 0.0;
 			for (idx, dot) in sdots.iter().enumerate() {
-				if dot.buried && self.trim_peripheral_band_check_dot(idx, sdots) { indices.push(idx); area += dot.area; }
+				if keep(idx) { indices.push(idx); area += dot.area; }
 			}
 			(indices, area)
-		};
-		self.base.run.trimmed_dots[i].clear();
-		self.base.run.trimmed_dots[i] = indices;
-		Ok(area)
+		}
+	}
+
+	fn trim_partner_distance_check_dot(&self, dot: &Dot, partner: &[Dot], grid: &SpatialGrid, cutoff: ScValue) -> bool {
+		let r2 = cutoff * cutoff;
+		let mut near = false;
+		grid.for_each_candidate(dot.coor, 1, |j| near |= partner[j].buried && dot.coor.distance_squared(partner[j].coor) <= r2);
+		near
 	}
 
-	fn trim_peripheral_band_check_dot(&self, dot_index: usize, sdots: &[Dot]) -> bool {
-		let r2 = self.base.settings.peripheral_band * self.base.settings.peripheral_band;
+	fn trim_peripheral_band_check_dot(&self, dot_index: usize, sdots: &[Dot], band: ScValue) -> bool {
+		let r2 = band * band;
 		let dot = &sdots[dot_index];
 		for (i, dot2) in sdots.iter().enumerate() {
 			if i == dot_index { continue; }
@@ -80,90 +277,757 @@ impl ScCalculator {
 		true
 	}
 
-	fn calc_neighbor_distance(&mut self, my: usize, their: usize) {
-		let (distances, scores, distmin_sum, score_sum) = if self.base.settings.enable_parallel {
-			let my_dots = &self.base.run.trimmed_dots[my];
-			let their_dots = &self.base.run.trimmed_dots[their];
-			if my_dots.is_empty() || their_dots.is_empty() { return; }
-			let gaussian_w = self.base.settings.gaussian_w;
-			let run_ref = &self.base.run;
-			let pairs: Vec<(f64, f64)> = my_dots.par_iter().filter_map(|&pd| {
-				let dot1 = &run_ref.dots[my][pd];
-				let mut distmin2: f64 = 9.0e20f64;
-				let mut neighbor: Option<&Dot> = None;
-				for &pd2 in their_dots {
-					let dot2 = &run_ref.dots[their][pd2];
-					if !dot2.buried { continue; }
-					let d2 = dot2.coor.distance_squared(dot1.coor);
-					if d2 <= distmin2 { distmin2 = d2; neighbor = Some(dot2); }
-				}
-				neighbor.map(|n| {
-					let distmin = distmin2.sqrt();
-					let mut r = dot1.outnml.dot(n.outnml);
-					r *= (-(distmin*distmin) * gaussian_w).exp();
-					r = r.clamp(-0.999, 0.999);
-					(distmin, -r)
-				})
-			}).collect();
-			let (distances, scores): (Vec<f64>, Vec<f64>) = pairs.iter().cloned().unzip();
-			let distmin_sum: f64 = distances.par_iter().sum();
-			let score_sum: f64 = scores.par_iter().map(|v| -v).sum();
-			(distances, scores, distmin_sum, score_sum)
-		} else {
-			let my_dots = &self.base.run.trimmed_dots[my];
-			let their_dots = &self.base.run.trimmed_dots[their];
-			if my_dots.is_empty() || their_dots.is_empty() { return; }
-			let mut distances: Vec<f64> = Vec::with_capacity(my_dots.len());
-			let mut scores: Vec<f64> = Vec::with_capacity(my_dots.len());
-			let mut distmin_sum = 0.0;
-			let mut score_sum = 0.0;
-			for &pd in my_dots {
-				let dot1 = &self.base.run.dots[my][pd];
-				let mut neighbor: Option<&Dot> = None;
-				let mut distmin2: f64 = 9.0e20f64;
-				for &pd2 in their_dots {
-					let dot2 = &self.base.run.dots[their][pd2];
-					if !dot2.buried { continue; }
-					let d2 = dot2.coor.distance_squared(dot1.coor);
-					if d2 <= distmin2 { distmin2 = d2; neighbor = Some(dot2); }
-				}
-				if let Some(n) = neighbor {
-					let distmin = distmin2.sqrt();
-					distmin_sum += distmin;
-					distances.push(distmin);
-					let mut r = dot1.outnml.dot(n.outnml);
-					r *= (-(distmin*distmin) * self.base.settings.gaussian_w).exp();
-					r = r.clamp(-0.999, 0.999);
-					score_sum += r;
-					scores.push(-r);
-				}
-			}
-			(distances, scores, distmin_sum, score_sum)
+	/// Pair each of `my_dots` with its nearest buried dot among `their_dots` and score it with the configured weight
+	fn pair_dots(&self, my: usize, their: usize, my_dots: &[usize], their_dots: &[usize], gaussian_w: ScValue) -> Vec<DotPair> {
+		if my_dots.is_empty() || their_dots.is_empty() { return Vec::new(); }
+		let weight = &self.base.settings.weight;
+		let run_ref = &self.base.run;
+		// Nearest buried partner dot from a grid search; ties go to the last dot in `their_dots`,
+		// as in a scan over all of them
+		let candidates: Vec<usize> = their_dots.iter().copied().filter(|&pd2| run_ref.dots[their][pd2].buried).collect();
+		let grid = SpatialGrid::new(candidates.iter().map(|&pd2| run_ref.dots[their][pd2].coor), PAIR_GRID_CELL);
+		let pair_for = |pd: usize| -> Option<DotPair> {
+			if self.base.settings.cancelled() { return None; }
+			let dot1 = &run_ref.dots[my][pd];
+			let nearest = grid.nearest(dot1.coor, |k| run_ref.dots[their][candidates[k]].coor.distance_squared(dot1.coor));
+			nearest.map(|(k, distmin2)| {
+				let n = candidates[k];
+				let distmin = distmin2.sqrt();
+				let r = dot1.outnml.dot(run_ref.dots[their][n].outnml);
+				DotPair { dot: pd, neighbor: n, distance: distmin, normal_dot: r, score: Self::score(r, distmin, weight, gaussian_w) }
+			})
 		};
-		let total_points = distances.len() as f64;
-		if total_points == 0.0 { return; }
-		let mut distances = distances;
-		let mut scores = scores;
+		if self.base.settings.enable_parallel {
+			my_dots.par_iter().filter_map(|&pd| pair_for(pd)).collect()
+		} else {
+			my_dots.iter().filter_map(|&pd| pair_for(pd)).collect()
+		}
+	}
+
+	/// S value from the normal product and gap distance, clamped as in the reference implementation
+	fn score(normal_dot: ScValue, distance: ScValue, weight: &WeightFunction, gaussian_w: ScValue) -> ScValue {
+		let r = (normal_dot * weight.weight(distance, gaussian_w)).clamp(-0.999, 0.999);
+		-r
+	}
+
+	fn calc_neighbor_distance(&mut self, my: usize, their: usize) {
+		self.base.run.pairs[my].clear();
+		let pairs = self.pair_dots(my, their, &self.base.run.trimmed_dots[my], &self.base.run.trimmed_dots[their], self.base.settings.gaussian_w);
+		if pairs.is_empty() { return; }
+		let mut distances: Vec<f64> = pairs.iter().map(|p| p.distance).collect();
+		let mut scores: Vec<f64> = pairs.iter().map(|p| p.score).collect();
+		let distmin_sum: f64 = distances.iter().sum();
+		let score_sum: f64 = scores.iter().sum();
 		let d_len = distances.len() as f64;
 		let s_len = scores.len() as f64;
-		// Scope mutable borrows so we can use lengths afterward without conflicts
-		let d_median_val = {
-			let median_idx = distances.len()/2;
-			let (_, m, _) = distances.select_nth_unstable_by(median_idx, |a,b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-			*m
+		self.base.run.results.surfaces[my].d_mean = distmin_sum / d_len;
+		self.base.run.results.surfaces[my].d_median = median(&mut distances);
+		self.base.run.results.surfaces[my].s_mean = score_sum / s_len;
+		self.base.run.results.surfaces[my].s_median = median(&mut scores);
+		self.base.run.pairs[my] = pairs;
+	}
+
+	/// Repeat `calc()` with the dot density scaled by `growth` (> 1) each step until Sc changes by
+	/// less than `tolerance`, `max_iterations` runs are done, or the density reaches
+	/// `MAX_DOT_DENSITY` (the last step runs at the cap; `converged` stays false unless it settles).
+	/// Every atom is sampled at the stepped density: per-molecule and adaptive densities are set
+	/// aside, and a frozen molecule 1 surface is dropped. The calculator holds the final run; the
+	/// density settings are restored afterwards.
+	pub fn calc_converged(&mut self, tolerance: ScValue, growth: ScValue, max_iterations: usize) -> Result<Convergence, SurfaceCalculatorError> {
+		if !(growth.is_finite() && growth > 1.0) { return Err(SurfaceCalculatorError::InvalidSettings(format!("convergence growth must be a finite factor above 1, got {growth}"))); }
+		if !(tolerance.is_finite() && tolerance >= 0.0) { return Err(SurfaceCalculatorError::InvalidSettings(format!("convergence tolerance must be finite and non-negative, got {tolerance}"))); }
+		self.base.unfreeze_molecule1();
+		self.base.run.pending = None;
+		let mut convergence = Convergence::default();
+		let mut density = self.base.settings.dot_density;
+		let mut previous: Option<ScValue> = None;
+		for _ in 0..max_iterations.max(1) {
+			let sc = self.at_density(density, |sc| sc.calc())?.sc;
+			convergence.steps.push(ConvergenceStep { density, sc });
+			convergence.density = density;
+			convergence.sc = sc;
+			if previous.is_some_and(|prev| (sc - prev).abs() < tolerance) { convergence.converged = true; break; }
+			if density >= MAX_DOT_DENSITY { break; }
+			previous = Some(sc);
+			density = (density * growth).min(MAX_DOT_DENSITY);
+		}
+		Ok(convergence)
+	}
+
+	/// Run `calc()` once per probe radius in `radii`, reusing the atoms and their radii, for the usual
+	/// robustness check of Sc against the probe size. Unlike `sweep` every point regenerates the
+	/// surfaces; a frozen molecule 1 surface is dropped. Per-molecule overrides
+	/// (`Settings::rp_molecule`) still win. The calculator holds the last run; `Settings::rp` is
+	/// restored afterwards.
+	pub fn probe_radius_scan(&mut self, radii: &[ScValue]) -> Result<Vec<ProbeRadiusPoint>, SurfaceCalculatorError> {
+		let start_rp = self.base.settings.rp;
+		self.base.unfreeze_molecule1();
+		let mut points = Vec::with_capacity(radii.len());
+		for &rp in radii {
+			self.base.settings.rp = rp;
+			self.base.run.pending = None;
+			let res = self.calc();
+			self.base.settings.rp = start_rp;
+			let r = res?;
+			points.push(ProbeRadiusPoint { probe_radius: rp, sc: r.sc, distance: r.distance, area: r.area, bsa: r.bsa });
+		}
+		Ok(points)
+	}
+
+	/// Sc at each dot density in `densities` (dots/Å²) from one surface construction: the first
+	/// density runs a full `calc()`, the others regenerate only the dots over the same neighbor
+	/// tables and probes, which are density-independent. A cheap check that Sc has converged in
+	/// density. A frozen molecule 1 surface is dropped. Every atom is sampled at the scanned density:
+	/// per-molecule and adaptive densities are set aside for the scan. The calculator holds the last
+	/// run; the density settings are restored afterwards.
+	pub fn density_scan(&mut self, densities: &[ScValue]) -> Result<Vec<DensityPoint>, SurfaceCalculatorError> {
+		let saved = (self.base.settings.dot_density, self.base.settings.dot_density_molecule, self.base.settings.adaptive_density);
+		let checked = densities.iter().try_for_each(|&density| {
+			self.base.settings.dot_density = density;
+			self.base.settings.validate()
+		});
+		self.base.settings.dot_density = saved.0;
+		checked?;
+		self.base.settings.dot_density_molecule = [None, None];
+		self.base.settings.adaptive_density = None;
+		self.base.unfreeze_molecule1();
+		self.base.run.pending = None;
+		let mut points = Vec::with_capacity(densities.len());
+		let mut scan = || {
+			for (i, &density) in densities.iter().enumerate() {
+				let r = if i == 0 {
+					self.base.settings.dot_density = density;
+					self.calc()?
+				} else {
+					self.regenerate_at(density)?
+				};
+				points.push(DensityPoint { density, sc: r.sc, distance: r.distance, area: r.area, n_dots: r.combined.n_trimmed_dots });
+			}
+			Ok(())
 		};
-		let s_median_val = {
-			let median_idx = scores.len()/2;
-			let (_, m, _) = scores.select_nth_unstable_by(median_idx, |a,b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-			*m
+		let res = scan();
+		(self.base.settings.dot_density, self.base.settings.dot_density_molecule, self.base.settings.adaptive_density) = saved;
+		res.map(|_| points)
+	}
+
+	fn regenerate_at(&mut self, density: ScValue) -> Result<Results, SurfaceCalculatorError> {
+		let run = |sc: &mut Self| {
+			sc.base.run.results.valid = 0;
+			sc.base.regenerate_dots(density)?;
+			sc.score_surfaces(true)
 		};
-		self.base.run.results.surfaces[my].d_mean = distmin_sum / d_len;
-		self.base.run.results.surfaces[my].d_median = d_median_val;
-		self.base.run.results.surfaces[my].s_mean = score_sum / s_len * -1.0;
-		self.base.run.results.surfaces[my].s_median = s_median_val;
+		match self.base.thread_pool() {
+			Some(pool) => pool.install(|| run(self)),
+			None => run(self),
+		}
+	}
+
+	/// Local Sc in slabs of `width` Å stepped by `step` Å along the interface major axis of the last
+	/// `calc()`; slabs without paired dots are omitted
+	pub fn sc_profile(&self, width: ScValue, step: ScValue) -> Vec<ProfileBin> {
+		interface::sc_profile(&self.base.run, &self.base.run.results.geometry, width, step)
+	}
+
+	/// Sc restricted to trimmed dots of the given kinds, from the pairing of the last `calc()`; e.g.
+	/// `&[DotKind::Reentrant]` for the share of complementarity carried by reentrant surface
+	pub fn dot_kind_sc(&self, kinds: &[DotKind]) -> DotKindSc { interface::dot_kind_sc(&self.base.run, kinds) }
+
+	/// Sc restricted to trimmed dots of atoms in the named residue types, from the pairing of the
+	/// last `calc()`; e.g. `interface::AROMATIC_RESIDUES` for aromatic packing alongside the global Sc
+	pub fn residue_type_sc(&self, residues: &[&str]) -> ResidueTypeSc { interface::residue_type_sc(&self.base.run, residues) }
+
+	/// Achieved contact dot density of the last surface generation against the target, per surface
+	/// and per atom, over each atom's geometric accessible area; atoms below `min_fraction` of their
+	/// target (tiny radii, heavy occlusion, or no dots at all) are flagged `undersampled`
+	pub fn density_report(&self, min_fraction: ScValue) -> DensityReport { interface::density_report(&self.base.run, &self.base.settings, min_fraction) }
+
+	/// Sc restricted to each named residue region, from the pairing of the last `calc()`
+	pub fn region_sc(&self, regions: &[Region]) -> Vec<RegionSc> {
+		regions.iter().map(|r| regions::region_sc(&self.base.run, r)).collect()
+	}
+
+	/// Sc with each trimmed dot weighted by its parent atom's weight (last matching entry, else 1),
+	/// from the pairing of the last `calc()`; e.g. up-weight a paratope for paratope-focused scoring
+	pub fn weighted_sc(&self, weights: &[AtomWeight]) -> WeightedSc { weights::weighted_sc(&self.base.run, weights) }
+
+	/// Recompute the Sc statistic over a grid of Gaussian weights and peripheral bands, reusing the
+	/// generated dot surfaces. `calc()` runs first when there are none, or when they were generated
+	/// under settings that have changed since (probe radius, density, trimming, statistic, ..).
+	/// Otherwise the stored results are left untouched.
+	/// `gaussian_w` values only affect weight functions that use it (the default Gaussian), and bands
+	/// only matter with `TrimMode::PeripheralBand`. Streamed surfaces keep no exposed dots, so they
+	/// can only be trimmed with the configured `peripheral_band`.
+	pub fn sweep(&mut self, gaussian_ws: &[ScValue], bands: &[ScValue]) -> Result<Vec<SweepPoint>, SurfaceCalculatorError> {
+		let current = Some(self.base.settings.surface_fingerprint());
+		if self.base.run.results.valid != 1 || self.base.run.scored_with != current { self.calc()?; }
+		if self.base.run.peripheral.is_some() && bands.iter().any(|&b| b != self.base.settings.peripheral_band) {
+			return Err(SurfaceCalculatorError::InvalidSettings("streamed surfaces can only be swept at the configured peripheral band".into()));
+		}
+		match self.base.thread_pool() {
+			Some(pool) => pool.install(|| self.sweep_in_pool(gaussian_ws, bands)),
+			None => self.sweep_in_pool(gaussian_ws, bands),
+		}
+	}
+
+	fn sweep_in_pool(&self, gaussian_ws: &[ScValue], bands: &[ScValue]) -> Result<Vec<SweepPoint>, SurfaceCalculatorError> {
+		let mut points = Vec::with_capacity(gaussian_ws.len() * bands.len());
+		for &band in bands {
+			let (t0, a0) = self.select_trimmed_dots(0, band);
+			let (t1, a1) = self.select_trimmed_dots(1, band);
+			// Pairing does not depend on the weight, so pair once per band and rescore per w
+			let p0 = self.pair_dots(0, 1, &t0, &t1, self.base.settings.gaussian_w);
+			let p1 = self.pair_dots(1, 0, &t1, &t0, self.base.settings.gaussian_w);
+			self.base.check_cancelled()?;
+			for &w in gaussian_ws {
+				let mut stats = [(0.0, 0.0); 2];
+				for (my, pairs) in [&p0, &p1].into_iter().enumerate() {
+					if pairs.is_empty() { continue; }
+					let mut scores: Vec<f64> = pairs.iter().map(|p| Self::score(p.normal_dot, p.distance, &self.base.settings.weight, w)).collect();
+					let mut distances: Vec<f64> = pairs.iter().map(|p| p.distance).collect();
+					stats[my] = (median(&mut scores), median(&mut distances));
+				}
+				points.push(SweepPoint {
+					gaussian_w: w,
+					peripheral_band: band,
+					sc: (stats[0].0 + stats[1].0) / 2.0,
+					distance: (stats[0].1 + stats[1].1) / 2.0,
+					area: a0 + a1,
+				});
+			}
+		}
+		Ok(points)
+	}
+
+	/// Interface residue pairs bridged by one of `waters` (e.g. crystallographic HOH oxygens), each
+	/// water lying within `cutoff` Å (3.5 for a hydrogen bond) of an N/O atom on both molecules.
+	/// Independent of the Sc calculation; waters should not be added as atoms.
+	pub fn water_bridges(&self, waters: &[Atom], cutoff: ScValue) -> Vec<WaterBridge> {
+		interface::water_bridges(&self.base.run.atoms, waters, cutoff)
 	}
 
 	pub fn add_atom(&mut self, molecule: i32, atom: Atom) -> Result<(), SurfaceCalculatorError> { self.base.add_atom(molecule, atom) }
+	/// Replace the atoms with `mol1` and `mol2` (radii assigned where missing) and compute Sc with the
+	/// current settings
+	pub fn calc_atoms(&mut self, mol1: &[Atom], mol2: &[Atom]) -> Result<Results, SurfaceCalculatorError> {
+		self.reset();
+		self.add_atoms(0, mol1.iter().cloned())?;
+		self.add_atoms(1, mol2.iter().cloned())?;
+		self.calc()
+	}
+
+	/// Sc between two chain selections of a PDB file (PQR by `.pqr` extension), each one chain ID or
+	/// a comma-separated list (`"H,L"`); see `pdb::read_pdb_atoms_from_str` for which records are read
+	pub fn calc_from_pdb(&mut self, path: &str, selection1: &str, selection2: &str) -> Result<Results, SurfaceCalculatorError> {
+		let (mol1, mol2, warnings) = pdb::read_structure_atoms_with_warnings_from_path(path, selection1, selection2)?;
+		for (atoms, selection) in [(&mol1, selection1), (&mol2, selection2)] {
+			if atoms.is_empty() { return Err(SurfaceCalculatorError::EmptySelection { selection: selection.to_string(), path: path.to_string() }); }
+		}
+		self.reset();
+		self.add_warnings(warnings);
+		self.add_atoms(0, mol1)?;
+		self.add_atoms(1, mol2)?;
+		self.calc()
+	}
+
+	/// Record non-fatal input issues (e.g. from `pdb::read_structure_atoms_with_warnings_from_path`)
+	/// in `Results::warnings`, where they stay until `reset()` like the atoms they concern
+	pub fn add_warnings(&mut self, warnings: impl IntoIterator<Item = Warning>) { Warning::merge(&mut self.base.run.results.warnings, warnings); }
+
+	/// Add a whole molecule at once (see `SurfaceGenerator::add_atoms`); returns the number added
+	pub fn add_atoms(&mut self, molecule: i32, atoms: impl IntoIterator<Item = Atom>) -> Result<usize, SurfaceCalculatorError> { self.base.add_atoms(molecule, atoms) }
+	/// Add occluding atoms that belong to neither scored molecule (see `SurfaceGenerator::add_context_atoms`)
+	pub fn add_context_atoms(&mut self, atoms: impl IntoIterator<Item = Atom>) -> Result<usize, SurfaceCalculatorError> { self.base.add_context_atoms(atoms) }
+
+	/// Generate and keep molecule 1's surface so later `calc()` calls only build molecule 2
+	/// (see `SurfaceGenerator::freeze_molecule1`); returns the number of frozen dots
+	pub fn freeze_molecule1(&mut self) -> Result<usize, SurfaceCalculatorError> { self.base.freeze_molecule1() }
+	pub fn unfreeze_molecule1(&mut self) { self.base.unfreeze_molecule1(); }
+
+	/// Replace molecule 2 with `atoms` (radii assigned as in `add_atom`) and compute Sc, reusing the
+	/// frozen molecule 1 surface when there is one
+	pub fn score_molecule2(&mut self, atoms: Vec<Atom>) -> Result<Results, SurfaceCalculatorError> {
+		self.base.clear_molecule(1);
+		self.base.add_atoms(1, atoms)?;
+		self.calc()
+	}
+
+	/// Move atoms (indices into the combined atom list) and recompute, regenerating only the surface
+	/// around them (see `SurfaceGenerator::move_atoms`)
+	pub fn update_atoms(&mut self, indices: &[usize], coords: &[Vec3]) -> Result<Results, SurfaceCalculatorError> {
+		self.base.move_atoms(indices, coords)?;
+		self.calc()
+	}
+
+	/// Sc of molecule 2 moved by each transform (applied to its current coordinates), one `Results`
+	/// per pose. Molecule 1's surface is frozen first if it is not already, molecule 2 keeps the radii
+	/// assigned when it was added, and its original coordinates are restored afterwards. Poses are
+	/// only trimmed, paired and scored; the interface reports are computed too when
+	/// `Settings::pose_reports` is set.
+	pub fn score_poses(&mut self, poses: &[RigidTransform]) -> Result<Vec<Results>, SurfaceCalculatorError> {
+		if !self.base.is_molecule1_frozen() { self.freeze_molecule1()?; }
+		let reports = self.base.settings.pose_reports;
+		let ligand: Vec<Atom> = self.base.run.atoms.iter().filter(|a| a.molecule == 1 && !a.context).cloned().collect();
+		let scored: Result<Vec<Results>, SurfaceCalculatorError> = poses.iter().map(|pose| {
+			let moved: Vec<Atom> = ligand.iter().cloned().map(|mut a| { a.coor = pose.apply(a.coor); a }).collect();
+			self.base.clear_molecule(1);
+			self.base.add_atoms(1, moved)?;
+			self.calc_with(reports)
+		}).collect();
+		self.base.clear_molecule(1);
+		self.base.add_atoms(1, ligand)?;
+		scored
+	}
+
+	/// Cheap estimate for ranking: count the contacts, and when there are any compute Sc from
+	/// surfaces sampled at `density` dots/Å² (at 2, about an eighth of the dots of the default 15),
+	/// trimming, pairing and scoring them without the interface reports. Rank the estimates of many
+	/// structures with `Prescreen::select` and run the full `calc()` on the best. The calculator
+	/// holds the coarse run; the dot densities are restored afterwards.
+	pub fn prescreen(&mut self, density: ScValue) -> Result<Prescreen, SurfaceCalculatorError> {
+		let grid = self.contact_grid();
+		let ligand: Vec<&Atom> = self.base.run.atoms.iter().filter(|a| a.molecule == 1 && !a.context).collect();
+		let contacts = self.count_contacts(&grid, ligand);
+		self.at_density(density, |sc| {
+			if contacts == 0 { return Ok(Prescreen { contacts, sc: None, area: 0.0 }); }
+			let r = sc.calc_with(false)?;
+			Ok(Prescreen { contacts, sc: Some(r.sc), area: r.area })
+		})
+	}
+
+	/// `prescreen` for each pose of `score_poses`: molecule 1's surface is frozen at `density`, poses
+	/// without contacts are rejected before any surface is built, and the rest are scored on coarse
+	/// surfaces without the interface reports. One estimate per pose, in pose order. Molecule 2 is
+	/// restored afterwards, as are the densities and any frozen molecule 1 surface (rebuilt at
+	/// full density).
+	pub fn prescreen_poses(&mut self, poses: &[RigidTransform], density: ScValue) -> Result<Vec<Prescreen>, SurfaceCalculatorError> {
+		let was_frozen = self.base.is_molecule1_frozen();
+		let grid = self.contact_grid();
+		let ligand: Vec<Atom> = self.base.run.atoms.iter().filter(|a| a.molecule == 1 && !a.context).cloned().collect();
+		let screened = self.at_density(density, |sc| {
+			sc.base.unfreeze_molecule1();
+			sc.freeze_molecule1()?;
+			poses.iter().map(|pose| {
+				let moved: Vec<Atom> = ligand.iter().cloned().map(|mut a| { a.coor = pose.apply(a.coor); a }).collect();
+				let contacts = sc.count_contacts(&grid, &moved);
+				if contacts == 0 { return Ok(Prescreen { contacts, sc: None, area: 0.0 }); }
+				sc.base.clear_molecule(1);
+				sc.base.add_atoms(1, moved)?;
+				let r = sc.calc_with(false)?;
+				Ok(Prescreen { contacts, sc: Some(r.sc), area: r.area })
+			}).collect()
+		});
+		self.base.unfreeze_molecule1();
+		self.base.clear_molecule(1);
+		self.base.add_atoms(1, ligand)?;
+		if was_frozen { self.freeze_molecule1()?; }
+		screened
+	}
+
+	/// Atoms (context atoms aside) binned for contact queries against molecule 1; the cell bounds the
+	/// largest contact distance, two radii and both probe diameters
+	fn contact_grid(&self) -> BurialGrid {
+		let radmax = self.base.run.atoms.iter().map(|a| a.radius).fold(0.0, ScValue::max);
+		let cell = 2.0 * radmax + self.base.settings.probe_radius(0) + self.base.settings.probe_radius(1);
+		BurialGrid::new(&AtomArrays::from_atoms(&self.base.run.atoms), cell)
+	}
+
+	/// Atoms of `ligand` within contact distance (both radii plus both probe radii) of molecule 1
+	fn count_contacts<'a>(&self, grid: &BurialGrid, ligand: impl IntoIterator<Item = &'a Atom>) -> usize {
+		let rps = self.base.settings.probe_radius(0) + self.base.settings.probe_radius(1);
+		ligand.into_iter().filter(|a| grid.is_buried_by(0, a.coor, a.radius + rps)).count()
+	}
+
+	/// Run `f` with every atom sampled at `density` dots/Å², restoring the density settings after
+	fn at_density<R>(&mut self, density: ScValue, f: impl FnOnce(&mut Self) -> Result<R, SurfaceCalculatorError>) -> Result<R, SurfaceCalculatorError> {
+		let saved = (self.base.settings.dot_density, self.base.settings.dot_density_molecule, self.base.settings.adaptive_density);
+		self.base.settings.dot_density = density;
+		self.base.settings.dot_density_molecule = [None, None];
+		self.base.settings.adaptive_density = None;
+		let res = self.base.settings.validate().and_then(|_| f(self));
+		(self.base.settings.dot_density, self.base.settings.dot_density_molecule, self.base.settings.adaptive_density) = saved;
+		res
+	}
+	/// Force the attention of atoms at `indices` (into `base.atoms()`) for later `calc()` calls; see
+	/// `SurfaceGenerator::override_attention`
+	pub fn override_attention(&mut self, indices: &[usize], attention: Option<Attention>) -> Result<(), SurfaceCalculatorError> { self.base.override_attention(indices, attention) }
+	/// Clear atoms and run state for the next job, keeping settings, radii and allocations
 	pub fn reset(&mut self) { self.base.reset(); }
 	pub fn results(&self) -> &Results { &self.base.run.results }
+	/// Per-dot pairing of the last `calc()`: one entry per trimmed dot of `molecule` that found a
+	/// partner, with its distance, normal product, and weighted S value. `dot`/`neighbor` index
+	/// into `base.dots(molecule)` and `base.dots(1 - molecule)`.
+	pub fn dot_pairs(&self, molecule: usize) -> &[DotPair] { &self.base.run.pairs[molecule] }
+	/// Dots of `molecule` that survived peripheral trimming in the last `calc()`, the exact set
+	/// behind the Sc statistic (see `SurfaceGenerator::trimmed_dot_views`)
+	pub fn trimmed_dots(&self, molecule: usize) -> impl Iterator<Item = DotView<'_>> + '_ { self.base.trimmed_dot_views(molecule) }
+	/// Total area of `trimmed_dots(molecule)`
+	pub fn trimmed_area(&self, molecule: usize) -> ScValue { self.base.trimmed_dot_area(molecule) }
+	/// Mean curvature (Å^-1, positive convex) at every dot of `molecule`, in the order of `dots(molecule)`,
+	/// estimated over `Settings::curvature_radius`
+	pub fn dot_curvatures(&self, molecule: usize) -> Vec<ScValue> {
+		let dots = &self.base.run.dots[molecule];
+		curvature::curvatures(dots, &(0..dots.len()).collect::<Vec<_>>(), self.base.settings.curvature_radius, self.base.settings.enable_parallel)
+	}
+	/// 3D Zernike descriptor up to `order` of the buried surface patch of `molecule` in the last
+	/// `calc()`, for comparing and clustering interfaces across complexes
+	pub fn patch_zernike(&self, molecule: usize, order: usize) -> ZernikeDescriptor { zernike::patch_descriptor(&self.base.run, molecule, order) }
+	/// Atoms of `molecule` that own at least one buried dot in the last `calc()`, with their buried
+	/// and trimmed areas; keep `n_trimmed_dots > 0` for the trimmed interface core. `atom_index`
+	/// indexes `base.atoms()`.
+	pub fn interface_atoms(&self, molecule: usize) -> Vec<InterfaceAtom> { interface::interface_atoms(&self.base.run, molecule) }
+}
+
+/// Median by selection (upper median for even lengths), matching the reference statistic
+pub(crate) fn median(values: &mut [f64]) -> f64 {
+	if values.is_empty() { return 0.0; }
+	let median_idx = values.len()/2;
+	let (_, m, _) = values.select_nth_unstable_by(median_idx, |a,b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+	*m
+}
+
+/// Evaluate a summary statistic; the median keeps the reference upper-median convention
+pub(crate) fn statistic(values: &mut [f64], stat: ScStatistic) -> f64 {
+	if values.is_empty() { return 0.0; }
+	match stat {
+		ScStatistic::Median => median(values),
+		ScStatistic::Mean => values.iter().sum::<f64>() / values.len() as f64,
+		ScStatistic::TrimmedMean(fraction) => {
+			values.sort_unstable_by(|a,b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+			let cut = ((values.len() as f64) * fraction.clamp(0.0, 0.5)).floor() as usize;
+			let kept = if 2 * cut < values.len() { &values[cut..values.len() - cut] } else { &values[..] };
+			kept.iter().sum::<f64>() / kept.len() as f64
+		}
+		ScStatistic::Quantile(q) => {
+			values.sort_unstable_by(|a,b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+			let pos = q.clamp(0.0, 1.0) * (values.len() - 1) as f64;
+			let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+			values[lo] + (values[hi] - values[lo]) * (pos - lo as f64)
+		}
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+
+	/// Two jittered 4×4×3 blocks of atoms facing each other along x
+	pub(crate) fn complex() -> (Vec<Atom>, Vec<Atom>) {
+		let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+		let mut jitter = || {
+			seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+			((seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * 0.6
+		};
+		let mut block = |x0: f64, chain: &str| -> Vec<Atom> {
+			let mut atoms = Vec::new();
+			for i in 0..3 { for j in 0..4 { for k in 0..4 {
+				let mut a = Atom::new();
+				a.atom = "CB".to_string();
+				a.residue = "ALA".to_string();
+				a.chain = chain.to_string();
+				a.resnum = atoms.len() as i32 + 1;
+				a.radius = 1.8;
+				a.coor = Vec3::new(x0 + 3.0 * i as f64 + jitter(), 3.0 * j as f64 + jitter(), 3.0 * k as f64 + jitter());
+				atoms.push(a);
+			} } }
+			atoms
+		};
+		let mol1 = block(0.0, "A");
+		let mol2 = block(9.8, "B");
+		(mol1, mol2)
+	}
+
+	fn calculator() -> ScCalculator {
+		let (mol1, mol2) = complex();
+		let mut sc = ScCalculator::new();
+		sc.add_atoms(0, mol1).unwrap();
+		sc.add_atoms(1, mol2).unwrap();
+		sc
+	}
+
+	#[test]
+	fn density_scan_matches_separate_runs() {
+		let mut sc = calculator();
+		let scan = sc.density_scan(&[10.0, 20.0]).unwrap();
+		for point in &scan {
+			let mut direct = calculator();
+			direct.settings_mut().dot_density = point.density;
+			let r = direct.calc().unwrap();
+			assert!((r.sc - point.sc).abs() < 1e-9, "density {}: scan {} vs calc {}", point.density, point.sc, r.sc);
+			assert!((r.area - point.area).abs() < 1e-9);
+			assert_eq!(r.combined.n_trimmed_dots, point.n_dots);
+		}
+	}
+
+	#[test]
+	fn sweep_regenerates_after_settings_change() {
+		let mut sc = calculator();
+		sc.calc().unwrap();
+		sc.settings_mut().rp = 1.2;
+		let (w, band) = (sc.settings().gaussian_w, sc.settings().peripheral_band);
+		let swept = sc.sweep(&[w], &[band]).unwrap();
+		let mut direct = calculator();
+		direct.settings_mut().rp = 1.2;
+		let r = direct.calc().unwrap();
+		assert!((swept[0].sc - r.sc).abs() < 1e-9, "sweep {} vs calc {}", swept[0].sc, r.sc);
+	}
+
+	#[test]
+	fn density_scan_overrides_per_molecule_density() {
+		let mut sc = calculator();
+		sc.settings_mut().dot_density_molecule = [Some(15.0), Some(15.0)];
+		let scan = sc.density_scan(&[5.0, 30.0]).unwrap();
+		assert!(scan[1].n_dots > 2 * scan[0].n_dots);
+		assert_eq!(sc.settings().dot_density_molecule, [Some(15.0), Some(15.0)]);
+	}
+
+	#[test]
+	fn score_poses_skips_reports_unless_asked() {
+		let mut sc = calculator();
+		let full = sc.calc().unwrap();
+		assert!(full.gap_volume > 0.0 && !full.contacts.is_empty());
+		let lean = sc.score_poses(&[RigidTransform::identity()]).unwrap();
+		assert!((lean[0].sc - full.sc).abs() < 0.01, "pose {} vs calc {}", lean[0].sc, full.sc);
+		assert_eq!(lean[0].gap_volume, 0.0);
+		assert!(lean[0].contacts.is_empty());
+		sc.settings_mut().pose_reports = true;
+		let reported = sc.score_poses(&[RigidTransform::identity()]).unwrap();
+		assert_eq!(reported[0].sc, lean[0].sc);
+		assert!(reported[0].gap_volume > 0.0 && !reported[0].contacts.is_empty());
+	}
+
+	#[test]
+	fn density_report_measures_geometric_area() {
+		let mut sc = calculator();
+		sc.calc().unwrap();
+		let report = sc.density_report(0.5);
+		for s in &report.surfaces {
+			assert!((s.achieved / s.target - 1.0).abs() < 0.25, "achieved {} vs target {}", s.achieved, s.target);
+		}
+		let atom = report.atoms.iter().find(|a| a.n_dots > 0 && !a.undersampled).unwrap().atom_index;
+		for dots in &mut sc.base.run.dots { dots.retain(|d| d.atom_index != atom || d.kind != DotKind::Contact); }
+		let starved = sc.density_report(0.5);
+		let entry = starved.atoms.iter().find(|a| a.atom_index == atom).expect("accessible atom without dots is listed");
+		assert!(entry.undersampled && entry.n_dots == 0 && entry.area > 0.0);
+	}
+
+	#[test]
+	fn calc_converged_steps_the_sampled_density() {
+		let mut sc = calculator();
+		sc.settings_mut().dot_density_molecule = [Some(15.0), Some(15.0)];
+		let conv = sc.calc_converged(0.0, 2.0, 3).unwrap();
+		assert_eq!(conv.steps.iter().map(|s| s.density).collect::<Vec<_>>(), [15.0, 30.0, 60.0]);
+		assert!(!conv.converged, "per-molecule densities must not pin Sc: {:?}", conv.steps);
+		assert!(conv.steps.windows(2).all(|w| w[0].sc != w[1].sc));
+		assert_eq!(sc.settings().dot_density_molecule, [Some(15.0), Some(15.0)]);
+		let mut direct = calculator();
+		direct.settings_mut().dot_density = 60.0;
+		assert!((direct.calc().unwrap().sc - conv.sc).abs() < 1e-9);
+	}
+
+	#[test]
+	fn calc_converged_stops_at_density_cap() {
+		// Two small atoms keep the capped density cheap
+		let mut sc = ScCalculator::new();
+		for (m, x) in [(0, 0.0), (1, 1.6)] {
+			let mut a = Atom::new();
+			a.atom = "CB".to_string();
+			a.residue = "ALA".to_string();
+			a.radius = 0.5;
+			a.coor = Vec3::new(x, 0.0, 0.0);
+			sc.add_atom(m, a).unwrap();
+		}
+		sc.settings_mut().rp = 0.5;
+		sc.settings_mut().dot_density = 400.0;
+		let conv = sc.calc_converged(0.0, 3.0, 5).unwrap();
+		assert_eq!(conv.steps.iter().map(|s| s.density).collect::<Vec<_>>(), [400.0, MAX_DOT_DENSITY]);
+		assert!(!conv.converged);
+		assert!(matches!(sc.calc_converged(0.01, 1.0, 5), Err(SurfaceCalculatorError::InvalidSettings(_))));
+		assert!(matches!(sc.calc_converged(ScValue::NAN, 1.5, 5), Err(SurfaceCalculatorError::InvalidSettings(_))));
+	}
+
+	/// `update_atoms` after a full run against a fresh `calc_atoms` on the moved coordinates
+	fn assert_update_matches_fresh(moves: &[(usize, Vec3)]) -> Results {
+		let mut sc = calculator();
+		sc.calc().unwrap();
+		let (indices, coords): (Vec<usize>, Vec<Vec3>) = moves.iter().copied().unzip();
+		let updated = sc.update_atoms(&indices, &coords).unwrap();
+		let (mut mol1, mut mol2) = complex();
+		let n1 = mol1.len();
+		for &(i, c) in moves {
+			if i < n1 { mol1[i].coor = c; } else { mol2[i - n1].coor = c; }
+		}
+		let fresh = ScCalculator::new().calc_atoms(&mol1, &mol2).unwrap();
+		for m in 0..2 {
+			let (u, f) = (&updated.surfaces[m], &fresh.surfaces[m]);
+			assert_eq!((u.n_buried_atoms, u.n_all_dots, u.n_trimmed_dots), (f.n_buried_atoms, f.n_all_dots, f.n_trimmed_dots), "molecule {}", m + 1);
+			assert!((u.trimmed_area - f.trimmed_area).abs() < 1e-9, "molecule {}: area {} vs {}", m + 1, u.trimmed_area, f.trimmed_area);
+		}
+		assert!((updated.sc - fresh.sc).abs() < 1e-9, "update {} vs fresh {}", updated.sc, fresh.sc);
+		updated
+	}
+
+	/// Position of atom `i` of `complex()` (molecule 1 first) shifted by `dx` along x
+	fn shifted(i: usize, dx: f64) -> (usize, Vec3) {
+		let (mol1, mol2) = complex();
+		let a = mol1.iter().chain(&mol2).nth(i).unwrap();
+		(i, a.coor + Vec3::new(dx, 0.0, 0.0))
+	}
+
+	#[test]
+	fn update_atoms_on_molecule1_matches_fresh_run() {
+		// Interface layer of the first block
+		assert_update_matches_fresh(&[shifted(37, 0.4), shifted(38, -0.3)]);
+	}
+
+	#[test]
+	fn update_atoms_on_molecule2_matches_fresh_run() {
+		assert_update_matches_fresh(&[shifted(58, -0.4)]);
+	}
+
+	#[test]
+	fn update_atoms_across_separation_cutoff_matches_fresh_run() {
+		let before = calculator().calc().unwrap();
+		// An interface atom of molecule 1 moved behind its block, beyond the 8 Å cutoff
+		let updated = assert_update_matches_fresh(&[shifted(37, -11.0)]);
+		assert_eq!(updated.surfaces[0].n_buried_atoms + 1, before.surfaces[0].n_buried_atoms);
+	}
+
+	#[test]
+	fn update_atoms_rejects_length_mismatch() {
+		let mut sc = calculator();
+		assert!(matches!(sc.update_atoms(&[0, 1], &[Vec3::zero()]), Err(SurfaceCalculatorError::InvalidSettings(_))));
+		assert!(matches!(sc.update_atoms(&[1000], &[Vec3::zero()]), Err(SurfaceCalculatorError::JumpOutOfBounds)));
+	}
+
+	#[test]
+	fn score_molecule2_matches_calc() {
+		let (_, mol2) = complex();
+		let expected = calculator().calc().unwrap();
+		let mut sc = calculator();
+		assert!(sc.freeze_molecule1().unwrap() > 0);
+		let got = sc.score_molecule2(mol2).unwrap();
+		assert!(sc.base.is_molecule1_frozen());
+		assert!((got.sc - expected.sc).abs() < 1e-9, "Sc {} vs {}", got.sc, expected.sc);
+		for m in 0..2 {
+			let (g, e) = (&got.surfaces[m], &expected.surfaces[m]);
+			assert_eq!((g.n_buried_atoms, g.n_trimmed_dots), (e.n_buried_atoms, e.n_trimmed_dots), "molecule {}", m + 1);
+			assert!((g.trimmed_area - e.trimmed_area).abs() < 1e-9, "molecule {}", m + 1);
+		}
+	}
+
+	#[test]
+	fn frozen_surface_follows_settings_changes() {
+		let (_, mol2) = complex();
+		let mut sc = calculator();
+		sc.freeze_molecule1().unwrap();
+		sc.settings_mut().dot_density = 8.0;
+		sc.settings_mut().rp = 1.2;
+		let got = sc.score_molecule2(mol2).unwrap();
+		let mut direct = calculator();
+		direct.settings_mut().dot_density = 8.0;
+		direct.settings_mut().rp = 1.2;
+		let expected = direct.calc().unwrap();
+		assert!((got.sc - expected.sc).abs() < 1e-9, "Sc {} vs {}", got.sc, expected.sc);
+		assert!((got.surfaces[0].trimmed_area - expected.surfaces[0].trimmed_area).abs() < 1e-9);
+		assert_eq!(sc.base.run.frozen.as_ref().map(|f| f.fingerprint.clone()), Some(sc.settings().surface_fingerprint()));
+	}
+
+	#[test]
+	fn context_atom_occludes_both_surfaces_once() {
+		let plain = calculator().calc().unwrap();
+		let mut sc = calculator();
+		// At the rim of the gap between the two blocks, touching both interface faces
+		let mut occluder = Atom::new();
+		occluder.atom = "C1".to_string();
+		occluder.residue = "LIG".to_string();
+		occluder.chain = "C".to_string();
+		occluder.radius = 1.8;
+		occluder.coor = Vec3::new(7.9, -1.5, 4.5);
+		assert_eq!(sc.add_context_atoms([occluder]).unwrap(), 1);
+		let got = sc.calc().unwrap();
+		assert_eq!((got.n_context_atoms, got.n_atoms), (1, plain.n_atoms + 1));
+		assert_eq!([got.surfaces[0].n_atoms, got.surfaces[1].n_atoms], [plain.surfaces[0].n_atoms, plain.surfaces[1].n_atoms]);
+		assert_eq!(sc.base.run.atoms.iter().filter(|a| a.context).count(), 2);
+		for m in 0..2 {
+			assert!(got.surfaces[m].trimmed_area < plain.surfaces[m].trimmed_area, "molecule {}: {} vs {}", m + 1, got.surfaces[m].trimmed_area, plain.surfaces[m].trimmed_area);
+			assert!(got.surfaces[m].bsa < plain.surfaces[m].bsa, "molecule {}: buried {} vs {}", m + 1, got.surfaces[m].bsa, plain.surfaces[m].bsa);
+			assert!(sc.base.run.dots[m].iter().all(|d| !sc.base.run.atoms[d.atom_index].context));
+		}
+	}
+
+	/// The complex with a copy of interface atom 37, 0.005 Å away, inserted after it (coincidence
+	/// is only checked for atoms that get dots)
+	fn complex_with_duplicate() -> (Vec<Atom>, Vec<Atom>) {
+		let (mut mol1, mol2) = complex();
+		let mut copy = mol1[37].clone();
+		copy.atom = "CG".to_string();
+		copy.coor.x += 0.005;
+		mol1.insert(38, copy);
+		(mol1, mol2)
+	}
+
+	#[test]
+	fn drop_coincident_atoms_skips_and_renumbers() {
+		let plain = calculator().calc().unwrap();
+		let (mol1, mol2) = complex_with_duplicate();
+		let moved = mol1[39].coor;
+		let mut sc = ScCalculator::new();
+		sc.settings_mut().drop_coincident_atoms = true;
+		sc.add_atoms(0, mol1).unwrap();
+		sc.add_atoms(1, mol2).unwrap();
+		let got = sc.calc().unwrap();
+		assert_eq!(got.skipped_atoms.len(), 1);
+		let skipped = &got.skipped_atoms[0];
+		assert_eq!((skipped.molecule, skipped.atom.as_str()), (0, "CG"));
+		assert!(skipped.reason.contains("Overlapping atoms"), "{}", skipped.reason);
+		assert_eq!((got.n_atoms, got.surfaces[0].n_atoms), (plain.n_atoms, plain.surfaces[0].n_atoms));
+		assert!(sc.base.run.atoms.iter().enumerate().all(|(i, a)| a.natom == (i + 1) as i32 && a.atom == "CB"));
+		assert_eq!(sc.base.run.atoms[38].coor, moved);
+		assert!((got.sc - plain.sc).abs() < 1e-12);
+	}
+
+	#[test]
+	fn coincident_atoms_fail_without_dropping() {
+		let (mol1, mol2) = complex_with_duplicate();
+		let mut sc = ScCalculator::new();
+		sc.add_atoms(0, mol1).unwrap();
+		sc.add_atoms(1, mol2).unwrap();
+		assert!(matches!(sc.calc(), Err(SurfaceCalculatorError::Coincident(_))));
+		sc.settings_mut().coincident_tolerance = 0.001;
+		assert!(sc.calc().is_ok());
+	}
+
+	#[test]
+	fn streaming_matches_full_surfaces() {
+		for (parallel, trim_mode) in [(false, TrimMode::PeripheralBand), (true, TrimMode::PeripheralBand), (true, TrimMode::PartnerDistance(2.0))] {
+			let mut full = calculator();
+			full.settings_mut().enable_parallel = parallel;
+			full.settings_mut().trim_mode = trim_mode;
+			let expected = full.calc().unwrap();
+			let mut streamed = calculator();
+			streamed.settings_mut().enable_parallel = parallel;
+			streamed.settings_mut().trim_mode = trim_mode;
+			streamed.settings_mut().streaming = true;
+			let got = streamed.calc().unwrap();
+			assert!((got.sc - expected.sc).abs() < 1e-12, "{trim_mode:?}: Sc {} vs {}", got.sc, expected.sc);
+			for m in 0..2 {
+				let (g, e) = (&got.surfaces[m], &expected.surfaces[m]);
+				assert_eq!((g.n_all_dots, g.n_trimmed_dots), (e.n_all_dots, e.n_trimmed_dots), "{trim_mode:?} molecule {}", m + 1);
+				assert!((g.trimmed_area - e.trimmed_area).abs() < 1e-9, "{trim_mode:?} molecule {}", m + 1);
+				assert!((g.d_median - e.d_median).abs() < 1e-12 && (g.bsa - e.bsa).abs() < 1e-9, "{trim_mode:?} molecule {}", m + 1);
+				assert!(streamed.base.run.dots[m].len() < full.base.run.dots[m].len());
+				assert!(streamed.base.run.dots[m].iter().all(|d| d.buried));
+			}
+		}
+	}
+
+	#[test]
+	fn streaming_sweeps_only_the_configured_band() {
+		let mut sc = calculator();
+		sc.settings_mut().streaming = true;
+		let (w, band) = (sc.settings().gaussian_w, sc.settings().peripheral_band);
+		let swept = sc.sweep(&[w], &[band]).unwrap();
+		assert!((swept[0].sc - sc.base.run.results.sc).abs() < 1e-12);
+		assert!(matches!(sc.sweep(&[w], &[band + 0.5]), Err(SurfaceCalculatorError::InvalidSettings(_))));
+	}
 }
@@ -1,38 +1,447 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::sc::atomic_radii::{embedded_atomic_radii, embedded_calpha_radii, embedded_martini_radii, read_atomic_radii_from_path};
+use crate::sc::regions::ResidueRange;
+use crate::sc::surface_generator::SurfaceCalculatorError;
+use crate::sc::types::AtomRadius;
+use crate::sc::vector3::Vec3;
+
 /// Lawrence & Colman (1993), Fig. 1: Gaussian weight w = 0.5 Å^-2
 pub const GAUSSIAN_W: f64 = 0.5;
 /// Lawrence & Colman (1993): exclude band d = 1.5 Å from periphery
 pub const PERIPH_BAND: f64 = 1.5;
 /// Lawrence & Colman (1993): ~15 dots per Å^2 sufficient; doubling density does not materially change Sc
 pub const DOT_DENSITY: f64 = 15.0;
+/// Upper bound accepted for dot densities; far past the point where Sc stops changing, and
+/// sampling cost grows linearly with density
+pub const MAX_DOT_DENSITY: f64 = 1000.0;
+
+/// Distance weight applied to the normal product when forming S values.
+/// The default reproduces the Gaussian of Lawrence & Colman (1993) using `Settings::gaussian_w`.
+#[derive(Clone, Default)]
+pub enum WeightFunction {
+	/// exp(-w d^2) with w = `Settings::gaussian_w`
+	#[default]
+	Gaussian,
+	/// exp(-d / scale)
+	Exponential(f64),
+	/// 1 within the cutoff distance, 0 beyond
+	Step(f64),
+	/// Constant 1: orientation term only
+	Uniform,
+	/// User-supplied weight of the nearest-neighbor distance
+	Custom(Arc<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl WeightFunction {
+	pub fn custom(f: impl Fn(f64) -> f64 + Send + Sync + 'static) -> Self { WeightFunction::Custom(Arc::new(f)) }
+
+	pub fn weight(&self, d: f64, gaussian_w: f64) -> f64 {
+		match self {
+			WeightFunction::Gaussian => (-(d*d) * gaussian_w).exp(),
+			WeightFunction::Exponential(scale) => (-d / scale).exp(),
+			WeightFunction::Step(cutoff) => if d <= *cutoff { 1.0 } else { 0.0 },
+			WeightFunction::Uniform => 1.0,
+			WeightFunction::Custom(f) => f(d),
+		}
+	}
+}
+
+impl fmt::Debug for WeightFunction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			WeightFunction::Gaussian => write!(f, "Gaussian"),
+			WeightFunction::Exponential(scale) => write!(f, "Exponential({scale})"),
+			WeightFunction::Step(cutoff) => write!(f, "Step({cutoff})"),
+			WeightFunction::Uniform => write!(f, "Uniform"),
+			WeightFunction::Custom(_) => write!(f, "Custom(..)"),
+		}
+	}
+}
+
+/// Stage of a calculation reported to a `Progress` callback; the names follow `Timings`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+	/// Same-molecule neighbor and opposite-molecule burial tables (per atom)
+	Neighbors,
+	/// Probe placement between atom pairs, plus contact dots in serial runs (per atom)
+	Toroidal,
+	/// Contact dots of the parallel path (per atom)
+	Contact,
+	/// Concave dots of the probe spheres (per probe)
+	Concave,
+	/// Deferred burial classification, partial-surface merging and dot ordering
+	Burial,
+	Trimming,
+	Statistic,
+	Reports,
+}
+
+/// Progress callback, called with the phase, the work items done and the phase total. Each phase
+/// starts with `done == 0` and ends with `done == total`. The parallel sections call it from
+/// worker threads once per atom or probe, so it should be cheap.
+#[derive(Clone)]
+pub struct Progress(Arc<dyn Fn(Phase, usize, usize) + Send + Sync>);
+
+impl Progress {
+	pub fn new(f: impl Fn(Phase, usize, usize) + Send + Sync + 'static) -> Self { Progress(Arc::new(f)) }
+
+	pub fn report(&self, phase: Phase, done: usize, total: usize) { (self.0)(phase, done, total) }
+}
+
+impl fmt::Debug for Progress {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "Progress(..)") }
+}
+
+/// Summary statistic of the per-dot S values that defines Sc. Lawrence & Colman (1993) use the
+/// median because the S distribution is skewed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ScStatistic {
+	#[default]
+	Median,
+	Mean,
+	/// Mean after discarding this fraction of values from each tail (0 ≤ f < 0.5)
+	TrimmedMean(f64),
+	/// Quantile q in [0, 1] with linear interpolation
+	Quantile(f64),
+}
+
+/// How buried dots are trimmed to the interface before pairing
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TrimMode {
+	/// Drop dots within `peripheral_band` of any non-buried dot (Lawrence & Colman 1993)
+	#[default]
+	PeripheralBand,
+	/// Keep buried dots lying within this distance (Å) of a buried dot on the opposing surface;
+	/// suited to ring-shaped or highly curved interfaces where a fixed band erodes too much
+	PartnerDistance(f64),
+}
+
+/// Interface-adaptive sampling: atoms whose nearest partner atom is farther than `distance` Å are
+/// sampled at the reduced `density`; atoms closer keep the full per-molecule density
+#[derive(Clone, Copy, Debug)]
+pub struct AdaptiveDensity { pub distance: f64, pub density: f64 }
+
+/// Spatial restriction (`Settings::focus`): only atoms within `radius` Å of the center take part in
+/// surface generation; the rest are treated as far from the interface and cost almost nothing
+#[derive(Clone, Debug)]
+pub struct Focus { pub center: FocusCenter, pub radius: f64 }
+
+#[derive(Clone, Debug)]
+pub enum FocusCenter {
+	/// A fixed point, e.g. a catalytic site
+	Point(Vec3),
+	/// Every atom of these residues, on either molecule
+	Residues(Vec<ResidueRange>),
+}
+
+/// Atoms of a molecule that get surfaces (`Settings::atom_subset`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AtomSubset {
+	#[default]
+	All,
+	/// Main-chain atoms N, CA, C, O and OXT (the MARTINI bead BB), the scaffold
+	Backbone,
+	/// Everything else: side chains, including CB
+	SideChain,
+}
+
+impl AtomSubset {
+	/// Whether `atom` belongs to this subset
+	pub fn contains(self, atom: &crate::sc::types::Atom) -> bool {
+		let backbone = matches!(atom.atom.trim(), "N" | "CA" | "C" | "O" | "OXT" | "BB");
+		match self {
+			AtomSubset::All => true,
+			AtomSubset::Backbone => backbone,
+			AtomSubset::SideChain => !backbone,
+		}
+	}
+}
+
+/// Radii table used for atoms without an explicit radius, resolved when the first atom is added
+#[derive(Clone, Debug)]
+pub enum RadiiSource {
+	/// `atomic_radii.json` compiled into the crate
+	Embedded,
+	/// Radii JSON file; a missing or malformed file is an error
+	Path(String),
+	/// In-memory table
+	Inline(Vec<AtomRadius>),
+	/// Coarse Cα-only models (predicted or low-resolution structures): residue-dependent effective
+	/// radii for CA atoms from the embedded `calpha_radii.json`. Other atoms find no radius, so
+	/// select only CA atoms or set `skip_unassigned_atoms`.
+	CalphaOnly,
+	/// MARTINI coarse-grained models: bead radii from the embedded `martini_radii.json`, matched
+	/// on the bead names BB and SC1.. written by martinize
+	Martini,
+}
+
+impl RadiiSource {
+	pub fn load(&self) -> std::io::Result<Vec<AtomRadius>> {
+		match self {
+			RadiiSource::Embedded => Ok(embedded_atomic_radii()),
+			RadiiSource::Path(path) => read_atomic_radii_from_path(path),
+			RadiiSource::Inline(radii) => Ok(radii.clone()),
+			RadiiSource::CalphaOnly => Ok(embedded_calpha_radii()),
+			RadiiSource::Martini => Ok(embedded_martini_radii()),
+		}
+	}
+}
 
 #[derive(Clone, Debug)]
 pub struct Settings {
 	/// Probe radius (Connolly 1983)
 	pub rp: f64,
+	/// Optional per-molecule probe radii overriding `rp` (e.g. coarse-grained vs atomistic partner)
+	pub rp_molecule: [Option<f64>; 2],
 	/// Target dot density per Å^2 (Lawrence & Colman 1993)
 	pub dot_density: f64,
+	/// Optional per-molecule dot densities overriding `dot_density`
+	pub dot_density_molecule: [Option<f64>; 2],
+	/// Optional reduced density away from the interface (implementation choice)
+	pub adaptive_density: Option<AdaptiveDensity>,
+	/// Restrict surface generation to the neighborhood of a point or residues (implementation choice)
+	pub focus: Option<Focus>,
 	/// Peripheral exclusion band d in Å (Lawrence & Colman 1993)
 	pub peripheral_band: f64,
+	/// Interface trimming criterion (peripheral band by default)
+	pub trim_mode: TrimMode,
 	/// Heuristic separation cutoff for attention classification (implementation choice)
 	pub separation_cutoff: f64,
+	/// Derive the separation cutoff from the atoms instead: 2·r_max + 2·r_p, plus the peripheral
+	/// band when trimming by band. Atoms farther from the partner can own neither a buried dot nor an
+	/// exposed dot within the band of one, whatever the radii (e.g. coarse-grained beads).
+	/// `Results::separation_cutoff` reports the value used.
+	pub auto_separation_cutoff: bool,
 	/// Gaussian weight parameter w in Å^-2 (Lawrence & Colman 1993)
 	pub gaussian_w: f64,
+	/// Statistic reported as `Results::sc` (median by default)
+	pub statistic: ScStatistic,
+	/// Additional statistics reported in `Results::statistics` next to the primary one
+	pub extra_statistics: Vec<ScStatistic>,
+	/// Distance weighting in the S value (Gaussian by default)
+	pub weight: WeightFunction,
+	/// Where radii come from. None keeps the deprecated behavior: the embedded table, replaced by the
+	/// file named in `ATOMIC_RADII` / `ATOMIC_RADII_PATH` when set. A table passed to `set_radii` wins.
+	pub radii_source: Option<RadiiSource>,
+	/// Skip atoms whose radius cannot be assigned instead of failing `add_atom`/`add_atoms`; they are
+	/// listed in `Results::skipped_atoms` (implementation choice)
+	pub skip_unassigned_atoms: bool,
+	/// Same-molecule atoms closer than this many Å are coincident and abort surface generation with
+	/// `SurfaceCalculatorError::Coincident` (default 0.01 Å)
+	pub coincident_tolerance: f64,
+	/// Most points sampled along one arc or circle of the surface construction. Extreme density and
+	/// radius combinations needing more are sampled with exactly this many, evenly spread, and
+	/// counted in a `WarningKind::ArcSamplingCapped` warning (implementation choice)
+	pub max_arc_points: usize,
+	/// Drop atoms coincident with an earlier atom of the same molecule (duplicated records,
+	/// symmetry-expanded files) before each calculation instead of failing; they are listed in
+	/// `Results::skipped_atoms` (implementation choice)
+	pub drop_coincident_atoms: bool,
+	/// Residues left out of surface generation and statistics (disordered loops, tags); empty by
+	/// default (implementation choice)
+	pub mask: Vec<ResidueRange>,
+	/// Keep masked atoms as occluders: they get no dots of their own but still shape and bury the
+	/// dots of other atoms. Otherwise they are removed before each calculation and listed in
+	/// `Results::skipped_atoms`.
+	pub mask_occludes: bool,
+	/// Atoms of each molecule that get surfaces, e.g. `[Backbone, Backbone]` for scaffold
+	/// complementarity or `[SideChain, SideChain]` for side-chain packing; the other atoms get no
+	/// dots but still occlude. `[All, All]` by default
+	pub atom_subset: [AtomSubset; 2],
 	/// Prefer using provided per-atom type radii when available (implementation choice)
 	pub use_atom_type_radius: bool,
-	/// Enable Rayon-parallel sections (trimming and neighbor pairing)
+	/// Linking distance in Å for clustering buried dots into interface patches (implementation choice)
+	pub patch_link_distance: f64,
+	/// Minimum gap in Å between a trimmed dot and its partner for the dot to line an interfacial void
+	/// (default 3 Å, room for a water molecule); void dots are linked at `patch_link_distance`
+	pub void_min_gap: f64,
+	/// Radius in Å of the neighborhood of same-surface dots each dot's curvature is estimated from
+	/// (default 1.5 Å, wide enough to smooth over the dot spacing; implementation choice)
+	pub curvature_radius: f64,
+	/// Paired normals within this many degrees of opposed count as antiparallel in
+	/// `Results::normal_angles` (default 30°; implementation choice)
+	pub antiparallel_tolerance: f64,
+	/// Van der Waals overlap in Å beyond which an atom pair across the interface is reported in
+	/// `Results::clashes` (default 1 Å: with united-atom radii a hydrogen-bonded N and O already
+	/// overlap by about 0.5 Å; implementation choice)
+	pub clash_tolerance: f64,
+	/// Treat the two molecules as equivalent protomers of a symmetric homodimer and report
+	/// `Results::symmetry` (per-half averages and the deviation between the two half-interfaces)
+	pub homodimer: bool,
+	/// Report each molecule's solvent-excluded volume in `SurfaceStats::volume`. The interface run
+	/// surfaces only atoms near the partner, so each molecule is surfaced again on its own, in full
+	/// (at its probe radius and density), which costs about one more surface generation.
+	pub molecular_volume: bool,
+	/// Compute the interface reports (gap volume, electrostatics, contacts, patches, voids, curvature,
+	/// clashes, volumes, symmetry) for each pose of `ScCalculator::score_poses`. Off by default: poses
+	/// are only trimmed, paired and scored, and the report fields stay empty (implementation choice)
+	pub pose_reports: bool,
+	/// Enable Rayon-parallel sections (trimming and neighbor pairing); without the `parallel` feature
+	/// everything runs serially whatever the value
 	pub enable_parallel: bool,
+	/// Classify dots as they are generated and store only the buried ones, so memory scales with the
+	/// interface rather than the whole surface. Peripheral-band trimming runs a second generation
+	/// pass in which each exposed dot marks the stored dots within the band, and pairing searches a
+	/// grid, so Sc, the trimmed areas and `n_all_dots` are unchanged. Reports over exposed dots
+	/// (`dot_curvatures`, `density_report`, dot views) see the buried dots only, sweeps are limited to
+	/// `peripheral_band`, and frozen or incremental surfaces are not reused (implementation choice).
+	pub streaming: bool,
+	/// Worker threads for the parallel sections; None uses the global Rayon pool
+	pub num_threads: Option<usize>,
+	/// Caller-provided pool for the parallel sections (takes precedence over `num_threads`)
+	#[cfg(feature = "parallel")]
+	pub thread_pool: Option<Arc<rayon::ThreadPool>>,
+	/// Cooperative cancellation flag: once set (from any thread), a running calculation stops at its
+	/// next checkpoint and returns `SurfaceCalculatorError::Cancelled`. Clear it before reusing.
+	pub cancel: Option<Arc<AtomicBool>>,
+	/// Called as each phase advances, e.g. to drive a progress bar
+	pub progress: Option<Progress>,
+	/// Sample contact dots and classify dot burial on the GPU when an adapter is available (CPU
+	/// otherwise); reentrant and cavity dots are still built on the CPU. Off by default: the f32 tests
+	/// can flip dots within rounding of a sphere, and for typical complexes the upload costs more than
+	/// the CPU paths (implementation choice)
+	#[cfg(feature = "gpu")]
+	pub use_gpu: bool,
 }
 
 impl Default for Settings {
 	fn default() -> Self {
 		Self {
 			rp: 1.7,
+			rp_molecule: [None, None],
 			dot_density: DOT_DENSITY,
+			dot_density_molecule: [None, None],
+			adaptive_density: None,
+			focus: None,
 			peripheral_band: PERIPH_BAND,
+			trim_mode: TrimMode::PeripheralBand,
 			separation_cutoff: 8.0,
+			auto_separation_cutoff: false,
 			gaussian_w: GAUSSIAN_W,
+			statistic: ScStatistic::Median,
+			extra_statistics: Vec::new(),
+			weight: WeightFunction::Gaussian,
+			radii_source: None,
+			skip_unassigned_atoms: false,
+			coincident_tolerance: 0.01,
+			max_arc_points: 100_000,
+			drop_coincident_atoms: false,
+			mask: Vec::new(),
+			mask_occludes: false,
+			atom_subset: [AtomSubset::All; 2],
 			use_atom_type_radius: false,
+			patch_link_distance: 2.0,
+			void_min_gap: 3.0,
+			curvature_radius: 1.5,
+			antiparallel_tolerance: 30.0,
+			clash_tolerance: 1.0,
+			homodimer: false,
+			molecular_volume: false,
+			pose_reports: false,
 			enable_parallel: true,
+			streaming: false,
+			num_threads: None,
+			#[cfg(feature = "parallel")]
+			thread_pool: None,
+			cancel: None,
+			progress: None,
+			#[cfg(feature = "gpu")]
+			use_gpu: false,
+		}
+	}
+}
+
+impl Settings {
+	/// Probe radius used for the surface of `molecule`
+	pub fn probe_radius(&self, molecule: usize) -> f64 { self.rp_molecule[molecule].unwrap_or(self.rp) }
+	/// Target dot density for atoms of `molecule`
+	pub fn molecule_density(&self, molecule: usize) -> f64 { self.dot_density_molecule[molecule].unwrap_or(self.dot_density) }
+	/// Largest probe radius over both molecules
+	pub fn max_probe_radius(&self) -> f64 { self.probe_radius(0).max(self.probe_radius(1)) }
+	/// Whether `atom` lies in a `mask` residue
+	pub fn masked(&self, atom: &crate::sc::types::Atom) -> bool { self.mask.iter().any(|r| r.contains(atom)) }
+	/// Whether `atom` is kept from getting dots, by the mask or its molecule's `atom_subset`
+	pub fn excluded(&self, atom: &crate::sc::types::Atom) -> bool {
+		self.masked(atom) || !self.atom_subset[atom.molecule].contains(atom)
+	}
+	/// The settings that shape the dots, their trimming and pairing, and the statistic, in one
+	/// comparable string; reports, threading and the sweep parameters (band, w) are left out
+	pub(crate) fn surface_fingerprint(&self) -> String {
+		format!("{:?}", (
+			(self.rp, self.rp_molecule, self.dot_density, self.dot_density_molecule, &self.adaptive_density, &self.focus),
+			(&self.trim_mode, self.separation_cutoff, self.auto_separation_cutoff, self.statistic, &self.extra_statistics, &self.weight),
+			(self.coincident_tolerance, self.max_arc_points, self.drop_coincident_atoms, &self.mask, self.mask_occludes, self.atom_subset, self.streaming),
+		))
+	}
+	/// Whether the `cancel` flag has been raised
+	pub fn cancelled(&self) -> bool { self.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) }
+	pub(crate) fn report_progress(&self, phase: Phase, done: usize, total: usize) {
+		if let Some(p) = &self.progress { p.report(phase, done, total); }
+	}
+
+	/// Reject configurations that would silently produce a meaningless Sc (negative probe radius,
+	/// zero density, a trimming band as wide as the interface). Called at the start of every calc.
+	pub fn validate(&self) -> Result<(), SurfaceCalculatorError> {
+		let invalid = |msg: String| Err(SurfaceCalculatorError::InvalidSettings(msg));
+		for m in 0..2 {
+			let rp = self.probe_radius(m);
+			if !rp.is_finite() || rp < 0.0 { return invalid(format!("probe radius for molecule {} must be a non-negative number of Å, got {rp}", m + 1)); }
+			let density = self.molecule_density(m);
+			if !(density > 0.0 && density <= MAX_DOT_DENSITY) { return invalid(format!("dot density for molecule {} must be in (0, {MAX_DOT_DENSITY}] per Å², got {density}", m + 1)); }
+		}
+		if let Some(ad) = self.adaptive_density {
+			if !(ad.distance.is_finite() && ad.distance > 0.0) { return invalid(format!("adaptive density distance must be positive, got {}", ad.distance)); }
+			if !(ad.density > 0.0 && ad.density <= MAX_DOT_DENSITY) { return invalid(format!("adaptive density must be in (0, {MAX_DOT_DENSITY}] per Å², got {}", ad.density)); }
+		}
+		if let Some(focus) = &self.focus {
+			if !(focus.radius.is_finite() && focus.radius > 0.0) { return invalid(format!("focus radius must be positive, got {}", focus.radius)); }
+			if matches!(&focus.center, FocusCenter::Residues(r) if r.is_empty()) { return invalid("focus residue selection is empty".to_string()); }
+		}
+		if !(self.separation_cutoff.is_finite() && self.separation_cutoff > 0.0) { return invalid(format!("separation cutoff must be positive, got {}", self.separation_cutoff)); }
+		if !(self.peripheral_band.is_finite() && self.peripheral_band >= 0.0) { return invalid(format!("peripheral band must be non-negative, got {}", self.peripheral_band)); }
+		// The band is measured against the same scale that decides which atoms face the partner; a
+		// band that wide trims every buried dot and leaves Sc undefined
+		if self.trim_mode == TrimMode::PeripheralBand && !self.auto_separation_cutoff && self.peripheral_band >= self.separation_cutoff {
+			return invalid(format!("peripheral band ({} Å) must be smaller than the separation cutoff ({} Å)", self.peripheral_band, self.separation_cutoff));
+		}
+		if let TrimMode::PartnerDistance(d) = self.trim_mode {
+			if !(d.is_finite() && d > 0.0) { return invalid(format!("partner trimming distance must be positive, got {d}")); }
+		}
+		if !(self.gaussian_w.is_finite() && self.gaussian_w > 0.0) { return invalid(format!("gaussian_w must be positive, got {}", self.gaussian_w)); }
+		match self.weight {
+			WeightFunction::Exponential(scale) if !(scale.is_finite() && scale > 0.0) => return invalid(format!("exponential weight scale must be positive, got {scale}")),
+			WeightFunction::Step(cutoff) if cutoff.is_nan() || cutoff < 0.0 => return invalid(format!("step weight cutoff must be non-negative, got {cutoff}")),
+			_ => {}
+		}
+		for stat in std::iter::once(&self.statistic).chain(&self.extra_statistics) {
+			match *stat {
+				ScStatistic::TrimmedMean(f) if !(0.0..0.5).contains(&f) => return invalid(format!("trimmed mean fraction must be in [0, 0.5), got {f}")),
+				ScStatistic::Quantile(q) if !(0.0..=1.0).contains(&q) => return invalid(format!("quantile must be in [0, 1], got {q}")),
+				_ => {}
+			}
+		}
+		if !(self.patch_link_distance.is_finite() && self.patch_link_distance > 0.0) { return invalid(format!("patch link distance must be positive, got {}", self.patch_link_distance)); }
+		if !(self.void_min_gap.is_finite() && self.void_min_gap >= 0.0) { return invalid(format!("void minimum gap must be non-negative, got {}", self.void_min_gap)); }
+		if !(self.curvature_radius.is_finite() && self.curvature_radius > 0.0) { return invalid(format!("curvature radius must be positive, got {}", self.curvature_radius)); }
+		if !(0.0..=180.0).contains(&self.antiparallel_tolerance) { return invalid(format!("antiparallel tolerance must be in [0, 180] degrees, got {}", self.antiparallel_tolerance)); }
+		if !(self.clash_tolerance.is_finite() && self.clash_tolerance >= 0.0) { return invalid(format!("clash tolerance must be non-negative, got {}", self.clash_tolerance)); }
+		if self.max_arc_points == 0 { return invalid("max_arc_points must be at least 1".to_string()); }
+		if !(self.coincident_tolerance.is_finite() && self.coincident_tolerance >= 0.0) { return invalid(format!("coincident tolerance must be non-negative, got {}", self.coincident_tolerance)); }
+		if self.num_threads == Some(0) { return invalid("num_threads must be at least 1".to_string()); }
+		match &self.radii_source {
+			Some(RadiiSource::Inline(radii)) => {
+				if radii.is_empty() { return invalid("radii set is empty".to_string()); }
+				if let Some(r) = radii.iter().find(|r| !(r.radius.is_finite() && r.radius > 0.0)) {
+					return invalid(format!("radius for {}:{} must be positive, got {}", r.residue, r.atom, r.radius));
+				}
+			}
+			Some(RadiiSource::Path(path)) if path.is_empty() => return invalid("radii path is empty".to_string()),
+			_ => {}
 		}
+		Ok(())
 	}
 }
@@ -0,0 +1,84 @@
+// Distance kernels over flat coordinate arrays, written in fixed-width lanes so the compiler
+// vectorizes them. On x86_64 an AVX2 build of each kernel is selected at runtime when the CPU
+// supports it; otherwise the baseline build (SSE2 / NEON) runs. Both builds compute the same
+// per-lane operations without fused multiply-add, so results do not depend on the CPU.
+
+use crate::sc::types::GeomValue;
+
+const LANES: usize = 8;
+
+#[inline(always)]
+fn lanes(s: &[GeomValue]) -> &[GeomValue; LANES] { s.try_into().unwrap() }
+
+#[inline(always)]
+fn min_distance_squared_impl(xs: &[GeomValue], ys: &[GeomValue], zs: &[GeomValue], p: [GeomValue; 3]) -> GeomValue {
+	let n = xs.len().min(ys.len()).min(zs.len());
+	let (xs, ys, zs) = (&xs[..n], &ys[..n], &zs[..n]);
+	let mut best = [GeomValue::INFINITY; LANES];
+	for ((cx, cy), cz) in xs.chunks_exact(LANES).zip(ys.chunks_exact(LANES)).zip(zs.chunks_exact(LANES)) {
+		let (cx, cy, cz) = (lanes(cx), lanes(cy), lanes(cz));
+		for l in 0..LANES {
+			let (dx, dy, dz) = (cx[l] - p[0], cy[l] - p[1], cz[l] - p[2]);
+			best[l] = best[l].min(dx * dx + dy * dy + dz * dz);
+		}
+	}
+	let mut m = best.iter().fold(GeomValue::INFINITY, |a, &b| a.min(b));
+	for i in (n - n % LANES)..n {
+		let (dx, dy, dz) = (xs[i] - p[0], ys[i] - p[1], zs[i] - p[2]);
+		m = m.min(dx * dx + dy * dy + dz * dz);
+	}
+	m
+}
+
+#[inline(always)]
+fn any_within_impl(xs: &[GeomValue], ys: &[GeomValue], zs: &[GeomValue], radii: &[GeomValue], p: [GeomValue; 3], pad: GeomValue) -> bool {
+	let n = xs.len().min(ys.len()).min(zs.len()).min(radii.len());
+	let (xs, ys, zs, radii) = (&xs[..n], &ys[..n], &zs[..n], &radii[..n]);
+	for (((cx, cy), cz), cr) in xs.chunks_exact(LANES).zip(ys.chunks_exact(LANES)).zip(zs.chunks_exact(LANES)).zip(radii.chunks_exact(LANES)) {
+		let (cx, cy, cz, cr) = (lanes(cx), lanes(cy), lanes(cz), lanes(cr));
+		let mut hit = false;
+		for l in 0..LANES {
+			let (dx, dy, dz) = (cx[l] - p[0], cy[l] - p[1], cz[l] - p[2]);
+			let reach = cr[l] + pad;
+			hit |= dx * dx + dy * dy + dz * dz <= reach * reach;
+		}
+		if hit { return true; }
+	}
+	(n - n % LANES..n).any(|i| {
+		let (dx, dy, dz) = (xs[i] - p[0], ys[i] - p[1], zs[i] - p[2]);
+		let reach = radii[i] + pad;
+		dx * dx + dy * dy + dz * dz <= reach * reach
+	})
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+fn min_distance_squared_avx2(xs: &[GeomValue], ys: &[GeomValue], zs: &[GeomValue], p: [GeomValue; 3]) -> GeomValue {
+	min_distance_squared_impl(xs, ys, zs, p)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+fn any_within_avx2(xs: &[GeomValue], ys: &[GeomValue], zs: &[GeomValue], radii: &[GeomValue], p: [GeomValue; 3], pad: GeomValue) -> bool {
+	any_within_impl(xs, ys, zs, radii, p, pad)
+}
+
+/// Smallest squared distance from `p` to the points (xs, ys, zs); infinity when empty
+pub(crate) fn min_distance_squared(xs: &[GeomValue], ys: &[GeomValue], zs: &[GeomValue], p: [GeomValue; 3]) -> GeomValue {
+	#[cfg(target_arch = "x86_64")]
+	if std::arch::is_x86_feature_detected!("avx2") {
+		// SAFETY: the CPU supports AVX2, checked just above
+		return unsafe { min_distance_squared_avx2(xs, ys, zs, p) };
+	}
+	min_distance_squared_impl(xs, ys, zs, p)
+}
+
+/// Whether `p` lies inside any sphere centered at (xs, ys, zs) with radius `radii + pad`
+pub(crate) fn any_within(xs: &[GeomValue], ys: &[GeomValue], zs: &[GeomValue], radii: &[GeomValue], p: [GeomValue; 3], pad: GeomValue) -> bool {
+	#[cfg(target_arch = "x86_64")]
+	if std::arch::is_x86_feature_detected!("avx2") {
+		// SAFETY: the CPU supports AVX2, checked just above
+		return unsafe { any_within_avx2(xs, ys, zs, radii, p, pad) };
+	}
+	any_within_impl(xs, ys, zs, radii, p, pad)
+}
@@ -2,102 +2,665 @@ use std::cmp::Ordering;
 use std::f64::consts::PI;
 use std::env;
 
-use crate::sc::atomic_radii::{read_atomic_radii_from_path, embedded_atomic_radii, wildcard_match};
-use crate::sc::settings::Settings;
+use crate::sc::grid::{BurialGrid, Csr, SpatialGrid};
+use crate::sc::simd;
+use crate::sc::atomic_radii::{read_atomic_radii_from_path, embedded_atomic_radii, RadiusIndex};
+use std::sync::Once;
+use crate::sc::settings::{Focus, FocusCenter, Phase, Settings, TrimMode};
 use crate::sc::types::*;
 use crate::sc::vector3::Vec3;
-use rayon::prelude::*;
+use crate::sc::par::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+// std's clock panics on wasm32-unknown-unknown
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
 // Geometry was originally single-threaded; selected phases are parallelized when enabled
 
 #[derive(thiserror::Error, Debug)]
 pub enum SurfaceCalculatorError {
 	#[error("No atoms defined")] NoAtoms,
 	#[error("Index out of bounds")] JumpOutOfBounds,
-	#[error("Failed to read radii: {0}")] Io(#[from] std::io::Error),
+	#[error("I/O error: {0}")] Io(#[from] std::io::Error),
 	#[error("Overlapping atoms detected: {0}")] Coincident(String),
 	#[error("Geometric construction invalid (far circle) for atom {0}, neighbor {1}")] ImagFar(i32, i32),
 	#[error("Geometric construction invalid (containment) for atom {0}, neighbor {1}")] ImagContain(i32, i32),
 	#[error("Invalid local frame for atom {0}, neighbor {1}")] NonPositiveFrame(i32, i32),
+	/// No longer returned: arcs over `Settings::max_arc_points` are sampled at the cap and reported
+	/// as a `WarningKind::ArcSamplingCapped` warning
+	#[deprecated(note = "never returned; capped arcs are reported as `WarningKind::ArcSamplingCapped`")]
 	#[error("Sampling limit exceeded")] TooManySubdivisions,
+	#[error("Calculation cancelled")] Cancelled,
+	#[error("Invalid settings: {0}")] InvalidSettings(String),
+	#[error("No radius for {residue}:{atom}")] RadiusNotFound { residue: String, atom: String },
+	#[error("Missing atom_type_radius for {residue}:{atom}")] MissingAtomTypeRadius { residue: String, atom: String },
+	/// One molecule (1 or 2) has no atoms
+	#[error("No atoms for molecule {0}")] EmptyMolecule(usize),
+	#[error("No molecular dots generated")] NoDots,
+	#[error("No atoms found for chain selection '{selection}' in {path}")] EmptySelection { selection: String, path: String },
+	/// `GroupScCalculator` pair naming a missing group, or the same group twice
+	#[error("Invalid group pair ({0}, {1})")] InvalidGroup(usize, usize),
+}
+
+fn coincident_error(atom1: &Atom, atom2: &Atom) -> SurfaceCalculatorError {
+	SurfaceCalculatorError::Coincident(format!(
+		"{}:{}:{} @ ({:.3},{:.3},{:.3}) == {}:{}:{} @ ({:.3},{:.3},{:.3})",
+		atom1.natom, atom1.residue, atom1.atom, atom1.coor.x, atom1.coor.y, atom1.coor.z,
+		atom2.natom, atom2.residue, atom2.atom, atom2.coor.x, atom2.coor.y, atom2.coor.z
+	))
+}
+
+fn radius_not_found(atom: &Atom) -> SurfaceCalculatorError {
+	SurfaceCalculatorError::RadiusNotFound { residue: atom.residue.clone(), atom: atom.atom.clone() }
+}
+
+/// Callback receiving each dot as it is generated, with the molecule whose surface it belongs to
+pub type DotVisitor = Box<dyn FnMut(usize, &Dot) + Send + Sync>;
+
+/// Registered dot visitor and whether visited dots are also kept in the surfaces
+struct DotSink {
+	visit: DotVisitor,
+	keep: bool,
 }
 
 pub struct SurfaceGenerator {
 	pub settings: Settings,
 	radii: Vec<crate::sc::types::AtomRadius>,
+	/// Lookup over `radii`, built by `init()` and dropped whenever the radii are replaced
+	radius_index: Option<RadiusIndex>,
 	pub(crate) run: RunState,
+	/// Pool built for `Settings::num_threads`, kept while the thread count is unchanged
+	#[cfg(feature = "parallel")]
+	pool: Option<(usize, Arc<ThreadPool>)>,
+	/// Set by `set_dot_visitor`
+	sink: Option<DotSink>,
+	/// Arcs of the current generation sampled at the `Settings::max_arc_points` cap
+	capped_arcs: AtomicUsize,
+}
+
+// Generators are moved into and shared with worker threads; keep that a compile-time guarantee
+const _: () = { const fn assert_send_sync<T: Send + Sync>() {} assert_send_sync::<SurfaceGenerator>() };
+
+/// Structure-of-arrays mirror of the atom positions, radii, molecule ids and context flags read by the hot
+/// distance loops (attention, neighbor search, burial). `RunState::atoms` stays the source of truth;
+/// the arrays are rebuilt from it before attention assignment and surface generation.
+#[derive(Clone, Default)]
+pub(crate) struct AtomArrays {
+	pub x: Vec<GeomValue>,
+	pub y: Vec<GeomValue>,
+	pub z: Vec<GeomValue>,
+	pub radius: Vec<GeomValue>,
+	pub molecule: Vec<usize>,
+	/// Context atoms occlude their own molecule's surface but never bury the partner's dots
+	pub context: Vec<bool>,
+}
+
+impl AtomArrays {
+	pub(crate) fn from_atoms(atoms: &[Atom]) -> Self {
+		Self {
+			x: atoms.iter().map(|a| a.coor.x as GeomValue).collect(),
+			y: atoms.iter().map(|a| a.coor.y as GeomValue).collect(),
+			z: atoms.iter().map(|a| a.coor.z as GeomValue).collect(),
+			radius: atoms.iter().map(|a| a.radius as GeomValue).collect(),
+			molecule: atoms.iter().map(|a| a.molecule).collect(),
+			context: atoms.iter().map(|a| a.context).collect(),
+		}
+	}
+	pub(crate) fn len(&self) -> usize { self.x.len() }
+	/// Contiguous x, y, z coordinates of one molecule's atoms, context atoms left out
+	pub(crate) fn molecule_coords(&self, molecule: usize) -> (Vec<GeomValue>, Vec<GeomValue>, Vec<GeomValue>) {
+		let idx = (0..self.len()).filter(|&i| self.molecule[i] == molecule && !self.context[i]);
+		(idx.clone().map(|i| self.x[i]).collect(), idx.clone().map(|i| self.y[i]).collect(), idx.map(|i| self.z[i]).collect())
+	}
+	pub(crate) fn coor(&self, i: usize) -> Vec3 { Vec3::new(self.x[i] as ScValue, self.y[i] as ScValue, self.z[i] as ScValue) }
+	pub(crate) fn radius(&self, i: usize) -> ScValue { self.radius[i] as ScValue }
+	/// Squared distance from atom i to `p`, evaluated in `GeomValue` precision
+	#[inline]
+	pub(crate) fn distance_squared(&self, i: usize, p: Vec3) -> ScValue {
+		let (dx, dy, dz) = (self.x[i] - p.x as GeomValue, self.y[i] - p.y as GeomValue, self.z[i] - p.z as GeomValue);
+		(dx * dx + dy * dy + dz * dz) as ScValue
+	}
+}
+
+/// Reusable buffers for the samplers and neighbor queries. Each parallel task and each serial pass
+/// owns one, so sampling a latitude, arc or probe reuses capacity instead of allocating.
+#[derive(Default)]
+struct Scratch {
+	lats: Vec<Vec3>,
+	points: Vec<Vec3>,
+	subs: Vec<Vec3>,
+	nears: Vec<usize>,
+}
+
+/// Order in which a parallel phase hands out its work items: largest estimated cost first, so the
+/// expensive interface atoms start early and the cheap tail fills in around them. Results are put
+/// back in index order afterwards, keeping the output independent of the schedule.
+fn schedule_by_cost(costs: impl Iterator<Item = ScValue>) -> Vec<usize> {
+	let mut order: Vec<(usize, ScValue)> = costs.enumerate().collect();
+	order.sort_by(|a, b| b.1.total_cmp(&a.1));
+	order.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Molecule 1's complete surface, generated once without a partner and reused by later runs
+#[derive(Clone, Default)]
+pub(crate) struct FrozenSurface {
+	pub dots: Vec<Dot>,
+	/// `Settings::surface_fingerprint` of the settings the surface was generated under
+	pub fingerprint: String,
+}
+
+/// Atom moves recorded by `move_atoms` since the last surface generation
+#[derive(Clone, Default)]
+pub(crate) struct PendingUpdate {
+	/// Old and new positions of the moved atoms
+	pub centers: Vec<Vec3>,
+	/// Attention of every atom in the previous run
+	pub attention: Vec<Attention>,
+	/// Surfaces of the previous run
+	pub dots: [Vec<Dot>; 2],
+}
+
+/// Regeneration restricted to part of the system: only `active` atoms take part in generation, new
+/// dots are kept for `owners` only, and `retained` dots from an earlier run cover every other atom
+struct PartialSurface {
+	active: Vec<bool>,
+	owners: Vec<bool>,
+	retained: [Vec<Dot>; 2],
+}
+
+/// Streaming-mode filter. The first pass keeps only buried dots; the second, run over the same
+/// probes when trimming by peripheral band, keeps none and has each exposed dot mark the stored
+/// buried dots of its molecule within the band instead
+#[derive(Clone, Default)]
+pub(crate) struct DotStream {
+	marks: Option<PeripheralMarks>,
+}
+
+impl DotStream {
+	pub(crate) fn keeps(&self, molecule: usize, dot: &Dot) -> bool {
+		match &self.marks {
+			None => dot.buried,
+			Some(marks) => {
+				if !dot.buried { marks.mark(molecule, dot.coor); }
+				false
+			}
+		}
+	}
+}
+
+/// Buried dots of each molecule within `band` of an exposed dot, found through a grid over the dots
+struct PeripheralMarks {
+	band: ScValue,
+	coords: [Vec<Vec3>; 2],
+	grids: [SpatialGrid; 2],
+	marked: [Vec<AtomicBool>; 2],
+}
+
+impl Clone for PeripheralMarks {
+	fn clone(&self) -> Self {
+		let marked = self.marked.each_ref().map(|flags| flags.iter().map(|f| AtomicBool::new(f.load(AtomicOrdering::Relaxed))).collect());
+		Self { band: self.band, coords: self.coords.clone(), grids: self.grids.clone(), marked }
+	}
+}
+
+impl PeripheralMarks {
+	fn new(dots: &[Vec<Dot>; 2], band: ScValue) -> Self {
+		let coords = [0, 1].map(|m| dots[m].iter().map(|d| d.coor).collect::<Vec<_>>());
+		let grids = [0, 1].map(|m| SpatialGrid::new(coords[m].iter().copied(), band));
+		let marked = [0, 1].map(|m| (0..coords[m].len()).map(|_| AtomicBool::new(false)).collect());
+		Self { band, coords, grids, marked }
+	}
+
+	fn mark(&self, molecule: usize, p: Vec3) {
+		let r2 = self.band * self.band;
+		self.grids[molecule].for_each_candidate(p, 1, |i| {
+			if self.coords[molecule][i].distance_squared(p) <= r2 { self.marked[molecule][i].store(true, AtomicOrdering::Relaxed); }
+		});
+	}
 }
 
 #[derive(Clone, Default)]
 pub(crate) struct RunState {
 	pub atoms: Vec<Atom>,
+	pub arrays: AtomArrays,
 	pub probes: Vec<Probe>,
 	pub dots: [Vec<Dot>; 2],
 	pub trimmed_dots: [Vec<usize>; 2],
+	/// Nearest-neighbor pairing of each surface's trimmed dots with the opposite surface
+	pub pairs: [Vec<DotPair>; 2],
 	pub results: Results,
 	pub radmax: ScValue,
+	/// Per-molecule atom grids for dot burial queries, rebuilt with each surface generation
+	pub burial: BurialGrid,
+	/// Same-molecule neighbors of each atom (nearest first), built once per surface generation
+	pub neighbors: Csr,
+	/// Opposite-molecule atoms close enough to bury each atom
+	pub buried_by: Csr,
+	/// Set by `freeze_molecule1`; surface generation then builds molecule 2 only
+	pub frozen: Option<FrozenSurface>,
+	/// Set by `move_atoms`; surface generation then rebuilds only the neighborhoods of moved atoms
+	pub pending: Option<PendingUpdate>,
+	/// Present in streaming mode while dots are generated
+	pub stream: Option<DotStream>,
+	/// Streaming mode under peripheral-band trimming: stored buried dots within the band of an
+	/// exposed dot, by dot index, which trimming drops in place of scanning the exposed dots
+	pub peripheral: Option<[Vec<bool>; 2]>,
+	/// Dots per molecule discarded by the streaming filter
+	pub dropped: [usize; 2],
+	/// `Settings::surface_fingerprint` of the settings the current results were scored under
+	pub scored_with: Option<String>,
 }
 
 impl Default for SurfaceGenerator {
 	fn default() -> Self { Self::new() }
 }
 
+/// Radii when `Settings::radii_source` is unset: the embedded table, or the file named by the
+/// deprecated `ATOMIC_RADII` / `ATOMIC_RADII_PATH` variables (unreadable files fall back silently)
+fn legacy_env_radii() -> Vec<crate::sc::types::AtomRadius> {
+	static DEPRECATION: Once = Once::new();
+	if let Ok(path) = env::var("ATOMIC_RADII").or_else(|_| env::var("ATOMIC_RADII_PATH")) {
+		DEPRECATION.call_once(|| eprintln!("warning: ATOMIC_RADII / ATOMIC_RADII_PATH are deprecated; set Settings::radii_source instead"));
+		if let Ok(r) = read_atomic_radii_from_path(&path) { return r; }
+	}
+	embedded_atomic_radii()
+}
+
 impl SurfaceGenerator {
 	pub fn new() -> Self {
-		Self { settings: Settings::default(), radii: Vec::new(), run: RunState::default() }
+		Self {
+			settings: Settings::default(),
+			radii: Vec::new(),
+			radius_index: None,
+			run: RunState::default(),
+			#[cfg(feature = "parallel")]
+			pool: None,
+			sink: None,
+			capped_arcs: AtomicUsize::new(0),
+		}
 	}
 
 	pub fn init(&mut self) -> Result<(), SurfaceCalculatorError> {
 		if self.radii.is_empty() {
-			// Default to embedded radii (portable), allow optional override via env
-			self.radii = embedded_atomic_radii();
-			if let Ok(path) = env::var("ATOMIC_RADII").or_else(|_| env::var("ATOMIC_RADII_PATH")) {
-				if let Ok(r) = read_atomic_radii_from_path(&path) { self.radii = r; }
-			}
+			self.radii = match &self.settings.radii_source {
+				Some(source) => source.load()?,
+				None => legacy_env_radii(),
+			};
+			self.radius_index = None;
 		}
+		if self.radius_index.is_none() { self.radius_index = Some(RadiusIndex::new(&self.radii)); }
 		Ok(())
 	}
 
-	pub fn set_radii(&mut self, radii: Vec<crate::sc::types::AtomRadius>) { self.radii = radii; }
+	/// Pool the parallel sections run in: the caller's pool, else one sized by `num_threads`,
+	/// else None for the global Rayon pool
+	#[cfg(feature = "parallel")]
+	pub(crate) fn thread_pool(&mut self) -> Option<Arc<ThreadPool>> {
+		if let Some(pool) = &self.settings.thread_pool { return Some(pool.clone()); }
+		let n = self.settings.num_threads?;
+		if let Some((built, pool)) = &self.pool {
+			if *built == n { return Some(pool.clone()); }
+		}
+		let pool = Arc::new(ThreadPoolBuilder::new().num_threads(n).build().ok()?);
+		self.pool = Some((n, pool.clone()));
+		Some(pool)
+	}
+	/// Without the `parallel` feature everything runs on the calling thread
+	#[cfg(not(feature = "parallel"))]
+	pub(crate) fn thread_pool(&mut self) -> Option<Arc<ThreadPool>> { None }
 
+	/// Run `f` inside the configured thread pool (or directly with the global pool)
+	pub(crate) fn in_pool<R: Send>(&mut self, f: impl FnOnce(&mut Self) -> R + Send) -> R {
+		match self.thread_pool() {
+			Some(pool) => pool.install(|| f(self)),
+			None => f(self),
+		}
+	}
+
+	pub fn set_radii(&mut self, radii: Vec<crate::sc::types::AtomRadius>) { self.radii = radii; self.radius_index = None; }
+
+	/// Call `visitor` with each dot of every following surface generation as it is produced, for custom
+	/// statistics or exports. Dots arrive in generation order (which differs between serial and
+	/// parallel runs) with their burial classified; in streaming mode only the dots the filter keeps
+	/// are visited. With `keep_dots` false visited dots are not stored at all: the surfaces stay
+	/// empty, so `SurfaceGenerator::calc` only fills the dot counts and `ScCalculator::calc` fails
+	/// with `NoDots`. While a visitor is set, frozen and incremental surfaces are not reused.
+	pub fn set_dot_visitor(&mut self, visitor: impl FnMut(usize, &Dot) + Send + Sync + 'static, keep_dots: bool) {
+		self.sink = Some(DotSink { visit: Box::new(visitor), keep: keep_dots });
+	}
+	pub fn clear_dot_visitor(&mut self) { self.sink = None; }
+
+	/// Hand a generated dot of `molecule` to the visitor, then store it unless the visitor discards it
+	fn emit_dot(&mut self, molecule: usize, dot: Dot) {
+		if let Some(sink) = &mut self.sink {
+			(sink.visit)(molecule, &dot);
+			if !sink.keep { return; }
+		}
+		self.run.dots[molecule].push(dot);
+	}
+
+	/// `emit_dot` for a batch produced by a parallel pass; `dots` is left empty
+	fn emit_dots(&mut self, molecule: usize, dots: &mut Vec<Dot>) {
+		if let Some(sink) = &mut self.sink {
+			for dot in dots.iter() { (sink.visit)(molecule, dot); }
+			if !sink.keep { dots.clear(); return; }
+		}
+		self.run.dots[molecule].append(dots);
+	}
+
+	/// Checkpoint for `Settings::cancel`
+	pub(crate) fn check_cancelled(&self) -> Result<(), SurfaceCalculatorError> {
+		if self.settings.cancelled() { Err(SurfaceCalculatorError::Cancelled) } else { Ok(()) }
+	}
+
+	/// Drop the atoms and everything computed from them so the generator can take an unrelated job.
+	/// Settings, the radii table (with its lookup index) and the thread pool are kept; the atom, probe
+	/// and dot buffers keep their capacity.
 	pub fn reset(&mut self) {
-		for a in &mut self.run.atoms { a.neighbor_indices.clear(); a.buried_by_indices.clear(); }
-		self.run.atoms.clear();
+		let run = std::mem::take(&mut self.run);
+		let (mut atoms, mut probes, mut dots, mut trimmed_dots, mut pairs) = (run.atoms, run.probes, run.dots, run.trimmed_dots, run.pairs);
+		atoms.clear();
+		probes.clear();
+		for i in 0..2 {
+			dots[i].clear();
+			trimmed_dots[i].clear();
+			pairs[i].clear();
+		}
+		self.run = RunState { atoms, probes, dots, trimmed_dots, pairs, ..RunState::default() };
+	}
+
+	/// Drop generated surfaces and per-atom geometry from a previous run, keeping the atoms themselves
+	pub(crate) fn clear_surfaces(&mut self) {
+		self.run.neighbors = Csr::default();
+		self.run.buried_by = Csr::default();
+		for a in &mut self.run.atoms {
+			a.accessible = false;
+			a.density = self.settings.molecule_density(a.molecule);
+		}
 		self.run.probes.clear();
-		self.run.dots[0].clear();
-		self.run.dots[1].clear();
-		self.run.trimmed_dots[0].clear();
-		self.run.trimmed_dots[1].clear();
-		self.run.results = Results::default();
+		for i in 0..2 {
+			self.run.dots[i].clear();
+			self.run.trimmed_dots[i].clear();
+			self.run.pairs[i].clear();
+		}
+		self.run.dropped = [0, 0];
+		self.run.peripheral = None;
+		// Start from empty results so nothing from the previous run survives a failed calc; only the
+		// atom counts and the radii assignment time belong to the atoms themselves
+		let previous = std::mem::take(&mut self.run.results);
+		self.run.results.n_atoms = previous.n_atoms;
+		self.run.results.n_context_atoms = previous.n_context_atoms;
+		for i in 0..2 { self.run.results.surfaces[i].n_atoms = previous.surfaces[i].n_atoms; }
+		self.run.results.timings.radii = previous.timings.radii;
+		self.run.results.skipped_atoms = previous.skipped_atoms;
+		self.run.results.warnings = previous.warnings;
 	}
 
 	pub fn add_atom(&mut self, molecule: i32, mut atom: Atom) -> Result<(), SurfaceCalculatorError> {
 		// Ensure radii are loaded before first assignment
-		if self.radii.is_empty() { self.init()?; }
-		if atom.radius <= 0.0 { self.assign_atom_radius(&mut atom)?; }
-		if atom.radius > 0.0 {
-			let mol = if molecule == 1 { 1 } else { 0 } as usize;
-			atom.density = self.settings.dot_density;
-			atom.molecule = mol;
-			atom.natom = (self.run.results.n_atoms + 1) as i32;
-			atom.accessible = false;
+		if self.radius_index.is_none() { self.init()?; }
+		if atom.radius <= 0.0 {
+			let t = Instant::now();
+			let assigned = self.assign_atom_radius(&mut atom);
+			self.run.results.timings.radii += t.elapsed().as_secs_f64();
+			match assigned {
+				Ok(fallback) => if fallback { self.warn_fallback(molecule, &atom) },
+				Err(e) => return self.skip_atom(molecule, &atom, e),
+			}
+		}
+		if atom.radius <= 0.0 { return self.skip_atom(molecule, &atom, radius_not_found(&atom)); }
+		self.push_atom(molecule, atom);
+		Ok(())
+	}
+
+	/// Record an atom without a radius when `skip_unassigned_atoms` is set, else fail with `err`
+	fn skip_atom(&mut self, molecule: i32, atom: &Atom, err: SurfaceCalculatorError) -> Result<(), SurfaceCalculatorError> {
+		let unassigned = matches!(err, SurfaceCalculatorError::RadiusNotFound { .. } | SurfaceCalculatorError::MissingAtomTypeRadius { .. });
+		if !(unassigned && self.settings.skip_unassigned_atoms) { return Err(err); }
+		let molecule = if molecule == 1 { 1 } else { 0 };
+		self.run.results.skipped_atoms.push(SkippedAtom { molecule, residue: atom.residue_id(), atom: atom.atom.clone(), reason: err.to_string() });
+		Ok(())
+	}
+
+	/// Add many atoms to `molecule` at once: radii are assigned in one pass (in parallel when
+	/// enabled) and storage is reserved up front. Either every atom is added or, on the first atom
+	/// without a radius, none is (with `skip_unassigned_atoms`, such atoms are skipped instead).
+	/// Returns the number of atoms added.
+	pub fn add_atoms(&mut self, molecule: i32, atoms: impl IntoIterator<Item = Atom>) -> Result<usize, SurfaceCalculatorError> {
+		if self.radius_index.is_none() { self.init()?; }
+		let mut atoms: Vec<Atom> = atoms.into_iter().collect();
+		let t = Instant::now();
+		let mut assigned: Vec<Result<bool, SurfaceCalculatorError>> = if self.settings.enable_parallel {
+			self.in_pool(|g| atoms.par_iter_mut().map(|a| g.assign_missing_radius(a)).collect())
+		} else {
+			atoms.iter_mut().map(|a| self.assign_missing_radius(a)).collect()
+		};
+		self.run.results.timings.radii += t.elapsed().as_secs_f64();
+		if !self.settings.skip_unassigned_atoms {
+			if let Some(i) = assigned.iter().position(|r| r.is_err()) { return Err(assigned.swap_remove(i).unwrap_err()); }
+		}
+		self.run.atoms.reserve(atoms.len());
+		let mut n = 0;
+		for (atom, r) in atoms.into_iter().zip(assigned) {
+			match r {
+				Ok(fallback) => {
+					if fallback { self.warn_fallback(molecule, &atom); }
+					self.push_atom(molecule, atom);
+					n += 1;
+				}
+				Err(e) => self.skip_atom(molecule, &atom, e)?,
+			}
+		}
+		Ok(n)
+	}
+
+	/// Add third-body occluders (another chain, a membrane mimic, a cofactor) that shape both surfaces
+	/// as they would in the full complex without being scored. Each atom is stored once per molecule
+	/// as a `Far` atom: it gets no dots and stays out of the statistics, the attention search and the
+	/// per-molecule atom counts, but collides with the probes of its molecule's surface, so
+	/// interface dots it covers are not generated. Radii are assigned as in `add_atoms`, with
+	/// warnings and skipped atoms reported under molecule 1. Context atoms are kept after all other
+	/// atoms (atoms added later are inserted before them, shifting their indices) and survive
+	/// `clear_molecule`. Returns the number of context atoms added.
+	pub fn add_context_atoms(&mut self, atoms: impl IntoIterator<Item = Atom>) -> Result<usize, SurfaceCalculatorError> {
+		self.add_atoms(0, atoms.into_iter().map(|mut a| { a.context = true; a }))
+	}
+
+	/// Ok(true) when the radius came from the element fallback
+	fn assign_missing_radius(&self, atom: &mut Atom) -> Result<bool, SurfaceCalculatorError> {
+		let fallback = atom.radius <= 0.0 && self.assign_atom_radius(atom)?;
+		if atom.radius <= 0.0 { return Err(radius_not_found(atom)); }
+		Ok(fallback)
+	}
+
+	/// Note an atom whose radius came from the generic radius of its element in `Results::warnings`
+	fn warn_fallback(&mut self, molecule: i32, atom: &Atom) {
+		let molecule = if molecule == 1 { 1 } else { 0 };
+		Warning::tally(&mut self.run.results.warnings, WarningKind::ElementFallbackRadius, Some(molecule), || atom.label());
+	}
+
+	fn push_atom(&mut self, molecule: i32, mut atom: Atom) {
+		let mol = if molecule == 1 { 1 } else { 0 } as usize;
+		// A frozen molecule 1 surface no longer matches its atoms
+		if mol == 0 { self.run.frozen = None; }
+		self.run.pending = None;
+		atom.density = self.settings.molecule_density(mol);
+		atom.molecule = mol;
+		atom.natom = (self.run.atoms.len() + 1) as i32;
+		atom.accessible = false;
+		if atom.context {
+			let mut copy = atom.clone();
 			self.run.atoms.push(atom);
-			self.run.results.surfaces[mol].n_atoms += 1;
+			copy.molecule = 1;
+			copy.density = self.settings.molecule_density(1);
+			copy.natom += 1;
+			self.run.atoms.push(copy);
+			self.run.results.n_context_atoms += 1;
 			self.run.results.n_atoms += 1;
-			Ok(())
-		} else {
-			Err(SurfaceCalculatorError::Io(std::io::Error::other("Failed to assign atom radius")))
+			return;
 		}
+		self.run.results.surfaces[mol].n_atoms += 1;
+		self.run.results.n_atoms += 1;
+		// Context atoms stay behind every scored atom: pairs and triplets are built from their
+		// lowest-numbered atom, which must not be a skipped Far context atom
+		let at = self.run.atoms.len() - 2 * self.run.results.n_context_atoms;
+		if at == self.run.atoms.len() { self.run.atoms.push(atom); return; }
+		self.run.atoms.insert(at, atom);
+		for (i, a) in self.run.atoms.iter_mut().enumerate().skip(at) { a.natom = (i + 1) as i32; }
 	}
 
-	fn assign_atom_radius(&self, atom: &mut Atom) -> Result<(), SurfaceCalculatorError> {
+	/// Remove every atom of `molecule` except context atoms, renumbering the remaining atoms (and any
+	/// frozen dots)
+	pub fn clear_molecule(&mut self, molecule: usize) {
+		let mut remap = vec![usize::MAX; self.run.atoms.len()];
+		let mut kept = 0;
+		for (i, a) in self.run.atoms.iter().enumerate() {
+			if a.molecule != molecule || a.context { remap[i] = kept; kept += 1; }
+		}
+		self.run.atoms.retain(|a| a.molecule != molecule || a.context);
+		self.run.pending = None;
+		for (i, a) in self.run.atoms.iter_mut().enumerate() { a.natom = (i + 1) as i32; }
+		if let Some(frozen) = &mut self.run.frozen {
+			if molecule == 0 { self.run.frozen = None; }
+			else { for dot in frozen.dots.iter_mut() { dot.atom_index = remap[dot.atom_index]; } }
+		}
+		self.clear_surfaces();
+		self.run.results.n_atoms = self.run.atoms.len() - self.run.results.n_context_atoms;
+		self.run.results.surfaces[molecule.min(1)] = SurfaceStats::default();
+		self.run.results.skipped_atoms.retain(|s| s.molecule != molecule.min(1));
+		self.run.results.warnings.retain(|w| w.molecule != Some(molecule.min(1)));
+	}
+
+	/// With `drop_coincident_atoms`, remove every atom lying within `coincident_tolerance` of an
+	/// earlier atom of the same molecule and list it in `Results::skipped_atoms`. Remaining atoms are
+	/// renumbered, so indices from before the call (e.g. for `update_atoms`) shift past a dropped atom.
+	pub(crate) fn remove_coincident_atoms(&mut self) {
+		if !self.settings.drop_coincident_atoms { return; }
+		let tolerance = self.settings.coincident_tolerance;
+		let atoms = &self.run.atoms;
+		let grid = SpatialGrid::new(atoms.iter().map(|a| a.coor), tolerance.max(1e-3));
+		let mut keep = vec![true; atoms.len()];
+		let mut skipped = Vec::new();
+		let mut candidates = Vec::new();
+		for (i, a) in atoms.iter().enumerate() {
+			grid.candidates_into(a.coor, 1, &mut candidates);
+			let original = candidates.iter().copied().take_while(|&j| j < i)
+				.find(|&j| keep[j] && atoms[j].molecule == a.molecule && atoms[j].distance_squared(a) <= tolerance * tolerance);
+			if let Some(j) = original {
+				keep[i] = false;
+				let reason = coincident_error(a, &atoms[j]).to_string();
+				skipped.push(SkippedAtom { molecule: a.molecule, residue: a.residue_id(), atom: a.atom.clone(), reason });
+			}
+		}
+		self.drop_atoms(keep, skipped);
+	}
+
+	/// Without `mask_occludes`, remove the atoms of `Settings::mask` residues and list them in
+	/// `Results::skipped_atoms`; remaining atoms are renumbered as for coincident atoms
+	pub(crate) fn remove_masked_atoms(&mut self) {
+		if self.settings.mask.is_empty() || self.settings.mask_occludes { return; }
+		let keep: Vec<bool> = self.run.atoms.iter().map(|a| !self.settings.masked(a)).collect();
+		let skipped = self.run.atoms.iter().zip(&keep).filter(|(_, &k)| !k)
+			.map(|(a, _)| SkippedAtom { molecule: a.molecule, residue: a.residue_id(), atom: a.atom.clone(), reason: "masked residue".to_string() })
+			.collect();
+		self.drop_atoms(keep, skipped);
+	}
+
+	/// Remove the atoms whose `keep` flag is false, recording them as `skipped`
+	fn drop_atoms(&mut self, keep: Vec<bool>, skipped: Vec<SkippedAtom>) {
+		if skipped.is_empty() { return; }
+		let mut keep = keep.into_iter();
+		self.run.atoms.retain(|_| keep.next().unwrap_or(true));
+		for (i, a) in self.run.atoms.iter_mut().enumerate() { a.natom = (i + 1) as i32; }
+		self.run.frozen = None;
+		self.run.pending = None;
+		self.run.results.n_context_atoms = self.run.atoms.iter().filter(|a| a.context && a.molecule == 0).count();
+		self.run.results.n_atoms = self.run.atoms.len() - self.run.results.n_context_atoms;
+		for m in 0..2 { self.run.results.surfaces[m].n_atoms = self.run.atoms.iter().filter(|a| a.molecule == m && !a.context).count(); }
+		self.run.results.skipped_atoms.extend(skipped);
+	}
+
+	/// Generate molecule 1's complete surface, ignoring molecule 2, and keep it for later runs.
+	/// Subsequent surface generations build molecule 2 only, take the frozen dots of molecule 1 atoms
+	/// within `separation_cutoff` of it and reclassify their burial: the docking rescoring workflow
+	/// of one receptor and many ligand poses. Reentrant and cavity dots that a full run skips at the
+	/// edge of the cutoff are kept, so dot counts can run slightly higher. The frozen surface ignores
+	/// adaptive density, and is generated again on the next run once the settings that shape the
+	/// surfaces change. Returns the number of frozen dots.
+	pub fn freeze_molecule1(&mut self) -> Result<usize, SurfaceCalculatorError> {
+		self.init()?;
+		self.remove_masked_atoms();
+		self.remove_coincident_atoms();
+		if self.run.results.surfaces[0].n_atoms == 0 { return Err(SurfaceCalculatorError::EmptyMolecule(1)); }
+		self.run.frozen = None;
+		self.clear_surfaces();
+		for a in &mut self.run.atoms { a.attention = if a.molecule == 0 && !a.context && !self.settings.excluded(a) { Attention::Buried } else { Attention::Far }; }
+		// The frozen surface is kept, not visited
+		let sink = self.sink.take();
+		let generated = self.in_pool(|g| g.generate_molecular_surfaces());
+		self.sink = sink;
+		generated?;
+		let frozen = FrozenSurface { dots: std::mem::take(&mut self.run.dots[0]), fingerprint: self.settings.surface_fingerprint() };
+		let n = frozen.dots.len();
+		self.clear_surfaces();
+		self.run.frozen = Some(frozen);
+		Ok(n)
+	}
+
+	pub fn unfreeze_molecule1(&mut self) { self.run.frozen = None; }
+
+	/// Freeze molecule 1 again when the settings have changed since its surface was frozen, so a run
+	/// never reuses dots generated under other settings
+	pub(crate) fn refresh_frozen_surface(&mut self) -> Result<(), SurfaceCalculatorError> {
+		match &self.run.frozen {
+			Some(frozen) if frozen.fingerprint != self.settings.surface_fingerprint() => self.freeze_molecule1().map(|_| ()),
+			_ => Ok(()),
+		}
+	}
+	pub fn is_molecule1_frozen(&self) -> bool { self.run.frozen.is_some() }
+
+	/// Move atoms to new coordinates. After a surface has been generated, the next generation rebuilds
+	/// only the dots of atoms near the old and new positions and reuses the rest, for design loops
+	/// where a few side chains move per step; settings must not change in between. Moving a
+	/// molecule 1 atom drops a frozen molecule 1 surface.
+	pub fn move_atoms(&mut self, indices: &[usize], coords: &[Vec3]) -> Result<(), SurfaceCalculatorError> {
+		if indices.len() != coords.len() { return Err(SurfaceCalculatorError::InvalidSettings(format!("{} atom indices but {} coordinates to move them to", indices.len(), coords.len()))); }
+		if indices.iter().any(|&i| i >= self.run.atoms.len()) { return Err(SurfaceCalculatorError::JumpOutOfBounds); }
+		if indices.iter().any(|&i| self.run.atoms[i].molecule == 0) { self.run.frozen = None; }
+		if self.run.pending.is_none() && !(self.run.dots[0].is_empty() && self.run.dots[1].is_empty()) {
+			let attention = self.run.atoms.iter().map(|a| a.attention).collect();
+			self.run.pending = Some(PendingUpdate { centers: Vec::new(), attention, dots: self.run.dots.clone() });
+		}
+		for (&i, &c) in indices.iter().zip(coords) {
+			if let Some(pending) = &mut self.run.pending { pending.centers.extend([self.run.atoms[i].coor, c]); }
+			self.run.atoms[i].coor = c;
+		}
+		Ok(())
+	}
+
+	/// Force the attention of the atoms at `indices` for later calculations, overriding the
+	/// separation-cutoff classification: `Buried` to treat a known epitope as interface whatever its
+	/// distance, `Far` to exclude a region from surface generation. `None` restores the heuristic.
+	pub fn override_attention(&mut self, indices: &[usize], attention: Option<Attention>) -> Result<(), SurfaceCalculatorError> {
+		if indices.iter().any(|&i| i >= self.run.atoms.len()) { return Err(SurfaceCalculatorError::JumpOutOfBounds); }
+		for &i in indices { self.run.atoms[i].attention_override = attention; }
+		// Surfaces reused by an incremental update were generated under the old attention
+		self.run.pending = None;
+		Ok(())
+	}
+
+	/// Ok(true) when no residue-specific entry matched and the generic radius of the atom's element was used
+	fn assign_atom_radius(&self, atom: &mut Atom) -> Result<bool, SurfaceCalculatorError> {
 		if self.settings.use_atom_type_radius {
-			if atom.atom_type_radius != 0.0 { atom.radius = atom.atom_type_radius; return Ok(()); }
-			return Err(SurfaceCalculatorError::Io(std::io::Error::other("Missing atom_type_radius")));
+			if atom.atom_type_radius != 0.0 { atom.radius = atom.atom_type_radius; return Ok(false); }
+			return Err(SurfaceCalculatorError::MissingAtomTypeRadius { residue: atom.residue.clone(), atom: atom.atom.clone() });
 		}
 		let debug = env::var("ATOMIC_RADII_DEBUG").ok().map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false);
-		for radius in &self.radii {
-			if !wildcard_match(&atom.residue, &radius.residue) { continue; }
-			if !wildcard_match(&atom.atom, &radius.atom) { continue; }
+		let index = self.radius_index.as_ref().expect("radius index is built by init()");
+		if let Some(i) = index.find(&self.radii, &atom.residue, &atom.atom) {
+			let radius = &self.radii[i];
 			atom.radius = radius.radius;
 			if debug {
 				let is_generic = radius.residue.starts_with("***");
@@ -106,15 +669,14 @@ impl SurfaceGenerator {
 					atom.residue.trim(), atom.atom.trim(), radius.residue.trim(), radius.atom.trim(), is_generic, radius.radius
 				);
 			}
-			return Ok(());
+			return Ok(false);
 		}
 		// Element fallback: if no specific match, use generic element radii (e.g., ***:C)
 		let elem = atom.atom.chars().find(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_uppercase()).unwrap_or(' ');
 		if elem != ' ' {
 			let elem_str = elem.to_string();
-			for radius in &self.radii {
-				if !radius.residue.trim().starts_with("***") { continue; }
-				if radius.atom.trim() != elem_str { continue; }
+			if let Some(i) = index.element(&elem_str) {
+				let radius = &self.radii[i];
 				atom.radius = radius.radius;
 				if debug {
 					eprintln!(
@@ -122,11 +684,11 @@ impl SurfaceGenerator {
 						atom.residue.trim(), atom.atom.trim(), elem_str, radius.radius
 					);
 				}
-				return Ok(());
+				return Ok(true);
 			}
 		}
 		if debug { eprintln!("[ATOMIC_RADII_DEBUG] no match for {}:{}", atom.residue.trim(), atom.atom.trim()); }
-		Err(SurfaceCalculatorError::Io(std::io::Error::other(format!("No radius for {}:{}", atom.residue, atom.atom))))
+		Err(radius_not_found(atom))
 	}
 
 	pub fn assign_attention_numbers(&mut self) {
@@ -136,179 +698,450 @@ impl SurfaceGenerator {
 		self.run.results.surfaces[1].n_buried_atoms = 0;
 		self.run.results.surfaces[1].n_blocked_atoms = 0;
 
-		let sep2 = self.settings.separation_cutoff * self.settings.separation_cutoff;
-		let atoms_len = self.run.atoms.len();
-		// Compute min squared distance to any atom in the other molecule, then set attention
-		let snapshot: Vec<(usize, usize, f64)> = (0..atoms_len).map(|i| {
-			let a1 = &self.run.atoms[i];
-			let mut dist_min2 = f64::INFINITY;
-			for j in 0..atoms_len {
-				let a2 = &self.run.atoms[j];
-				if a2.molecule == a1.molecule { continue; }
-				let r2 = a1.distance_squared(a2);
-				if r2 < dist_min2 { dist_min2 = r2; }
-			}
-			(i, a1.molecule, dist_min2)
-		}).collect();
+		let t = Instant::now();
+		let cutoff = self.separation_cutoff();
+		self.run.results.separation_cutoff = cutoff;
+		let sep2 = cutoff * cutoff;
+		self.run.arrays = AtomArrays::from_atoms(&self.run.atoms);
+		let arrays = &self.run.arrays;
+		let by_molecule = [arrays.molecule_coords(0), arrays.molecule_coords(1)];
+		let in_focus = self.settings.focus.as_ref().map(|f| self.focus_mask(f));
+		let excluded: Vec<bool> = self.run.atoms.iter().map(|a| self.settings.excluded(a)).collect();
+		// Compute min squared distance to any atom in the other molecule, then set attention; atoms
+		// outside the focus skip the search and end up Far
+		let min_dist2 = |i: usize| {
+			let mol = arrays.molecule[i];
+			// Context atoms are no partner: they neither draw atoms into the interface nor get classified
+			if arrays.context[i] || in_focus.as_ref().is_some_and(|m| !m[i]) { return (i, mol, ScValue::INFINITY); }
+			let (xs, ys, zs) = &by_molecule[1 - mol.min(1)];
+			let dist_min2 = simd::min_distance_squared(xs, ys, zs, [arrays.x[i], arrays.y[i], arrays.z[i]]);
+			(i, mol, dist_min2 as ScValue)
+		};
+		let snapshot: Vec<(usize, usize, f64)> = if self.settings.enable_parallel {
+			(0..arrays.len()).into_par_iter().map(min_dist2).collect()
+		} else {
+			(0..arrays.len()).map(min_dist2).collect()
+		};
+		let adaptive = self.settings.adaptive_density;
 		for (i, mol, dist_min2) in snapshot {
 			let a1 = &mut self.run.atoms[i];
-			if dist_min2 >= sep2 {
-				a1.attention = Attention::Far;
-				self.run.results.surfaces[mol].n_blocked_atoms += 1;
-			} else {
-				a1.attention = Attention::Buried;
-				self.run.results.surfaces[mol].n_buried_atoms += 1;
+			if let Some(ad) = adaptive {
+				if dist_min2 > ad.distance * ad.distance { a1.density = ad.density; }
+			}
+			// Masked occluders and atoms outside the subset never get dots, whatever the override
+			a1.attention = match a1.attention_override {
+				_ if excluded[i] || a1.context => Attention::Far,
+				Some(forced) => forced,
+				None if dist_min2 >= sep2 => Attention::Far,
+				None => Attention::Buried,
+			};
+			if a1.context { continue; }
+			if a1.attention == Attention::Far { self.run.results.surfaces[mol].n_blocked_atoms += 1; }
+			else { self.run.results.surfaces[mol].n_buried_atoms += 1; }
+		}
+		self.run.results.timings.attention = t.elapsed().as_secs_f64();
+	}
+
+	/// `Settings::separation_cutoff`, or in auto mode the farthest an atom can be from the partner and
+	/// still take part: its expanded sphere touching a partner's (2·r_max + 2·r_p) for buried dots,
+	/// plus the band for the exposed dots that trimming measures the periphery against
+	fn separation_cutoff(&self) -> ScValue {
+		if !self.settings.auto_separation_cutoff { return self.settings.separation_cutoff; }
+		let radmax = self.run.atoms.iter().map(|a| a.radius).fold(0.0, ScValue::max);
+		let band = match self.settings.trim_mode { TrimMode::PeripheralBand => self.settings.peripheral_band, TrimMode::PartnerDistance(_) => 0.0 };
+		2.0 * radmax + 2.0 * self.settings.max_probe_radius() + band
+	}
+
+	/// Atoms within the focus radius of its center (a point, or any atom of the focus residues)
+	fn focus_mask(&self, focus: &Focus) -> Vec<bool> {
+		let arrays = &self.run.arrays;
+		let r2 = focus.radius * focus.radius;
+		match &focus.center {
+			FocusCenter::Point(c) => (0..arrays.len()).map(|i| arrays.distance_squared(i, *c) <= r2).collect(),
+			FocusCenter::Residues(ranges) => {
+				let centers: Vec<usize> = (0..arrays.len()).filter(|&i| ranges.iter().any(|r| r.contains(&self.run.atoms[i]))).collect();
+				let xs: Vec<GeomValue> = centers.iter().map(|&i| arrays.x[i]).collect();
+				let ys: Vec<GeomValue> = centers.iter().map(|&i| arrays.y[i]).collect();
+				let zs: Vec<GeomValue> = centers.iter().map(|&i| arrays.z[i]).collect();
+				(0..arrays.len()).map(|i| !centers.is_empty() && simd::min_distance_squared(&xs, &ys, &zs, [arrays.x[i], arrays.y[i], arrays.z[i]]) as ScValue <= r2).collect()
 			}
 		}
 	}
 
 	pub fn calc(&mut self) -> Result<(), SurfaceCalculatorError> {
+		self.settings.validate()?;
 		self.init()?;
+		self.remove_masked_atoms();
+		self.remove_coincident_atoms();
 		self.run.results.valid = 0;
 		if self.run.atoms.is_empty() { return Err(SurfaceCalculatorError::NoAtoms); }
-		self.assign_attention_numbers();
-		self.generate_molecular_surfaces()?;
-		Ok(())
+		self.in_pool(|g| { g.refresh_frozen_surface()?; g.assign_attention_numbers(); g.generate_molecular_surfaces() })
 	}
 
 	pub(crate) fn generate_molecular_surfaces(&mut self) -> Result<(), SurfaceCalculatorError> {
 		if self.run.atoms.is_empty() { return Err(SurfaceCalculatorError::NoAtoms); }
-		self.calc_dots_for_all_atoms()?;
+		self.calc_dots_for_all_atoms(false)?;
 		Ok(())
 	}
 
-	fn calc_dots_for_all_atoms(&mut self) -> Result<(), SurfaceCalculatorError> {
+	/// Regenerate the dots of the last surface generation at `density` dots/Å², reusing its atom
+	/// classification, neighbor tables, probes and accessibility, none of which depend on the
+	/// density. Atoms keeping their molecule's density (not adaptive or per-atom overrides) take the
+	/// new one. Needs a completed full generation of the same atoms with nothing frozen or pending.
+	pub(crate) fn regenerate_dots(&mut self, density: ScValue) -> Result<(), SurfaceCalculatorError> {
+		let previous = [0, 1].map(|m| self.settings.molecule_density(m));
+		self.settings.dot_density = density;
+		for a in &mut self.run.atoms {
+			if a.density == previous[a.molecule] { a.density = self.settings.molecule_density(a.molecule); }
+		}
+		for i in 0..2 {
+			self.run.dots[i].clear();
+			self.run.trimmed_dots[i].clear();
+			self.run.pairs[i].clear();
+		}
+		self.run.dropped = [0, 0];
+		self.run.peripheral = None;
+		// Keep what belongs to the atoms and their classification; everything else is recomputed
+		let previous = std::mem::take(&mut self.run.results);
+		self.run.results.n_atoms = previous.n_atoms;
+		self.run.results.n_context_atoms = previous.n_context_atoms;
+		self.run.results.surfaces = previous.surfaces.map(|s| SurfaceStats { n_atoms: s.n_atoms, n_buried_atoms: s.n_buried_atoms, n_blocked_atoms: s.n_blocked_atoms, ..SurfaceStats::default() });
+		self.run.results.timings = Timings { radii: previous.timings.radii, attention: previous.timings.attention, ..Timings::default() };
+		self.run.results.skipped_atoms = previous.skipped_atoms;
+		self.run.results.warnings = previous.warnings;
+		self.run.results.separation_cutoff = previous.separation_cutoff;
+		self.calc_dots_for_all_atoms(true)
+	}
+
+	/// Generate the dots of every atom; with `reuse_geometry`, the neighbor tables and probes of the
+	/// previous generation are kept and only the dots are emitted again
+	fn calc_dots_for_all_atoms(&mut self, reuse_geometry: bool) -> Result<(), SurfaceCalculatorError> {
 		self.run.radmax = 0.0;
 		for a in &self.run.atoms { if a.radius > self.run.radmax { self.run.radmax = a.radius; } }
-		let atoms_ptrs: Vec<*const Atom> = self.run.atoms.iter().map(|a| a as *const Atom).collect();
-		let len = self.run.atoms.len();
-		// Phase 1: compute neighbors in parallel to avoid repeated mutable borrows
-		if self.settings.enable_parallel { self.compute_neighbors_all_parallel()?; }
-		for i in 0..len {
+		// Inactive atoms of a partial regeneration sit out as Far until the dots are merged
+		let partial = if reuse_geometry { None } else { self.partial_surface() };
+		self.capped_arcs.store(0, AtomicOrdering::Relaxed);
+		let attention: Vec<Attention> = self.run.atoms.iter().map(|a| a.attention).collect();
+		if let Some(p) = &partial {
+			for (a, &on) in self.run.atoms.iter_mut().zip(&p.active) { if !on { a.attention = Attention::Far; } }
+		}
+		self.run.arrays = AtomArrays::from_atoms(&self.run.atoms);
+		// With the GPU backend, contact dots are sampled on the GPU and burial is classified in one
+		// pass after generation; the empty grid makes the inline checks report "not buried" until
+		// then. Streaming and dot visitors need each dot's burial as it is generated
+		let deferred_burial = self.gpu_burial() && !self.settings.streaming && self.sink.is_none();
+		self.run.burial = if deferred_burial { BurialGrid::default() } else { self.burial_grid() };
+		self.run.stream = self.settings.streaming.then(DotStream::default);
+		self.run.peripheral = None;
+		// Phase 1: neighbor tables, held outside `run` while generation mutates it
+		let adjacency = if reuse_geometry {
+			Ok((std::mem::take(&mut self.run.neighbors), std::mem::take(&mut self.run.buried_by)))
+		} else {
+			// Every neighbor and burial bridge is shorter than one cell, so searches stay local
+			let grid = SpatialGrid::new((0..self.run.atoms.len()).map(|i| self.run.arrays.coor(i)), 2.0 * self.run.radmax + 2.0 * self.settings.max_probe_radius());
+			let t = Instant::now();
+			let adjacency = self.build_adjacency(&grid);
+			self.run.results.timings.neighbors = t.elapsed().as_secs_f64();
+			adjacency
+		};
+		let generated = adjacency.and_then(|(neighbors, buried_by)| {
+			let generated = self.generate_dots(&neighbors, &buried_by, !reuse_geometry, deferred_burial);
+			self.run.neighbors = neighbors;
+			self.run.buried_by = buried_by;
+			generated
+		});
+		for (a, att) in self.run.atoms.iter_mut().zip(attention) { a.attention = att; }
+		// Unlike the input warnings, this one describes the current surfaces only
+		self.run.results.warnings.retain(|w| w.kind != WarningKind::ArcSamplingCapped);
+		let capped = self.capped_arcs.load(AtomicOrdering::Relaxed);
+		if capped > 0 { self.run.results.warnings.push(Warning { kind: WarningKind::ArcSamplingCapped, molecule: None, count: capped, examples: Vec::new() }); }
+		generated?;
+		let t = Instant::now();
+		self.settings.report_progress(Phase::Burial, 0, 1);
+		if let Some(p) = partial { self.merge_partial_surface(p, deferred_burial); }
+		if deferred_burial { self.classify_burial_deferred(); }
+		self.canonicalize_dot_order();
+		self.settings.report_progress(Phase::Burial, 1, 1);
+		self.run.results.timings.burial = t.elapsed().as_secs_f64();
+		let marked = self.mark_peripheral_dots();
+		self.run.stream = None;
+		marked
+	}
+
+	/// Streaming mode's second pass under peripheral-band trimming: emit every dot again over the
+	/// same neighbor tables and probes, storing none, so that each exposed dot marks the stored
+	/// buried dots of its molecule within the band. Counts are those of the first pass; timings add up.
+	fn mark_peripheral_dots(&mut self) -> Result<(), SurfaceCalculatorError> {
+		if self.run.stream.is_none() || !matches!(self.settings.trim_mode, TrimMode::PeripheralBand) { return Ok(()); }
+		let marks = PeripheralMarks::new(&self.run.dots, self.settings.peripheral_band);
+		self.run.stream = Some(DotStream { marks: Some(marks) });
+		let (dots, dropped, first) = (self.run.results.dots.clone(), self.run.dropped, self.run.results.timings.clone());
+		let capped = self.capped_arcs.load(AtomicOrdering::Relaxed);
+		let (neighbors, buried_by) = (std::mem::take(&mut self.run.neighbors), std::mem::take(&mut self.run.buried_by));
+		let generated = self.generate_dots(&neighbors, &buried_by, false, false);
+		self.run.neighbors = neighbors;
+		self.run.buried_by = buried_by;
+		self.run.results.dots = dots;
+		self.run.dropped = dropped;
+		self.capped_arcs.store(capped, AtomicOrdering::Relaxed);
+		let timings = &mut self.run.results.timings;
+		timings.toroidal += first.toroidal;
+		timings.contact += first.contact;
+		timings.concave += first.concave;
+		let marks = self.run.stream.take().and_then(|s| s.marks);
+		generated?;
+		self.run.peripheral = marks.map(|m| m.marked.map(|flags| flags.into_iter().map(AtomicBool::into_inner).collect()));
+		Ok(())
+	}
+
+	/// Emit the dots of every atom; `build_triplets` false keeps the probes already in `run.probes`,
+	/// and `gpu` samples the contact dots on the GPU when one is available
+	fn generate_dots(&mut self, neighbors: &Csr, buried_by: &Csr, build_triplets: bool, gpu: bool) -> Result<(), SurfaceCalculatorError> {
+		// Accessibility found while building probes is collected here and written back once the
+		// probe pass is done; an atom's flag is final by the time the loop reaches it.
+		let mut accessible: Vec<bool> = self.run.atoms.iter().map(|a| a.accessible).collect();
+		let mut scratch = Scratch::default();
+		let (mut toroidal, mut contact) = (0.0, 0.0);
+		let n = self.run.atoms.len();
+		for i in 0..n {
+			self.settings.report_progress(Phase::Toroidal, i, n);
 			let att = self.run.atoms[i].attention;
 			if matches!(att, Attention::Far) { continue; }
-			if !self.settings.enable_parallel { let _ = self.find_neighbors_for_atom_by_index(i, &atoms_ptrs)?; }
-			if matches!(self.run.atoms[i].attention, Attention::Far) { continue; }
-			if matches!(self.run.atoms[i].attention, Attention::Consider) && self.run.atoms[i].buried_by_indices.is_empty() { continue; }
-			self.build_probes(i, &atoms_ptrs)?;
-			if !self.settings.enable_parallel && self.run.atoms[i].accessible { self.emit_contact_surface_for_atom(i)?; }
-		}
-		// Phase 3: contact dot generation in parallel (uses per-atom buffers)
-		if self.settings.enable_parallel { self.generate_contact_surface_parallel()?; }
-		if self.settings.rp > 0.0 {
+			if matches!(att, Attention::Consider) && buried_by.row(i).is_empty() { continue; }
+			self.check_cancelled()?;
+			let t = Instant::now();
+			self.build_probes(i, neighbors, build_triplets, &mut accessible, &mut scratch)?;
+			toroidal += t.elapsed().as_secs_f64();
+			if !self.settings.enable_parallel && !gpu && accessible[i] {
+				self.run.atoms[i].accessible = true;
+				let t = Instant::now();
+				self.emit_contact_surface_for_atom(i, neighbors, &mut scratch)?;
+				contact += t.elapsed().as_secs_f64();
+			}
+		}
+		self.settings.report_progress(Phase::Toroidal, n, n);
+		for (a, acc) in self.run.atoms.iter_mut().zip(accessible) { a.accessible = acc; }
+		// Phase 3: contact dot generation on the GPU, or in parallel (uses per-atom buffers)
+		if gpu || self.settings.enable_parallel {
+			let t = Instant::now();
+			if !(gpu && self.generate_contact_surface_gpu(neighbors, buried_by)?) {
+				if self.settings.enable_parallel { self.generate_contact_surface_parallel(neighbors, buried_by)?; }
+				else { self.generate_contact_surface_serial(neighbors, buried_by)?; }
+			}
+			contact += t.elapsed().as_secs_f64();
+		}
+		self.run.results.timings.toroidal = toroidal;
+		self.run.results.timings.contact = contact;
+		let t = Instant::now();
+		if self.settings.max_probe_radius() > 0.0 {
 			if self.settings.enable_parallel { self.generate_concave_surface_parallel()?; }
 			else { self.generate_concave_surface()?; }
 		}
+		self.run.results.timings.concave = t.elapsed().as_secs_f64();
 		Ok(())
 	}
 
-	fn compute_neighbors_all_parallel(&mut self) -> Result<(), SurfaceCalculatorError> {
+	/// What the next generation may reuse: a frozen molecule 1 surface (restricted to atoms near
+	/// molecule 2, as a full run samples), or the previous surfaces away from moved atoms
+	fn partial_surface(&mut self) -> Option<PartialSurface> {
+		let n = self.run.atoms.len();
+		let pending = self.run.pending.take();
+		// Streamed surfaces lack the dots far from the old partner, and reused dots would bypass the
+		// visitor, so nothing is reused
+		if self.settings.streaming || self.sink.is_some() { return None; }
+		if let Some(frozen) = &self.run.frozen {
+			let atoms = &self.run.atoms;
+			let active: Vec<bool> = atoms.iter().map(|a| a.molecule == 1).collect();
+			let near = frozen.dots.iter().filter(|d| !matches!(atoms[d.atom_index].attention, Attention::Far)).cloned().collect();
+			return Some(PartialSurface { owners: active.clone(), active, retained: [near, Vec::new()] });
+		}
+		let pending = pending?;
+		if pending.attention.len() != n { return None; }
+		let mut centers = pending.centers;
+		// Atoms crossing the separation cutoff change which pairs and triplets are built around them
+		centers.extend(self.run.atoms.iter().zip(&pending.attention).filter(|(a, old)| a.attention != **old).map(|(a, _)| a.coor));
+		// A dot depends on atoms within two bridges of its owner (pair and triplet partners and their
+		// neighbors); building those pairs and triplets needs atoms one bridge further out
+		let bridge = 2.0 * self.run.radmax + 2.0 * self.settings.max_probe_radius();
+		let grid = SpatialGrid::new(self.run.atoms.iter().map(|a| a.coor), bridge);
+		let (mut owners, mut active) = (vec![false; n], vec![false; n]);
+		let mut candidates = Vec::new();
+		for c in centers {
+			grid.candidates_into(c, 3, &mut candidates);
+			for &j in &candidates {
+				let d = self.run.atoms[j].coor.distance(c);
+				if d < 2.0 * bridge { owners[j] = true; }
+				if d < 3.0 * bridge { active[j] = true; }
+			}
+		}
+		let retained = pending.dots.map(|dots| dots.into_iter().filter(|d| !owners[d.atom_index]).collect());
+		Some(PartialSurface { active, owners, retained })
+	}
+
+	/// Keep the regenerated dots of the owners, add the retained dots with their burial classified
+	/// against the current partner, and recount the dot totals
+	fn merge_partial_surface(&mut self, partial: PartialSurface, deferred_burial: bool) {
+		let PartialSurface { owners, retained, .. } = partial;
+		let parallel = self.settings.enable_parallel;
+		for (mol, mut kept) in retained.into_iter().enumerate() {
+			if !deferred_burial {
+				let rp = self.settings.probe_radius(mol);
+				let burial = &self.run.burial;
+				let classify = |dot: &mut Dot| dot.buried = burial.is_buried_by(1 - mol, Self::probe_center(dot, rp), rp);
+				if parallel { kept.par_iter_mut().for_each(classify); }
+				else { kept.iter_mut().for_each(classify); }
+			}
+			self.run.dots[mol].retain(|d| owners[d.atom_index]);
+			self.run.dots[mol].append(&mut kept);
+		}
+		self.run.results.dots = DotStats::default();
+		for dot in self.run.dots.iter().flatten() {
+			match dot.kind {
+				DotKind::Contact => self.run.results.dots.convex += 1,
+				DotKind::Reentrant => self.run.results.dots.toroidal += 1,
+				DotKind::Cavity => self.run.results.dots.concave += 1,
+			}
+		}
+	}
+
+	/// Serial and parallel generation interleave contact and reentrant dots differently; a stable
+	/// sort by (owning atom, kind) gives the same dot order, and therefore the same dot indices,
+	/// regardless of `enable_parallel` or thread count.
+	fn canonicalize_dot_order(&mut self) {
+		let parallel = self.settings.enable_parallel;
+		for dots in self.run.dots.iter_mut() {
+			if parallel { dots.par_sort_by_key(|d| (d.atom_index, d.kind)); }
+			else { dots.sort_by_key(|d| (d.atom_index, d.kind)); }
+		}
+	}
+
+	fn burial_grid(&self) -> BurialGrid { BurialGrid::new(&self.run.arrays, self.run.radmax + self.settings.max_probe_radius()) }
+
+	#[cfg(feature = "gpu")]
+	fn gpu_burial(&self) -> bool { self.settings.use_gpu && crate::sc::gpu::available() }
+	#[cfg(not(feature = "gpu"))]
+	fn gpu_burial(&self) -> bool { false }
+
+	/// Probe center of a dot, recovered from its outward normal (`outnml = (pcen - coor) / rp`)
+	fn probe_center(dot: &Dot, rp: ScValue) -> Vec3 { if rp <= 0.0 { dot.coor } else { dot.coor + dot.outnml * rp } }
+
+	/// Classify burial of every generated dot on the GPU against the burial grid's cells, falling
+	/// back to the CPU grid per molecule
+	fn classify_burial_deferred(&mut self) {
+		let grid = self.burial_grid();
+		for mol in 0..2 {
+			let rp = self.settings.probe_radius(mol);
+			let other = 1 - mol;
+			#[cfg(feature = "gpu")]
+			if let Some(bins) = grid.dense_bins(other) {
+				let centers: Vec<Vec3> = self.run.dots[mol].iter().map(|d| Self::probe_center(d, rp)).collect();
+				let points: Vec<[f32; 4]> = centers.iter().map(|p| [p.x as f32, p.y as f32, p.z as f32, 0.0]).collect();
+				let cells: Vec<u32> = centers.iter().map(|&p| bins.cell_of(p)).collect();
+				if let Some(flags) = crate::sc::gpu::buried_flags(&points, &cells, &bins, rp as f32) {
+					for (dot, buried) in self.run.dots[mol].iter_mut().zip(flags) { dot.buried = buried; }
+					continue;
+				}
+			}
+			for dot in self.run.dots[mol].iter_mut() { dot.buried = grid.is_buried_by(other, Self::probe_center(dot, rp), rp); }
+		}
+	}
+
+	/// Same-molecule neighbors (nearest first) and opposite-molecule burying atoms of every atom as
+	/// CSR tables; Far atoms get empty rows. In parallel, each task fills a contiguous block of rows
+	/// and the blocks are concatenated in atom order.
+	fn build_adjacency(&mut self, grid: &SpatialGrid) -> Result<(Csr, Csr), SurfaceCalculatorError> {
 		let len = self.run.atoms.len();
-		let rp = self.settings.rp;
-		let radmax = self.run.radmax;
-		let _bb2 = (4.0 * radmax + 4.0 * rp).powi(2);
-		let atoms: &Vec<Atom> = &self.run.atoms;
-		let results: Result<Vec<(Vec<usize>, Vec<usize>, bool)>, SurfaceCalculatorError> = (0..len).into_par_iter().map(|i| {
-			let atom1 = &atoms[i];
-			let mut neighbor_indices: Vec<usize> = Vec::new();
-			let mut buried_by_indices: Vec<usize> = Vec::new();
-			// count not used; rely on buried_by_indices length
-			for j in 0..len {
-				if j == i { continue; }
-				let atom2 = &atoms[j];
-				if atom1.natom == atom2.natom { continue; }
-				let d2 = atom1.distance_squared(atom2);
-				if atom1.molecule == atom2.molecule {
-					if d2 <= 0.0001 {
-						return Err(SurfaceCalculatorError::Coincident(format!(
-							"{}:{}:{} @ ({:.3},{:.3},{:.3}) == {}:{}:{} @ ({:.3},{:.3},{:.3})",
-							atom1.natom, atom1.residue, atom1.atom, atom1.coor.x, atom1.coor.y, atom1.coor.z,
-							atom2.natom, atom2.residue, atom2.atom, atom2.coor.x, atom2.coor.y, atom2.coor.z
-						)));
+		let rps = [self.settings.probe_radius(0), self.settings.probe_radius(1)];
+		let (atoms, arrays, settings) = (&self.run.atoms, &self.run.arrays, &self.settings);
+		let coincident2 = settings.coincident_tolerance * settings.coincident_tolerance;
+		let done = AtomicUsize::new(0);
+		settings.report_progress(Phase::Neighbors, 0, len);
+		// Each fold carries its own candidate buffer alongside the rows it builds
+		let push_rows = |(mut neighbors, mut buried_by, mut candidates): (Csr, Csr, Vec<usize>), i: usize| -> Result<(Csr, Csr, Vec<usize>), SurfaceCalculatorError> {
+			if settings.cancelled() { return Err(SurfaceCalculatorError::Cancelled); }
+			settings.report_progress(Phase::Neighbors, done.fetch_add(1, AtomicOrdering::Relaxed) + 1, len);
+			if !matches!(atoms[i].attention, Attention::Far) {
+				let (center, mol1, r1) = (arrays.coor(i), arrays.molecule[i], arrays.radius(i));
+				grid.candidates_into(center, 1, &mut candidates);
+				for &j in &candidates {
+					if j == i { continue; }
+					let d2 = arrays.distance_squared(j, center);
+					if arrays.molecule[j] == mol1 {
+						if d2 <= coincident2 { return Err(coincident_error(&atoms[i], &atoms[j])); }
+						let bridge = r1 + arrays.radius(j) + 2.0 * rps[mol1];
+						if d2 < bridge * bridge { neighbors.push(j); }
+					} else if !arrays.context[j] {
+						let bridge = r1 + arrays.radius(j) + rps[mol1] + rps[arrays.molecule[j]];
+						if d2 < bridge * bridge { buried_by.push(j); }
 					}
-					let bridge = atom1.radius + atom2.radius + 2.0 * rp;
-					if d2 < bridge * bridge { neighbor_indices.push(j); }
-				} else {
-					let bridge = atom1.radius + atom2.radius + 2.0 * rp;
-					if d2 < bridge * bridge { buried_by_indices.push(j); }
 				}
+				neighbors.open_row_mut().sort_unstable_by(|&a1, &a2| {
+					let d1 = arrays.distance_squared(a1, center);
+					let d2 = arrays.distance_squared(a2, center);
+					if d1 < d2 { Ordering::Less } else if d1 > d2 { Ordering::Greater } else { Ordering::Equal }
+				});
 			}
-			let center = atom1.coor;
-			neighbor_indices.sort_unstable_by(|&a1, &a2| {
-				let d1 = atoms[a1].coor.distance_squared(center);
-				let d2 = atoms[a2].coor.distance_squared(center);
-				if d1 < d2 { Ordering::Less } else if d1 > d2 { Ordering::Greater } else { Ordering::Equal }
-			});
-			let accessible = neighbor_indices.is_empty();
-			Ok((neighbor_indices, buried_by_indices, accessible))
-		}).collect();
-		let outs = results?;
-		for (i, (neighbors, buried_by, accessible)) in outs.into_iter().enumerate() {
-			let a1 = &mut self.run.atoms[i];
-			a1.neighbor_indices = neighbors;
-			a1.buried_by_indices = buried_by;
-			if accessible { a1.accessible = true; }
+			neighbors.end_row();
+			buried_by.end_row();
+			Ok((neighbors, buried_by, candidates))
+		};
+		type Rows = (Csr, Csr, Vec<usize>);
+		#[cfg(feature = "parallel")]
+		let (neighbors, buried_by, _) = if self.settings.enable_parallel {
+			let blocks: Vec<Rows> = (0..len).into_par_iter().try_fold(Rows::default, push_rows).collect::<Result<_, _>>()?;
+			blocks.into_iter().fold(Rows::default(), |(mut n, mut b, c), (block_n, block_b, _)| { n.append(block_n); b.append(block_b); (n, b, c) })
+		} else {
+			(0..len).try_fold(Rows::default(), push_rows)?
+		};
+		#[cfg(not(feature = "parallel"))]
+		let (neighbors, buried_by, _) = (0..len).try_fold(Rows::default(), push_rows)?;
+		for (i, a) in self.run.atoms.iter_mut().enumerate() {
+			if !matches!(a.attention, Attention::Far) && neighbors.row(i).is_empty() { a.accessible = true; }
 		}
-		Ok(())
+		Ok((neighbors, buried_by))
 	}
 
-	fn generate_contact_surface_parallel(&mut self) -> Result<(), SurfaceCalculatorError> {
-		let rp = self.settings.rp;
+	fn generate_contact_surface_parallel(&mut self, neighbors: &Csr, buried_by: &Csr) -> Result<(), SurfaceCalculatorError> {
+		let limit = self.arc_limit();
+		let rps = [self.settings.probe_radius(0), self.settings.probe_radius(1)];
 		let atoms: &Vec<Atom> = &self.run.atoms;
-		let results: Vec<(usize, Vec<Dot>, usize)> = (0..atoms.len()).into_par_iter().filter_map(|i| {
+		let burial = &self.run.burial;
+		let stream = &self.run.stream;
+		let settings = &self.settings;
+		// Sampled points scale with the sphere area, and each is tested against every neighbor
+		let order = schedule_by_cost(atoms.iter().enumerate().map(|(i, a)| {
+			if matches!(a.attention, Attention::Far) || !a.accessible { return 0.0; }
+			a.density * a.radius * a.radius * (1 + neighbors.row(i).len()) as ScValue
+		}));
+		let done = AtomicUsize::new(0);
+		settings.report_progress(Phase::Contact, 0, order.len());
+		let mut results: Vec<(usize, usize, Vec<Dot>, usize)> = order.par_iter().with_max_len(1).map_init(Scratch::default, |scratch, &i| {
+			if settings.cancelled() { return None; }
+			settings.report_progress(Phase::Contact, done.fetch_add(1, AtomicOrdering::Relaxed) + 1, order.len());
 			let a_i = &atoms[i];
+			let rp = rps[a_i.molecule];
 			let att = a_i.attention;
 			if matches!(att, Attention::Far) { return None; }
-			if matches!(att, Attention::Consider) && a_i.buried_by_indices.is_empty() { return None; }
+			if matches!(att, Attention::Consider) && buried_by.row(i).is_empty() { return None; }
 			if !a_i.accessible { return None; }
-			let neighbors = a_i.neighbor_indices.clone();
-			let mut north_dir = Vec3::new(0.0, 0.0, 1.0);
-			let mut south_dir = Vec3::new(0.0, 0.0, -1.0);
-			let mut equatorial_vector = Vec3::new(1.0, 0.0, 0.0);
+			let neighbors = neighbors.row(i);
 			let radius_i = a_i.radius;
 			let expanded_radius_i = a_i.radius + rp;
-			if !neighbors.is_empty() {
-				let neighbor = &atoms[neighbors[0]];
-				north_dir = a_i.coor - neighbor.coor;
-				north_dir.normalize();
-				let mut temp_vec = Vec3::new(north_dir.y*north_dir.y + north_dir.z*north_dir.z, north_dir.x*north_dir.x + north_dir.z*north_dir.z, north_dir.x*north_dir.x + north_dir.y*north_dir.y);
-				temp_vec.normalize();
-				let dt = temp_vec.dot(north_dir);
-				if dt.abs() > 0.99 { temp_vec = Vec3::new(1.0, 0.0, 0.0); }
-				equatorial_vector = north_dir.cross(temp_vec);
-				equatorial_vector.normalize();
-				let _ = equatorial_vector.cross(north_dir);
-				let radius_neighbor = neighbor.radius;
-				let expanded_radius_j = neighbor.radius + rp;
-				let dij = a_i.coor.distance(neighbor.coor);
-				let unit_axis = (neighbor.coor - a_i.coor) / dij;
-				let asymmetry_term = (expanded_radius_i*expanded_radius_i - expanded_radius_j*expanded_radius_j) / dij;
-				let midplane_center = (a_i.coor + neighbor.coor) * 0.5 + (unit_axis * (asymmetry_term*0.5));
-				let mut far_term = (expanded_radius_i + expanded_radius_j)*(expanded_radius_i + expanded_radius_j) - dij*dij;
-				if far_term <= 0.0 { return None; }
-				far_term = far_term.sqrt();
-				let mut contain_term = dij*dij - (radius_i - radius_neighbor).powi(2);
-				if contain_term <= 0.0 { return None; }
-				contain_term = contain_term.sqrt();
-				let ring_radius = 0.5 * far_term * contain_term / dij;
-				let ring_point = midplane_center + (equatorial_vector.cross(north_dir) * ring_radius);
-				south_dir = (ring_point - a_i.coor) / expanded_radius_i;
-				if north_dir.cross(south_dir).dot(equatorial_vector) <= 0.0 { return None; }
-			}
-			let mut lats: Vec<Vec3> = Vec::new();
+			// Degenerate frames are skipped here; the serial path reports them
+			let Ok((north_dir, south_dir, equatorial_vector)) = contact_frame(a_i, neighbors.first().map(|&j| &atoms[j]), rp) else { return None };
+			let Scratch { lats, points, .. } = scratch;
 			let o = Vec3::zero();
-			let cs = geom_sample_arc(o, radius_i, equatorial_vector, a_i.density, north_dir, south_dir, &mut lats).ok()?;
+			let cs = geom_sample_arc(limit, o, radius_i, equatorial_vector, a_i.density, north_dir, south_dir, lats);
 			if lats.is_empty() { return None; }
 			let mut dots: Vec<Dot> = Vec::new();
-			let mut points: Vec<Vec3> = Vec::new();
+			let mut dropped = 0;
 			for ilat in lats.iter() {
 				let dt = ilat.dot(north_dir);
 				let cen = a_i.coor + (north_dir * dt);
 				let mut rad = radius_i*radius_i - dt*dt;
 				if rad <= 0.0 { continue; }
 				rad = rad.sqrt();
-				points.clear();
-				let ps = geom_sample_circle(cen, rad, north_dir, a_i.density, &mut points).ok()?;
+				let ps = geom_sample_circle(limit, cen, rad, north_dir, a_i.density, points);
 				if points.is_empty() { continue; }
 				let area = ps * cs;
 				for &point in points.iter() {
@@ -322,82 +1155,125 @@ impl SurfaceGenerator {
 					if coll { continue; }
 					// burial check against opposite molecule
 					let other_mol = if a_i.molecule == 0 { 1 } else { 0 };
-					let mut buried = false;
-					for b in atoms.iter() {
-						if b.molecule != other_mol { continue; }
-						let erl = b.radius + rp;
-						let d = pcen.distance_squared(b.coor);
-						if d <= erl*erl { buried = true; break; }
-					}
+					let buried = burial.is_buried_by(other_mol, pcen, rp);
 					let outnml = if rp <= 0.0 { point - a_i.coor } else { (pcen - point) / rp };
-					dots.push(Dot { coor: point, outnml, area, buried, kind: DotKind::Contact, atom_index: i });
+					let dot = Dot { coor: point, outnml, area, buried, kind: DotKind::Contact, atom_index: i };
+					if stream.as_ref().is_none_or(|s| s.keeps(a_i.molecule, &dot)) { dots.push(dot); } else { dropped += 1; }
 				}
 			}
-			if dots.is_empty() { None } else { let n = dots.len(); Some((a_i.molecule, dots, n)) }
-		}).collect();
-		for (mol, mut dots, n) in results.into_iter() {
-			self.run.results.dots.convex += n;
-			self.run.dots[mol].append(&mut dots);
+			if dots.is_empty() && dropped == 0 { None } else { Some((i, a_i.molecule, dots, dropped)) }
+		}).flatten().collect();
+		self.check_cancelled()?;
+		results.sort_unstable_by_key(|r| r.0);
+		for (_, mol, mut dots, dropped) in results.into_iter() {
+			self.run.results.dots.convex += dots.len() + dropped;
+			self.run.dropped[mol] += dropped;
+			self.emit_dots(mol, &mut dots);
 		}
 		Ok(())
 	}
 
 
-	fn find_neighbors_for_atom_by_index(&mut self, atom_index: usize, atoms_ptrs: &[*const Atom]) -> Result<bool, SurfaceCalculatorError> {
-		let mut nbb = 0;
-		let bb2 = (4.0 * self.run.radmax + 4.0 * self.settings.rp).powi(2);
-		let total = self.run.atoms.len();
-		let (_left, rest) = self.run.atoms.split_at_mut(atom_index);
-		let (atom1, _right) = rest.split_first_mut().unwrap();
-		atom1.neighbor_indices.clear();
-		atom1.buried_by_indices.clear();
-		for j in 0..total {
-			if j == atom_index { continue; }
-			let ptr2 = atoms_ptrs[j];
-			let atom2 = unsafe { &*ptr2 };
-			if atom1.natom == atom2.natom { continue; }
-			if atom1.molecule == atom2.molecule {
-				let d2 = atom1.distance_squared(atom2);
-				if d2 <= 0.0001 {
-					return Err(SurfaceCalculatorError::Coincident(format!(
-						"{}:{}:{} @ ({:.3},{:.3},{:.3}) == {}:{}:{} @ ({:.3},{:.3},{:.3})",
-						atom1.natom, atom1.residue, atom1.atom, atom1.coor.x, atom1.coor.y, atom1.coor.z,
-						atom2.natom, atom2.residue, atom2.atom, atom2.coor.x, atom2.coor.y, atom2.coor.z
-					)));
-				}
-				let bridge = atom1.radius + atom2.radius + 2.0 * self.settings.rp;
-				if d2 < bridge * bridge { atom1.neighbor_indices.push(j); }
-			} else {
-				// Include all opposite-molecule atoms for burial check; geometry will decide actual burial
-				let d2 = atom1.distance_squared(atom2);
-				if d2 < bb2 { nbb += 1; }
-				let bridge = atom1.radius + atom2.radius + 2.0 * self.settings.rp;
-				if d2 < bridge * bridge { atom1.buried_by_indices.push(j); }
+	/// Contact dots of every atom after the probe pass, one atom at a time (the GPU fallback when
+	/// parallelism is off)
+	fn generate_contact_surface_serial(&mut self, neighbors: &Csr, buried_by: &Csr) -> Result<(), SurfaceCalculatorError> {
+		let mut scratch = Scratch::default();
+		for i in 0..self.run.atoms.len() {
+			let a = &self.run.atoms[i];
+			if matches!(a.attention, Attention::Far) || !a.accessible { continue; }
+			if matches!(a.attention, Attention::Consider) && buried_by.row(i).is_empty() { continue; }
+			self.check_cancelled()?;
+			self.emit_contact_surface_for_atom(i, neighbors, &mut scratch)?;
+		}
+		Ok(())
+	}
+
+	/// Contact dots sampled on the GPU (see `gpu::contact_flags`): the latitude circles of every
+	/// atom are laid out here, the GPU places their points and drops those inside a neighbor's
+	/// expanded sphere, and the kept points are rebuilt in f64 with burial left to the deferred
+	/// pass. Atoms with a degenerate frame are skipped, as in the parallel path. Returns false,
+	/// having emitted nothing, when no GPU is available.
+	#[cfg(feature = "gpu")]
+	fn generate_contact_surface_gpu(&mut self, neighbors: &Csr, buried_by: &Csr) -> Result<bool, SurfaceCalculatorError> {
+		use crate::sc::gpu::{self, ContactCircle};
+		/// Where each circle's points go once kept
+		struct Layout { atom: usize, center: Vec3, rad: ScValue, x: Vec3, y: Vec3, delta: ScValue, count: usize, area: ScValue }
+		let limit = self.arc_limit();
+		let rps = [self.settings.probe_radius(0), self.settings.probe_radius(1)];
+		let atoms = &self.run.atoms;
+		self.settings.report_progress(Phase::Contact, 0, 1);
+		let (mut circles, mut layouts) = (Vec::new(), Vec::new());
+		let mut lats = Vec::new();
+		let mut n_points = 0u32;
+		let capped = self.capped_arcs.load(AtomicOrdering::Relaxed);
+		for (i, a) in atoms.iter().enumerate() {
+			if matches!(a.attention, Attention::Far) || !a.accessible { continue; }
+			if matches!(a.attention, Attention::Consider) && buried_by.row(i).is_empty() { continue; }
+			let rp = rps[a.molecule];
+			let Ok((north_dir, south_dir, equatorial_vector)) = contact_frame(a, neighbors.row(i).first().map(|&j| &atoms[j]), rp) else { continue };
+			let cs = geom_sample_arc(limit, Vec3::zero(), a.radius, equatorial_vector, a.density, north_dir, south_dir, &mut lats);
+			let (x, y) = circle_axes(north_dir);
+			for ilat in &lats {
+				let dt = ilat.dot(north_dir);
+				let rad = a.radius*a.radius - dt*dt;
+				if rad <= 0.0 { continue; }
+				let rad = rad.sqrt();
+				let (delta, count) = arc_steps(limit, rad, 2.0 * PI, a.density);
+				if count == 0 { continue; }
+				let center = a.coor + (north_dir * dt);
+				let f = |v: Vec3, w: ScValue| [v.x as f32, v.y as f32, v.z as f32, w as f32];
+				circles.push(ContactCircle { center: f(center, rad), x: f(x, delta), y: f(y, rp), atom: i as u32, first: n_points, count: count as u32, pad: 0 });
+				layouts.push(Layout { atom: i, center, rad, x, y, delta, count, area: rad * 2.0 * PI / count as ScValue * cs });
+				n_points += count as u32;
 			}
 		}
-		if matches!(atom1.attention, Attention::Consider) && nbb == 0 { return Ok(false); }
-		if atom1.neighbor_indices.is_empty() { atom1.accessible = true; return Ok(false); }
-		let center = atom1.coor;
-		atom1.neighbor_indices.sort_unstable_by(|&a1, &a2| {
-			let d1 = unsafe { (*atoms_ptrs[a1]).coor.distance_squared(center) };
-			let d2 = unsafe { (*atoms_ptrs[a2]).coor.distance_squared(center) };
-			if d1 < d2 { Ordering::Less } else if d1 > d2 { Ordering::Greater } else { Ordering::Equal }
-		});
+		let spheres: Vec<[f32; 4]> = atoms.iter().map(|a| [a.coor.x as f32, a.coor.y as f32, a.coor.z as f32, a.radius as f32]).collect();
+		let (mut ranges, mut flat) = (Vec::with_capacity(atoms.len()), Vec::new());
+		for i in 0..atoms.len() {
+			let row = neighbors.row(i);
+			ranges.push([flat.len() as u32, row.len() as u32]);
+			flat.extend(row.iter().map(|&j| j as u32));
+		}
+		self.check_cancelled()?;
+		let Some(kept) = gpu::contact_flags(&circles, &spheres, &ranges, &flat) else {
+			// The fallback samples the same arcs again
+			self.capped_arcs.store(capped, AtomicOrdering::Relaxed);
+			return Ok(false);
+		};
+		let mut dots: [Vec<Dot>; 2] = Default::default();
+		let mut kept = kept.into_iter();
+		for l in &layouts {
+			let atom = &atoms[l.atom];
+			let rp = rps[atom.molecule];
+			let scale = (atom.radius + rp) / atom.radius;
+			for (k, keep) in (0..l.count).zip(&mut kept) {
+				if !keep { continue; }
+				let angle = (k as ScValue + 0.5) * l.delta;
+				let point = l.center + l.x*(l.rad * angle.cos()) + l.y*(l.rad * angle.sin());
+				let pcen = atom.coor + ((point - atom.coor) * scale);
+				let outnml = if rp <= 0.0 { point - atom.coor } else { (pcen - point) / rp };
+				dots[atom.molecule].push(Dot { coor: point, outnml, area: l.area, buried: false, kind: DotKind::Contact, atom_index: l.atom });
+			}
+		}
+		for (mol, mut dots) in dots.into_iter().enumerate() {
+			self.run.results.dots.convex += dots.len();
+			self.emit_dots(mol, &mut dots);
+		}
+		self.settings.report_progress(Phase::Contact, 1, 1);
 		Ok(true)
 	}
 
-	fn build_probes(&mut self, atom_index: usize, atoms_ptrs: &[*const Atom]) -> Result<(), SurfaceCalculatorError> {
-		let expanded_radius_i;
-		let neighbor_indices: Vec<usize>;
-		{
-			let atom1 = &self.run.atoms[atom_index];
-			expanded_radius_i = atom1.radius + self.settings.rp;
-			neighbor_indices = atom1.neighbor_indices.clone();
-		}
-		for &j in &neighbor_indices {
-			let atom2 = unsafe { &*atoms_ptrs[j] };
+	#[cfg(not(feature = "gpu"))]
+	fn generate_contact_surface_gpu(&mut self, _neighbors: &Csr, _buried_by: &Csr) -> Result<bool, SurfaceCalculatorError> { Ok(false) }
+
+	fn build_probes(&mut self, atom_index: usize, neighbors: &Csr, build_triplets: bool, accessible: &mut [bool], scratch: &mut Scratch) -> Result<(), SurfaceCalculatorError> {
+		let rp = self.settings.probe_radius(self.run.atoms[atom_index].molecule);
+		let expanded_radius_i = self.run.atoms[atom_index].radius + rp;
+		let neighbor_indices = neighbors.row(atom_index);
+		for &j in neighbor_indices {
+			let atom2 = &self.run.atoms[j];
 			if atom2.natom <= self.run.atoms[atom_index].natom { continue; }
-			let expanded_radius_j = atom2.radius + self.settings.rp;
+			let expanded_radius_j = atom2.radius + rp;
 			let dist_ij = self.run.atoms[atom_index].coor.distance(atom2.coor);
 			let unit_axis = (atom2.coor - self.run.atoms[atom_index].coor) / dist_ij;
 			let asymmetry_term = (expanded_radius_i*expanded_radius_i - expanded_radius_j*expanded_radius_j) / dist_ij;
@@ -409,30 +1285,33 @@ impl SurfaceGenerator {
 			if contain_term <= 0.0 { continue; }
 			contain_term = contain_term.sqrt();
 			let ring_radius = 0.5 * far_term * contain_term / dist_ij;
+			let atom2_far = matches!(atom2.attention, Attention::Far);
 			if neighbor_indices.len() <= 1 {
-				self.run.atoms[atom_index].accessible = true;
-				self.run.atoms[j].accessible = true;
+				accessible[atom_index] = true;
+				accessible[j] = true;
 				break;
 			}
-			self.build_probe_triplets(atom_index, atoms_ptrs[j], unit_axis, midplane_center, ring_radius)?;
+			if build_triplets && self.build_probe_triplets(atom_index, j, unit_axis, midplane_center, ring_radius, neighbor_indices) { accessible[atom_index] = true; }
 			let has_point_cusp = asymmetry_term.abs() < dist_ij;
-			if !matches!(self.run.atoms[atom_index].attention, Attention::Far) || (!matches!(atom2.attention, Attention::Far) && self.settings.rp > 0.0) {
-				self.emit_reentrant_surface(atom_index, atoms_ptrs[j], unit_axis, midplane_center, ring_radius, has_point_cusp)?;
+			if !matches!(self.run.atoms[atom_index].attention, Attention::Far) || (!atom2_far && rp > 0.0) {
+				self.emit_reentrant_surface(atom_index, j, unit_axis, midplane_center, ring_radius, has_point_cusp, neighbor_indices, accessible, scratch)?;
 			}
 		}
 		Ok(())
 	}
 
-	fn build_probe_triplets(&mut self, atom1_index: usize, atom2_ptr: *const Atom, unit_axis: Vec3, midplane_center: Vec3, ring_radius: ScValue) -> Result<(), SurfaceCalculatorError> {
-		let neighbor_indices = self.run.atoms[atom1_index].neighbor_indices.clone();
-		let expanded_radius_i = self.run.atoms[atom1_index].radius + self.settings.rp;
-		let atom2 = unsafe { &*atom2_ptr };
-		let expanded_radius_j = atom2.radius + self.settings.rp;
+	/// Pushes the probes resting on atoms `atom1_index`, `atom2_index` and a third neighbor; returns
+	/// whether atom 1 is accessible through them
+	fn build_probe_triplets(&mut self, atom1_index: usize, atom2_index: usize, unit_axis: Vec3, midplane_center: Vec3, ring_radius: ScValue, neighbor_indices: &[usize]) -> bool {
+		let rp = self.settings.probe_radius(self.run.atoms[atom1_index].molecule);
+		let expanded_radius_i = self.run.atoms[atom1_index].radius + rp;
+		let atom2 = &self.run.atoms[atom2_index];
+		let expanded_radius_j = atom2.radius + rp;
 		let mut made_probe = false;
-		for &k in &neighbor_indices {
+		for &k in neighbor_indices {
 			let atom3 = &self.run.atoms[k];
 			if atom3.natom <= atom2.natom { continue; }
-			let expanded_radius_k = atom3.radius + self.settings.rp;
+			let expanded_radius_k = atom3.radius + rp;
 			let dist_jk = atom2.coor.distance(atom3.coor);
 			if dist_jk >= expanded_radius_j + expanded_radius_k { continue; }
 			let dist_ik = self.run.atoms[atom1_index].coor.distance(atom3.coor);
@@ -441,7 +1320,7 @@ impl SurfaceGenerator {
 			let unit_axis_ik = (atom3.coor - self.run.atoms[atom1_index].coor) / dist_ik;
 			let wedge_angle = unit_axis.dot(unit_axis_ik).acos();
 			let sin_wedge = wedge_angle.sin();
-			if sin_wedge <= 0.0 { let dtijk2 = midplane_center.distance(atom3.coor); let rkp2 = expanded_radius_k*expanded_radius_k - ring_radius*ring_radius; if dtijk2 < rkp2 { return Ok(()); } continue; }
+			if sin_wedge <= 0.0 { let dtijk2 = midplane_center.distance(atom3.coor); let rkp2 = expanded_radius_k*expanded_radius_k - ring_radius*ring_radius; if dtijk2 < rkp2 { return false; } continue; }
 			let axis_normal = unit_axis.cross(unit_axis_ik) / sin_wedge;
 			let perp_tangent = axis_normal.cross(unit_axis);
 			let asymmetry_term_ik = (expanded_radius_i*expanded_radius_i - expanded_radius_k*expanded_radius_k) / dist_ik;
@@ -456,55 +1335,56 @@ impl SurfaceGenerator {
 			for is0 in 1..=2 {
 				let sign_choice = 3 - 2*is0;
 				let probe_center = torus_center + axis_normal * (height * (sign_choice as f64));
-				if self.check_atom_collision2_idx(probe_center, atom2, atom3, &neighbor_indices) { continue; }
+				if self.check_atom_collision2_idx(probe_center, atom2, atom3, neighbor_indices) { continue; }
 				let mut probe = Probe { atom_indices: [0; 3], height, point: probe_center, alt: axis_normal * (sign_choice as f64) };
-				if sign_choice > 0 { probe.atom_indices = [atom1_index, atom2.natom as usize - 1, k]; }
-				else { probe.atom_indices = [atom2.natom as usize - 1, atom1_index, k]; }
+				if sign_choice > 0 { probe.atom_indices = [atom1_index, atom2_index, k]; }
+				else { probe.atom_indices = [atom2_index, atom1_index, k]; }
 				self.run.probes.push(probe);
 				made_probe = true;
 			}
 		}
-		if made_probe { self.run.atoms[atom1_index].accessible = true; }
-		Ok(())
+		made_probe
 	}
 
-	fn emit_reentrant_surface(&mut self, atom1_index: usize, atom2_ptr: *const Atom, unit_axis: Vec3, midplane_center: Vec3, ring_radius: ScValue, has_point_cusp: bool) -> Result<(), SurfaceCalculatorError> {
-		let neighbors = self.run.atoms[atom1_index].neighbor_indices.clone();
-		let density = (self.run.atoms[atom1_index].density + unsafe { &*atom2_ptr }.density) / 2.0;
-		let expanded_radius_i = self.run.atoms[atom1_index].radius + self.settings.rp;
-		let expanded_radius_j = unsafe { &*atom2_ptr }.radius + self.settings.rp;
+	#[allow(clippy::too_many_arguments)]
+	fn emit_reentrant_surface(&mut self, atom1_index: usize, atom2_index: usize, unit_axis: Vec3, midplane_center: Vec3, ring_radius: ScValue, has_point_cusp: bool, neighbors: &[usize], accessible: &mut [bool], scratch: &mut Scratch) -> Result<(), SurfaceCalculatorError> {
+		let rp = self.settings.probe_radius(self.run.atoms[atom1_index].molecule);
+		let (atom2_coor, atom2_radius, atom2_attention) = { let a = &self.run.atoms[atom2_index]; (a.coor, a.radius, a.attention) };
+		let density = (self.run.atoms[atom1_index].density + self.run.atoms[atom2_index].density) / 2.0;
+		let expanded_radius_i = self.run.atoms[atom1_index].radius + rp;
+		let expanded_radius_j = atom2_radius + rp;
 		let roll_circle_radius_i = ring_radius * self.run.atoms[atom1_index].radius / expanded_radius_i;
-		let roll_circle_radius_j = ring_radius * unsafe { &*atom2_ptr }.radius / expanded_radius_j;
-		let mut belt_radius = ring_radius - self.settings.rp; if belt_radius <= 0.0 { belt_radius = 0.0; }
+		let roll_circle_radius_j = ring_radius * atom2_radius / expanded_radius_j;
+		let mut belt_radius = ring_radius - rp; if belt_radius <= 0.0 { belt_radius = 0.0; }
 		let mean_radius = (roll_circle_radius_i + 2.0*belt_radius + roll_circle_radius_j) / 4.0;
 		let eccentricity = mean_radius / ring_radius;
 		let effective_density = eccentricity*eccentricity*density;
-		let mut subs: Vec<Vec3> = Vec::new();
-		let ts = self.sample_circle(midplane_center, ring_radius, unit_axis, effective_density, &mut subs)?;
+		let Scratch { subs, points, .. } = scratch;
+		let ts = self.sample_circle(midplane_center, ring_radius, unit_axis, effective_density, subs);
 		if subs.is_empty() { return Ok(()) }
-		for sub in subs.into_iter() {
+		for &sub in subs.iter() {
 			let mut tooclose = false;
-			for &ni in &neighbors {
+			for &ni in neighbors {
 				let neighbor = &self.run.atoms[ni];
-				if neighbor.natom == unsafe { &*atom2_ptr }.natom { continue; }
-				let expanded_neighbor_radius = neighbor.radius + self.settings.rp;
+				if ni == atom2_index { continue; }
+				let expanded_neighbor_radius = neighbor.radius + rp;
 				let d2 = sub.distance_squared(neighbor.coor);
 				if d2 < expanded_neighbor_radius*expanded_neighbor_radius { tooclose = true; break; }
 			}
 			if tooclose { continue; }
 			let ring_point = sub;
-			self.run.atoms[atom1_index].accessible = true;
-			unsafe { (*(atom2_ptr as *mut Atom)).accessible = true; }
+			accessible[atom1_index] = true;
+			accessible[atom2_index] = true;
 			let vec_pi = (self.run.atoms[atom1_index].coor - ring_point) / expanded_radius_i;
-			let vec_pj = (unsafe { &*atom2_ptr }.coor - ring_point) / expanded_radius_j;
+			let vec_pj = (atom2_coor - ring_point) / expanded_radius_j;
 			let mut toroid_axis = vec_pi.cross(vec_pj); toroid_axis.normalize();
-			let mut cusp_term = self.settings.rp*self.settings.rp - ring_radius*ring_radius;
+			let mut cusp_term = rp*rp - ring_radius*ring_radius;
 			let has_cusp_point = cusp_term > 0.0 && has_point_cusp;
 			let (arc_end_i, arc_end_j) = if has_cusp_point {
 				cusp_term = cusp_term.sqrt();
 				let qij = midplane_center - unit_axis * cusp_term;
 				let _qjk = midplane_center + unit_axis * cusp_term;
-				(((qij - ring_point)/self.settings.rp), Vec3::zero())
+				(((qij - ring_point)/rp), Vec3::zero())
 			} else {
 				let mut pq = vec_pi + vec_pj; pq.normalize();
 				(pq, pq)
@@ -514,74 +1394,39 @@ impl SurfaceGenerator {
 			dot_tmp = arc_end_j.dot(vec_pj);
 			if dot_tmp >= 1.0 || dot_tmp <= -1.0 { return Ok(()) ; }
 			if !matches!(self.run.atoms[atom1_index].attention, Attention::Far) {
-				let mut points: Vec<Vec3> = Vec::new();
-				let ps = self.sample_arc(ring_point, self.settings.rp, toroid_axis, density, vec_pi, arc_end_i, &mut points)?;
+				let ps = self.sample_arc(ring_point, rp, toroid_axis, density, vec_pi, arc_end_i, points);
 				for &point in points.iter() { let area = ps * ts * self.distance_point_to_line(midplane_center, unit_axis, point) / ring_radius; self.run.results.dots.toroidal += 1; let molecule = self.run.atoms[atom1_index].molecule; self.add_dot(molecule, DotKind::Reentrant, point, area, ring_point, atom1_index); }
 			}
-			let atom2_attention = unsafe { (*atom2_ptr).attention };
 			if !matches!(atom2_attention, Attention::Far) {
-				let mut points: Vec<Vec3> = Vec::new();
-				let ps = self.sample_arc(ring_point, self.settings.rp, toroid_axis, density, arc_end_j, vec_pj, &mut points)?;
-				let atom2_index = unsafe { &*atom2_ptr }.natom as usize - 1;
+				let ps = self.sample_arc(ring_point, rp, toroid_axis, density, arc_end_j, vec_pj, points);
 				for &point in points.iter() { let area = ps * ts * self.distance_point_to_line(midplane_center, unit_axis, point) / ring_radius; self.run.results.dots.toroidal += 1; let molecule2 = self.run.atoms[atom2_index].molecule; self.add_dot(molecule2, DotKind::Reentrant, point, area, ring_point, atom2_index); }
 			}
 		}
 		Ok(())
 	}
 
-	fn emit_contact_surface_for_atom(&mut self, atom_index: usize) -> Result<(), SurfaceCalculatorError> {
-		let neighbors = self.run.atoms[atom_index].neighbor_indices.clone();
-		let mut north_dir = Vec3::new(0.0, 0.0, 1.0);
-		let mut south_dir = Vec3::new(0.0, 0.0, -1.0);
-		let mut equatorial_vector = Vec3::new(1.0, 0.0, 0.0);
+	fn emit_contact_surface_for_atom(&mut self, atom_index: usize, neighbors: &Csr, scratch: &mut Scratch) -> Result<(), SurfaceCalculatorError> {
+		let rp = self.settings.probe_radius(self.run.atoms[atom_index].molecule);
+		let neighbors = neighbors.row(atom_index);
 		let radius_i = self.run.atoms[atom_index].radius;
-		let expanded_radius_i = self.run.atoms[atom_index].radius + self.settings.rp;
-		if !neighbors.is_empty() {
-			let neighbor = &self.run.atoms[neighbors[0]];
-			north_dir = self.run.atoms[atom_index].coor - neighbor.coor;
-			north_dir.normalize();
-			let mut temp_vec = Vec3::new(north_dir.y*north_dir.y + north_dir.z*north_dir.z, north_dir.x*north_dir.x + north_dir.z*north_dir.z, north_dir.x*north_dir.x + north_dir.y*north_dir.y);
-			temp_vec.normalize();
-			let dt = temp_vec.dot(north_dir);
-			if dt.abs() > 0.99 { temp_vec = Vec3::new(1.0, 0.0, 0.0); }
-			equatorial_vector = north_dir.cross(temp_vec);
-			equatorial_vector.normalize();
-			let _ = equatorial_vector.cross(north_dir);
-			let radius_neighbor = neighbor.radius;
-			let expanded_radius_j = neighbor.radius + self.settings.rp;
-			let dij = self.run.atoms[atom_index].coor.distance(neighbor.coor);
-			let unit_axis = (neighbor.coor - self.run.atoms[atom_index].coor) / dij;
-			let asymmetry_term = (expanded_radius_i*expanded_radius_i - expanded_radius_j*expanded_radius_j) / dij;
-			let midplane_center = (self.run.atoms[atom_index].coor + neighbor.coor) * 0.5 + (unit_axis * (asymmetry_term*0.5));
-			let mut far_term = (expanded_radius_i + expanded_radius_j)*(expanded_radius_i + expanded_radius_j) - dij*dij;
-			if far_term <= 0.0 { return Err(SurfaceCalculatorError::ImagFar(self.run.atoms[atom_index].natom, neighbor.natom)); }
-			far_term = far_term.sqrt();
-			let mut contain_term = dij*dij - (radius_i - radius_neighbor).powi(2);
-			if contain_term <= 0.0 { return Err(SurfaceCalculatorError::ImagContain(self.run.atoms[atom_index].natom, neighbor.natom)); }
-			contain_term = contain_term.sqrt();
-			let ring_radius = 0.5 * far_term * contain_term / dij;
-			let ring_point = midplane_center + (equatorial_vector.cross(north_dir) * ring_radius);
-			south_dir = (ring_point - self.run.atoms[atom_index].coor) / expanded_radius_i;
-			if north_dir.cross(south_dir).dot(equatorial_vector) <= 0.0 { return Err(SurfaceCalculatorError::NonPositiveFrame(self.run.atoms[atom_index].natom, neighbor.natom)); }
-		}
-		let mut lats: Vec<Vec3> = Vec::new();
+		let expanded_radius_i = self.run.atoms[atom_index].radius + rp;
+		let (north_dir, south_dir, equatorial_vector) = contact_frame(&self.run.atoms[atom_index], neighbors.first().map(|&j| &self.run.atoms[j]), rp)?;
+		let Scratch { lats, points, .. } = scratch;
 		let o = Vec3::zero();
-		let cs = self.sample_arc(o, radius_i, equatorial_vector, self.run.atoms[atom_index].density, north_dir, south_dir, &mut lats)?;
+		let cs = self.sample_arc(o, radius_i, equatorial_vector, self.run.atoms[atom_index].density, north_dir, south_dir, lats);
 		if lats.is_empty() { return Ok(()); }
-		let mut points: Vec<Vec3> = Vec::new();
 		for ilat in lats.iter() {
 			let dt = ilat.dot(north_dir);
 			let cen = self.run.atoms[atom_index].coor + (north_dir * dt);
 			let mut rad = radius_i*radius_i - dt*dt;
 			if rad <= 0.0 { continue; }
 			rad = rad.sqrt();
-			points.clear();
-			let ps = self.sample_circle(cen, rad, north_dir, self.run.atoms[atom_index].density, &mut points)?;
+			let ps = self.sample_circle(cen, rad, north_dir, self.run.atoms[atom_index].density, points);
 			if points.is_empty() { continue; }
 			let area = ps * cs;
 			for &point in points.iter() {
 				let pcen = self.run.atoms[atom_index].coor + ((point - self.run.atoms[atom_index].coor) * (expanded_radius_i/radius_i));
-				if self.check_point_collision(pcen, &neighbors) { continue; }
+				if self.check_point_collision(pcen, neighbors, rp) { continue; }
 				self.run.results.dots.convex += 1;
 				let molecule = self.run.atoms[atom_index].molecule;
 				self.add_dot(molecule, DotKind::Contact, point, area, pcen, atom_index);
@@ -590,25 +1435,32 @@ impl SurfaceGenerator {
 		Ok(())
 	}
 
-	fn check_atom_collision2_idx(&self, probe_center: Vec3, atom1: &Atom, atom2: &Atom, neighbor_indices: &Vec<usize>) -> bool {
+	fn check_atom_collision2_idx(&self, probe_center: Vec3, atom1: &Atom, atom2: &Atom, neighbor_indices: &[usize]) -> bool {
+		let rp = self.settings.probe_radius(atom1.molecule);
 		for &ni in neighbor_indices {
 			let neighbor = &self.run.atoms[ni];
 			if neighbor.natom == atom1.natom || neighbor.natom == atom2.natom { continue; }
-			if probe_center.distance_squared(neighbor.coor) <= (neighbor.radius + self.settings.rp).powi(2) { return true; }
+			if probe_center.distance_squared(neighbor.coor) <= (neighbor.radius + rp).powi(2) { return true; }
 		}
 		false
 	}
 
+	#[allow(clippy::needless_range_loop)]
 	fn generate_concave_surface(&mut self) -> Result<(), SurfaceCalculatorError> {
 		let mut lowprobs: Vec<usize> = Vec::new();
-		for (idx, probe) in self.run.probes.iter().enumerate() { if probe.height < self.settings.rp { lowprobs.push(idx); } }
-		for i in 0..self.run.probes.len() {
+		for (idx, probe) in self.run.probes.iter().enumerate() { if probe.height < self.settings.probe_radius(self.run.atoms[probe.atom_indices[0]].molecule) { lowprobs.push(idx); } }
+		let Scratch { lats, points, nears, .. } = &mut Scratch::default();
+		let n = self.run.probes.len();
+		for i in 0..n {
+			self.check_cancelled()?;
+			self.settings.report_progress(Phase::Concave, i, n);
 			let probe = &self.run.probes[i];
 			let aidx = probe.atom_indices;
+			let rp = self.settings.probe_radius(self.run.atoms[aidx[0]].molecule);
 			if matches!(self.run.atoms[aidx[0]].attention, Attention::Consider) && matches!(self.run.atoms[aidx[1]].attention, Attention::Consider) && matches!(self.run.atoms[aidx[2]].attention, Attention::Consider) { continue; }
 			let pijk = probe.point; let uijk = probe.alt; let hijk = probe.height; let density = (self.run.atoms[aidx[0]].density + self.run.atoms[aidx[1]].density + self.run.atoms[aidx[2]].density) / 3.0;
-			let mut nears: Vec<usize> = Vec::new();
-			for &lp in &lowprobs { if lp == i { continue; } let d2 = pijk.distance_squared(self.run.probes[lp].point); if d2 <= 4.0 * self.settings.rp*self.settings.rp { nears.push(lp); } }
+			nears.clear();
+			for &lp in &lowprobs { if lp == i { continue; } let d2 = pijk.distance_squared(self.run.probes[lp].point); if d2 <= 4.0 * rp*rp { nears.push(lp); } }
 			let mut vp = [Vec3::zero();3];
 			for k in 0..3 { vp[k] = self.run.atoms[aidx[k]].coor - pijk; vp[k].normalize(); }
 			let mut vectors = [Vec3::zero();3];
@@ -618,42 +1470,60 @@ impl SurfaceGenerator {
 			let mut dm = -1.0; let mut mm = 0usize;
 			for k in 0..3 { let dt = uijk.dot(vp[k]); if dt > dm { dm = dt; mm = k; } }
 			let south_dir = uijk * -1.0; let mut arc_axis = vp[mm].cross(south_dir); arc_axis.normalize();
-			let mut lats: Vec<Vec3> = Vec::new(); let o = Vec3::zero();
-			let cs = self.sample_arc(o, self.settings.rp, arc_axis, density, vp[mm], south_dir, &mut lats)?; if lats.is_empty() { continue; }
-			let mut points: Vec<Vec3> = Vec::new();
+			let o = Vec3::zero();
+			let cs = self.sample_arc(o, rp, arc_axis, density, vp[mm], south_dir, lats); if lats.is_empty() { continue; }
 			for ilat in lats.iter() {
-				let dt = ilat.dot(south_dir); let cen = south_dir * dt; let mut rad = self.settings.rp*self.settings.rp - dt*dt; if rad <= 0.0 { continue; } rad = rad.sqrt();
-				points.clear(); let ps = self.sample_circle(cen, rad, south_dir, density, &mut points)?; if points.is_empty() { continue; }
+				let dt = ilat.dot(south_dir); let cen = south_dir * dt; let mut rad = rp*rp - dt*dt; if rad <= 0.0 { continue; } rad = rad.sqrt();
+				let ps = self.sample_circle(cen, rad, south_dir, density, points); if points.is_empty() { continue; }
 				let area = ps * cs;
 				for &point in points.iter() {
 					let mut bail = false; for v in vectors.iter() { let dt2 = point.dot(*v); if dt2 >= 0.0 { bail = true; break; } } if bail { continue; }
 					let point = point + pijk;
-					if (hijk < self.settings.rp && !nears.is_empty()) && self.check_probe_collision_idx(point, &nears, self.settings.rp*self.settings.rp) { continue; }
-					let mut mc = 0usize; let mut dmin = 2.0 * self.settings.rp; for kk in 0..3 { let d = point.distance(self.run.atoms[aidx[kk]].coor) - self.run.atoms[aidx[kk]].radius; if d < dmin { dmin = d; mc = kk; } }
-					let atom_index = aidx[mc]; let molecule = self.run.atoms[atom_index].molecule; self.run.results.dots.concave += 1; self.add_dot(molecule, DotKind::Cavity, point, area, pijk, atom_index);
+					if (hijk < rp && !nears.is_empty()) && self.check_probe_collision_idx(point, nears, rp*rp) { continue; }
+					let mut mc = 0usize; let mut dmin = 2.0 * rp; for kk in 0..3 { let d = point.distance(self.run.atoms[aidx[kk]].coor) - self.run.atoms[aidx[kk]].radius; if d < dmin { dmin = d; mc = kk; } }
+					let atom_index = aidx[mc]; if self.run.atoms[atom_index].context { continue; } let molecule = self.run.atoms[atom_index].molecule; self.run.results.dots.concave += 1; self.add_dot(molecule, DotKind::Cavity, point, area, pijk, atom_index);
 				}
 			}
 		}
+		self.settings.report_progress(Phase::Concave, n, n);
 		Ok(())
 	}
 
+	#[allow(clippy::needless_range_loop, clippy::type_complexity)]
 	fn generate_concave_surface_parallel(&mut self) -> Result<(), SurfaceCalculatorError> {
-		let rp = self.settings.rp;
-		let rp2 = rp*rp;
+		let limit = self.arc_limit();
+		let rps = [self.settings.probe_radius(0), self.settings.probe_radius(1)];
 		let atoms: &Vec<Atom> = &self.run.atoms;
+		let burial = &self.run.burial;
 		let probes: &Vec<Probe> = &self.run.probes;
 		if probes.is_empty() { return Ok(()); }
 		let mut lowprobs: Vec<usize> = Vec::new();
-		for (idx, probe) in probes.iter().enumerate() { if probe.height < rp { lowprobs.push(idx); } }
-		let results: Vec<(Vec<Dot>, Vec<Dot>, usize)> = (0..probes.len()).into_par_iter().filter_map(|i| {
+		for (idx, probe) in probes.iter().enumerate() { if probe.height < rps[atoms[probe.atom_indices[0]].molecule] { lowprobs.push(idx); } }
+		let stream = &self.run.stream;
+		let settings = &self.settings;
+		// Probes sample a patch of their sphere; low probes also test each point against nearby low probes
+		let order = schedule_by_cost(probes.iter().map(|p| {
+			let aidx = p.atom_indices;
+			let rp = rps[atoms[aidx[0]].molecule];
+			let density = (atoms[aidx[0]].density + atoms[aidx[1]].density + atoms[aidx[2]].density) / 3.0;
+			density * rp * rp * if p.height < rp { 2.0 } else { 1.0 }
+		}));
+		let done = AtomicUsize::new(0);
+		settings.report_progress(Phase::Concave, 0, order.len());
+		let mut results: Vec<(usize, Vec<Dot>, Vec<Dot>, [usize; 2])> = order.par_iter().with_max_len(1).map_init(Scratch::default, |scratch, &i| {
+			if settings.cancelled() { return None; }
+			settings.report_progress(Phase::Concave, done.fetch_add(1, AtomicOrdering::Relaxed) + 1, order.len());
 			let probe = &probes[i];
 			let aidx = probe.atom_indices;
+			let rp = rps[atoms[aidx[0]].molecule];
+			let rp2 = rp*rp;
 			// skip if all 3 atoms are Consider
 			if matches!(atoms[aidx[0]].attention, Attention::Consider) && matches!(atoms[aidx[1]].attention, Attention::Consider) && matches!(atoms[aidx[2]].attention, Attention::Consider) { return None; }
 			let pijk = probe.point; let uijk = probe.alt; let hijk = probe.height;
 			let density = (atoms[aidx[0]].density + atoms[aidx[1]].density + atoms[aidx[2]].density) / 3.0;
+			let Scratch { lats, points, nears, .. } = scratch;
 			// build nears
-			let mut nears: Vec<usize> = Vec::new();
+			nears.clear();
 			for &lp in &lowprobs { if lp == i { continue; } let d2 = pijk.distance_squared(probes[lp].point); if d2 <= 4.0 * rp2 { nears.push(lp); } }
 			let mut vp = [Vec3::zero();3];
 			for k in 0..3 { vp[k] = atoms[aidx[k]].coor - pijk; vp[k].normalize(); }
@@ -664,152 +1534,191 @@ impl SurfaceGenerator {
 			let mut dm = -1.0; let mut mm = 0usize;
 			for k in 0..3 { let dt = uijk.dot(vp[k]); if dt > dm { dm = dt; mm = k; } }
 			let south_dir = uijk * -1.0; let mut arc_axis = vp[mm].cross(south_dir); arc_axis.normalize();
-			let mut lats: Vec<Vec3> = Vec::new(); let o = Vec3::zero();
-			let cs = geom_sample_arc(o, rp, arc_axis, density, vp[mm], south_dir, &mut lats).ok()?; if lats.is_empty() { return None; }
+			let o = Vec3::zero();
+			let cs = geom_sample_arc(limit, o, rp, arc_axis, density, vp[mm], south_dir, lats); if lats.is_empty() { return None; }
 			let mut d0: Vec<Dot> = Vec::new();
 			let mut d1: Vec<Dot> = Vec::new();
-			let mut points: Vec<Vec3> = Vec::new();
+			let mut dropped = [0, 0];
 			for ilat in lats.iter() {
 				let dt = ilat.dot(south_dir); let cen = south_dir * dt; let mut rad = rp2 - dt*dt; if rad <= 0.0 { continue; } rad = rad.sqrt();
-				points.clear(); let ps = geom_sample_circle(cen, rad, south_dir, density, &mut points).ok()?; if points.is_empty() { continue; }
+				let ps = geom_sample_circle(limit, cen, rad, south_dir, density, points); if points.is_empty() { continue; }
 				let area = ps * cs;
 				for &point in points.iter() {
 					let mut bail = false; for v in vectors.iter() { let dt2 = point.dot(*v); if dt2 >= 0.0 { bail = true; break; } } if bail { continue; }
 					let point = point + pijk;
 					if hijk < rp && !nears.is_empty() {
-						let mut coll = false; for &np in &nears { let p = &probes[np]; if point.distance_squared(p.point) < rp2 { coll = true; break; } }
+						let mut coll = false; for &np in nears.iter() { let p = &probes[np]; if point.distance_squared(p.point) < rp2 { coll = true; break; } }
 						if coll { continue; }
 					}
 					let mut mc = 0usize; let mut dmin = 2.0 * rp; for kk in 0..3 { let d = point.distance(atoms[aidx[kk]].coor) - atoms[aidx[kk]].radius; if d < dmin { dmin = d; mc = kk; } }
 					let atom_index = aidx[mc];
+					// Cavity dots nearest a context atom lie on its surface, which is not scored
+					if atoms[atom_index].context { continue; }
 					let molecule = atoms[atom_index].molecule;
 					let pcen = pijk;
 					let outnml = if rp <= 0.0 { point - atoms[atom_index].coor } else { (pcen - point) / rp };
 					let other_mol = if molecule == 0 { 1 } else { 0 };
-					let mut buried = false;
-					for b in atoms.iter() {
-						if b.molecule != other_mol { continue; }
-						let erl = b.radius + rp;
-						let d = pcen.distance_squared(b.coor);
-						if d <= erl*erl { buried = true; break; }
-					}
+					let buried = burial.is_buried_by(other_mol, pcen, rp);
 					let dot = Dot { coor: point, outnml, area, buried, kind: DotKind::Cavity, atom_index };
-					if molecule == 0 { d0.push(dot); } else { d1.push(dot); }
+					if stream.as_ref().is_some_and(|s| !s.keeps(molecule, &dot)) { dropped[molecule] += 1; }
+					else if molecule == 0 { d0.push(dot); } else { d1.push(dot); }
 				}
 			}
-			let n = d0.len() + d1.len();
-			if n == 0 { None } else { Some((d0, d1, n)) }
-		}).collect();
-		for (mut d0, mut d1, n) in results.into_iter() {
+			if d0.is_empty() && d1.is_empty() && dropped == [0, 0] { None } else { Some((i, d0, d1, dropped)) }
+		}).flatten().collect();
+		self.check_cancelled()?;
+		results.sort_unstable_by_key(|r| r.0);
+		for (_, mut d0, mut d1, dropped) in results.into_iter() {
+			let n = d0.len() + d1.len() + dropped[0] + dropped[1];
+			self.run.dropped[0] += dropped[0];
+			self.run.dropped[1] += dropped[1];
 			self.run.results.dots.concave += n;
-			self.run.dots[0].append(&mut d0);
-			self.run.dots[1].append(&mut d1);
+			self.emit_dots(0, &mut d0);
+			self.emit_dots(1, &mut d1);
 		}
 		Ok(())
 	}
-	fn check_probe_collision_idx(&self, point: Vec3, nears: &Vec<usize>, r2: ScValue) -> bool {
+	fn check_probe_collision_idx(&self, point: Vec3, nears: &[usize], r2: ScValue) -> bool {
 		for &np in nears { let p = &self.run.probes[np]; if point.distance_squared(p.point) < r2 { return true; } }
 		false
 	}
 
 	fn add_dot(&mut self, molecule: usize, kind: DotKind, coor: Vec3, area: ScValue, pcen: Vec3, atom_index: usize) {
+		let rp = self.settings.probe_radius(molecule);
 		let atom = &self.run.atoms[atom_index];
-		let outnml = if self.settings.rp <= 0.0 { coor - atom.coor } else { (pcen - coor) / self.settings.rp };
-		let mut buried = false;
-		// Robust burial: check against all atoms in the opposite molecule
+		let outnml = if rp <= 0.0 { coor - atom.coor } else { (pcen - coor) / rp };
+		// Robust burial: check against every nearby atom of the opposite molecule
 		let other_mol = if molecule == 0 { 1 } else { 0 };
-		for b in self.run.atoms.iter() {
-			if b.molecule != other_mol { continue; }
-			let erl = b.radius + self.settings.rp;
-			let d = pcen.distance_squared(b.coor);
-			if d <= erl*erl { buried = true; break; }
-		}
+		let buried = self.run.burial.is_buried_by(other_mol, pcen, rp);
 		let dot = Dot { coor, outnml, area, buried, kind, atom_index };
-		self.run.dots[molecule].push(dot);
+		if self.run.stream.as_ref().is_some_and(|s| !s.keeps(molecule, &dot)) { self.run.dropped[molecule] += 1; return; }
+		self.emit_dot(molecule, dot);
 	}
 
 	fn distance_point_to_line(&self, cen: Vec3, axis: Vec3, pnt: Vec3) -> ScValue { let vec = pnt - cen; let dt = vec.dot(axis); let mut d2 = vec.magnitude_squared() - dt*dt; if d2 < 0.0 { d2 = 0.0; } d2.sqrt() }
 
-	fn sample_arc(&self, cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, x: Vec3, v: Vec3, points: &mut Vec<Vec3>) -> Result<ScValue, SurfaceCalculatorError> {
-		let y = axis.cross(x);
-		let dt1 = v.dot(x);
-		let dt2 = v.dot(y);
-		let mut angle = dt2.atan2(dt1);
-		if angle < 0.0 { angle += 2.0 * PI; }
-		self.sample_arc_segment(cen, rad, x, y, angle, density, points)
-	}
-
-	fn sample_circle(&self, cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, points: &mut Vec<Vec3>) -> Result<ScValue, SurfaceCalculatorError> {
-		let mut v1 = Vec3::new(axis.y*axis.y + axis.z*axis.z, axis.x*axis.x + axis.z*axis.z, axis.x*axis.x + axis.y*axis.y);
-		v1.normalize();
-		let dt = v1.dot(axis);
-		if dt.abs() > 0.99 { v1 = Vec3::new(1.0, 0.0, 0.0); }
-		let mut v2 = axis.cross(v1); v2.normalize();
-		let mut x = axis.cross(v2); x.normalize();
-		let y = axis.cross(x);
-		self.sample_arc_segment(cen, rad, x, y, 2.0*PI, density, points)
-	}
-
-	fn sample_arc_segment(&self, cen: Vec3, rad: ScValue, x: Vec3, y: Vec3, angle: ScValue, density: ScValue, points: &mut Vec<Vec3>) -> Result<ScValue, SurfaceCalculatorError> {
-		// Match original spacing: delta = 1/(sqrt(density)*rad); sample at midpoints
-		if rad <= 0.0 { points.clear(); return Ok(0.0); }
-		let delta = 1.0 / (density.sqrt() * rad);
-		let mut a = -delta / 2.0;
-		points.clear();
-		for _ in 0..100000 {
-			a += delta;
-			if a > angle { break; }
-			let c = rad * a.cos();
-			let s = rad * a.sin();
-			points.push(cen + x*c + y*s);
-		}
-		if a + delta < angle { return Err(SurfaceCalculatorError::TooManySubdivisions); }
-		let ps = if !points.is_empty() { rad * angle / (points.len() as f64) } else { 0.0 };
-		Ok(ps)
+	#[allow(clippy::too_many_arguments)]
+	fn sample_arc(&self, cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, x: Vec3, v: Vec3, points: &mut Vec<Vec3>) -> ScValue {
+		geom_sample_arc(self.arc_limit(), cen, rad, axis, density, x, v, points)
 	}
 
+	fn sample_circle(&self, cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, points: &mut Vec<Vec3>) -> ScValue {
+		geom_sample_circle(self.arc_limit(), cen, rad, axis, density, points)
+	}
+
+	fn arc_limit(&self) -> ArcLimit<'_> { ArcLimit { max_points: self.settings.max_arc_points, capped: &self.capped_arcs } }
+
 	pub fn results(&self) -> &Results { &self.run.results }
 	pub fn dots(&self, molecule: usize) -> &Vec<Dot> { &self.run.dots[molecule] }
+	/// Atoms of both molecules in insertion order, with assigned radii
+	pub fn atoms(&self) -> &[Atom] { &self.run.atoms }
+	/// Same-molecule neighbors of atom `i` (nearest first) from the last surface generation, the atoms
+	/// close enough to shape its convex and toroidal surface; empty for Far atoms and before any run
+	pub fn neighbor_indices(&self, i: usize) -> &[usize] { self.run.neighbors.row(i) }
+	/// Opposite-molecule atoms that can bury atom `i`'s dots, from the last surface generation
+	pub fn buried_by_indices(&self, i: usize) -> &[usize] { self.run.buried_by.row(i) }
 
 	// Compatibility wrappers (legacy names → new terminology). Safe to remove once callers are updated.
-	fn check_point_collision(&self, pcen: Vec3, atoms: &Vec<usize>) -> bool {
+	fn check_point_collision(&self, pcen: Vec3, atoms: &[usize], rp: ScValue) -> bool {
 		for &idx in atoms.iter().skip(1) {
 			let a = &self.run.atoms[idx];
-			if pcen.distance(a.coor) <= (a.radius + self.settings.rp) { return true; }
+			if pcen.distance(a.coor) <= (a.radius + rp) { return true; }
 		}
 		false
 	}
 }
 
+/// Sampling frame of an atom's contact surface: the pole facing away from its nearest same-molecule
+/// `neighbor`, the direction of the circle where the neighbor's expanded sphere cuts the surface off
+/// (the opposite pole without a neighbor), and the axis of the arc between the two
+fn contact_frame(atom: &Atom, neighbor: Option<&Atom>, rp: ScValue) -> Result<(Vec3, Vec3, Vec3), SurfaceCalculatorError> {
+	let mut north_dir = Vec3::new(0.0, 0.0, 1.0);
+	let mut south_dir = Vec3::new(0.0, 0.0, -1.0);
+	let mut equatorial_vector = Vec3::new(1.0, 0.0, 0.0);
+	let Some(neighbor) = neighbor else { return Ok((north_dir, south_dir, equatorial_vector)) };
+	let radius_i = atom.radius;
+	let expanded_radius_i = atom.radius + rp;
+	north_dir = atom.coor - neighbor.coor;
+	north_dir.normalize();
+	let mut temp_vec = Vec3::new(north_dir.y*north_dir.y + north_dir.z*north_dir.z, north_dir.x*north_dir.x + north_dir.z*north_dir.z, north_dir.x*north_dir.x + north_dir.y*north_dir.y);
+	temp_vec.normalize();
+	let dt = temp_vec.dot(north_dir);
+	if dt.abs() > 0.99 { temp_vec = Vec3::new(1.0, 0.0, 0.0); }
+	equatorial_vector = north_dir.cross(temp_vec);
+	equatorial_vector.normalize();
+	let radius_neighbor = neighbor.radius;
+	let expanded_radius_j = neighbor.radius + rp;
+	let dij = atom.coor.distance(neighbor.coor);
+	let unit_axis = (neighbor.coor - atom.coor) / dij;
+	let asymmetry_term = (expanded_radius_i*expanded_radius_i - expanded_radius_j*expanded_radius_j) / dij;
+	let midplane_center = (atom.coor + neighbor.coor) * 0.5 + (unit_axis * (asymmetry_term*0.5));
+	let mut far_term = (expanded_radius_i + expanded_radius_j)*(expanded_radius_i + expanded_radius_j) - dij*dij;
+	if far_term <= 0.0 { return Err(SurfaceCalculatorError::ImagFar(atom.natom, neighbor.natom)); }
+	far_term = far_term.sqrt();
+	let mut contain_term = dij*dij - (radius_i - radius_neighbor).powi(2);
+	if contain_term <= 0.0 { return Err(SurfaceCalculatorError::ImagContain(atom.natom, neighbor.natom)); }
+	contain_term = contain_term.sqrt();
+	let ring_radius = 0.5 * far_term * contain_term / dij;
+	let ring_point = midplane_center + (equatorial_vector.cross(north_dir) * ring_radius);
+	south_dir = (ring_point - atom.coor) / expanded_radius_i;
+	if north_dir.cross(south_dir).dot(equatorial_vector) <= 0.0 { return Err(SurfaceCalculatorError::NonPositiveFrame(atom.natom, neighbor.natom)); }
+	Ok((north_dir, south_dir, equatorial_vector))
+}
+
 // Pure geometry helpers for use in parallel closures (no &self access)
-fn geom_sample_arc_segment(cen: Vec3, rad: ScValue, x: Vec3, y: Vec3, angle: ScValue, density: ScValue, points: &mut Vec<Vec3>) -> Result<ScValue, SurfaceCalculatorError> {
+
+/// Point budget per sampled arc (`Settings::max_arc_points`) and the count of arcs that hit it
+#[derive(Clone, Copy)]
+struct ArcLimit<'a> {
+	max_points: usize,
+	capped: &'a AtomicUsize,
+}
+
+/// Angular step and point count for sampling an arc of `angle` radians and radius `rad` at
+/// `density`; over the `max_points` budget the points are spread evenly over the arc instead, and
+/// the arc is counted in `limit.capped`
+fn arc_steps(limit: ArcLimit, rad: ScValue, angle: ScValue, density: ScValue) -> (ScValue, usize) {
 	// Match original spacing: delta = 1/(sqrt(density)*rad); sample at midpoints
-	if rad <= 0.0 { points.clear(); return Ok(0.0); }
+	if rad <= 0.0 { return (0.0, 0); }
 	let delta = 1.0 / (density.sqrt() * rad);
 	let mut a = -delta / 2.0;
-	points.clear();
-	for _ in 0..100000 {
+	let mut count = 0;
+	for _ in 0..limit.max_points {
 		a += delta;
 		if a > angle { break; }
-		let c = rad * a.cos();
-		let s = rad * a.sin();
-		points.push(cen + x*c + y*s);
+		count += 1;
+	}
+	if a + delta < angle {
+		limit.capped.fetch_add(1, AtomicOrdering::Relaxed);
+		return (angle / limit.max_points as ScValue, limit.max_points);
+	}
+	(delta, count)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn geom_sample_arc_segment(limit: ArcLimit, cen: Vec3, rad: ScValue, x: Vec3, y: Vec3, angle: ScValue, density: ScValue, points: &mut Vec<Vec3>) -> ScValue {
+	let (delta, count) = arc_steps(limit, rad, angle, density);
+	points.clear();
+	let mut a = -delta / 2.0;
+	for _ in 0..count {
+		a += delta;
+		points.push(cen + x*(rad * a.cos()) + y*(rad * a.sin()));
 	}
-	if a + delta < angle { return Err(SurfaceCalculatorError::TooManySubdivisions); }
-	let ps = if !points.is_empty() { rad * angle / (points.len() as f64) } else { 0.0 };
-	Ok(ps)
+	if count > 0 { rad * angle / (count as f64) } else { 0.0 }
 }
 
-fn geom_sample_arc(cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, x: Vec3, v: Vec3, points: &mut Vec<Vec3>) -> Result<ScValue, SurfaceCalculatorError> {
+#[allow(clippy::too_many_arguments)]
+fn geom_sample_arc(limit: ArcLimit, cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, x: Vec3, v: Vec3, points: &mut Vec<Vec3>) -> ScValue {
 	let y = axis.cross(x);
 	let dt1 = v.dot(x);
 	let dt2 = v.dot(y);
 	let mut angle = dt2.atan2(dt1);
 	if angle < 0.0 { angle += 2.0 * PI; }
-	geom_sample_arc_segment(cen, rad, x, y, angle, density, points)
+	geom_sample_arc_segment(limit, cen, rad, x, y, angle, density, points)
 }
 
-fn geom_sample_circle(cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, points: &mut Vec<Vec3>) -> Result<ScValue, SurfaceCalculatorError> {
+/// In-plane axes of the circles sampled around `axis`
+fn circle_axes(axis: Vec3) -> (Vec3, Vec3) {
 	let mut v1 = Vec3::new(axis.y*axis.y + axis.z*axis.z, axis.x*axis.x + axis.z*axis.z, axis.x*axis.x + axis.y*axis.y);
 	v1.normalize();
 	let dt = v1.dot(axis);
@@ -817,5 +1726,10 @@ fn geom_sample_circle(cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, poi
 	let mut v2 = axis.cross(v1); v2.normalize();
 	let mut x = axis.cross(v2); x.normalize();
 	let y = axis.cross(x);
-	geom_sample_arc_segment(cen, rad, x, y, 2.0*PI, density, points)
+	(x, y)
+}
+
+fn geom_sample_circle(limit: ArcLimit, cen: Vec3, rad: ScValue, axis: Vec3, density: ScValue, points: &mut Vec<Vec3>) -> ScValue {
+	let (x, y) = circle_axes(axis);
+	geom_sample_arc_segment(limit, cen, rad, x, y, 2.0*PI, density, points)
 }
@@ -1,7 +1,15 @@
+use super::settings::ScStatistic;
 use super::vector3::Vec3;
 
 pub type ScValue = f64;
 
+/// Scalar of the flat geometry arrays scanned by the hot distance loops: single precision with the
+/// `f32` feature (about 1e-3 Å at protein coordinates). Dot areas and statistics stay in `ScValue`.
+#[cfg(not(feature = "f32"))]
+pub type GeomValue = f64;
+#[cfg(feature = "f32")]
+pub type GeomValue = f32;
+
 /// Atom attention/visibility state (neutral names for states used in the algorithm).
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub enum Attention {
@@ -23,14 +31,38 @@ pub struct Atom {
 	/// Per-atom sampling density; chosen to achieve overall ~15 dots/Å^2 (Lawrence & Colman, 1993)
 	pub density: ScValue,
 	pub attention: Attention,
+	/// Attention forced for every `calc()` in place of the separation-cutoff classification
+	/// (see `SurfaceGenerator::override_attention`)
+	pub attention_override: Option<Attention>,
 	/// Is atom accessible to solvent/contact surface (Connolly, 1983)
 	pub accessible: bool,
 	pub atom: String,
 	pub residue: String,
+	/// Chain identifier and residue sequence number (with optional insertion code) from the input structure
+	pub chain: String,
+	pub resnum: i32,
+	pub icode: Option<char>,
+	/// Partial charge in e (e.g. from PQR input); enables electrostatic complementarity
+	pub charge: Option<ScValue>,
+	/// Formal charge from the PDB charge column (`1+`, `2-`); informational, not used for
+	/// electrostatic complementarity
+	pub formal_charge: Option<i32>,
+	/// Temperature factor in Å² (PDB B-factor column, or pLDDT in predicted models)
+	pub bfactor: Option<ScValue>,
+	/// Fractional occupancy from the PDB record
+	pub occupancy: Option<ScValue>,
+	/// Segment identifier (PDB columns 73-76), when not blank
+	pub segid: Option<String>,
+	/// Third-body occluder (see `SurfaceGenerator::add_context_atoms`): present in both molecules to
+	/// shape their surfaces, but never given dots or counted
+	pub context: bool,
 	pub coor: Vec3,
-	/// Neighbor indices on same molecule for convex/toroidal construction (implementation choice: indices over raw pointers)
+	/// No longer filled: the lists are kept in shared tables, read with
+	/// `SurfaceGenerator::neighbor_indices`
+	#[deprecated(note = "always empty; use SurfaceGenerator::neighbor_indices")]
 	pub neighbor_indices: Vec<usize>,
-	/// Neighbor indices on opposite molecule that bury this atom
+	/// No longer filled: read with `SurfaceGenerator::buried_by_indices`
+	#[deprecated(note = "always empty; use SurfaceGenerator::buried_by_indices")]
 	pub buried_by_indices: Vec<usize>,
 }
 
@@ -45,16 +77,45 @@ impl Atom {
 			atom_type_radius: 0.0,
 			density: 0.0,
 			attention: Attention::Buried,
+			attention_override: None,
 			accessible: false,
 			atom: String::new(),
 			residue: String::new(),
+			chain: String::new(),
+			resnum: 0,
+			icode: None,
+			charge: None,
+			formal_charge: None,
+			bfactor: None,
+			occupancy: None,
+			segid: None,
+			context: false,
 			coor: Vec3::zero(),
+			#[allow(deprecated)]
 			neighbor_indices: Vec::new(),
+			#[allow(deprecated)]
 			buried_by_indices: Vec::new(),
 		}
 	}
 	pub fn distance_squared(&self, other: &Atom) -> ScValue { self.coor.distance_squared(other.coor) }
 	pub fn distance(&self, other: &Atom) -> ScValue { self.coor.distance(other.coor) }
+	pub fn residue_id(&self) -> ResidueId {
+		ResidueId { chain: self.chain.clone(), resnum: self.resnum, icode: self.icode, residue: self.residue.clone() }
+	}
+	/// `chain:residue+resnum+icode:atom`, e.g. `A:LYS12:NZ`, for messages
+	pub fn label(&self) -> String {
+		format!("{}:{}{}{}:{}", self.chain.trim(), self.residue.trim(), self.resnum, self.icode.map(String::from).unwrap_or_default(), self.atom.trim())
+	}
+}
+
+/// Identity of a residue (chain, number, insertion code, name) used to key per-residue reports
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResidueId {
+	pub chain: String,
+	pub resnum: i32,
+	pub icode: Option<char>,
+	pub residue: String,
 }
 
 #[derive(Clone, Debug)]
@@ -66,9 +127,23 @@ pub struct Probe {
 	pub alt: super::vector3::Vec3,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum DotKind { Contact, Reentrant, Cavity }
 
+impl std::str::FromStr for DotKind {
+	type Err = String;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim().to_ascii_lowercase().as_str() {
+			"contact" => Ok(DotKind::Contact),
+			"reentrant" => Ok(DotKind::Reentrant),
+			"cavity" => Ok(DotKind::Cavity),
+			other => Err(format!("unknown dot kind '{other}' (expected contact, reentrant or cavity)")),
+		}
+	}
+}
+
 #[derive(Clone, Debug)]
 pub struct Dot {
 	/// Discretized surface point; buried points per Lawrence & Colman (1993)
@@ -81,10 +156,336 @@ pub struct Dot {
 	pub atom_index: usize,
 }
 
+/// Nearest-neighbor pairing of a trimmed dot with the closest buried dot on the opposite surface
+#[derive(Clone, Debug)]
+pub struct DotPair {
+	/// Index into the dots of this surface
+	pub dot: usize,
+	/// Index into the dots of the opposite surface
+	pub neighbor: usize,
+	pub distance: ScValue,
+	/// Unweighted normal product n_A · n_B (-1 for perfectly opposed normals)
+	pub normal_dot: ScValue,
+	/// Weighted S value -(n_A · n_B) exp(-w d^2); positive for complementary surfaces
+	pub score: ScValue,
+}
+
+impl DotPair {
+	/// Angle between the two dot normals in degrees (180 for perfectly opposed normals)
+	pub fn normal_angle(&self) -> ScValue { self.normal_dot.clamp(-1.0, 1.0).acos().to_degrees() }
+}
+
+/// Residue pair across the interface with the trimmed dot area mapped onto it by nearest-neighbor pairing
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResidueContact {
+	/// Residue on molecule 1
+	pub residue1: ResidueId,
+	/// Residue on molecule 2
+	pub residue2: ResidueId,
+	/// Trimmed dot area (both surfaces) whose nearest partner lies on the other residue
+	pub area: ScValue,
+	/// Minimum gap distance between paired dots of the two residues
+	pub min_distance: ScValue,
+	pub n_dots: usize,
+}
+
+/// Residue contributing buried dots to one side of the interface (epitope/paratope listing)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InterfaceResidue {
+	pub residue: ResidueId,
+	/// Total area of buried dots owned by the residue's atoms
+	pub buried_area: ScValue,
+	pub n_buried_dots: usize,
+	/// Area of the residue's dots that survived peripheral trimming
+	pub trimmed_area: ScValue,
+	/// Local Sc: median S of the residue's paired trimmed dots; None when it has none
+	pub sc: Option<ScValue>,
+}
+
+/// Breakdown of one surface's statistics by chain, for molecules made of several chains
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ChainStats {
+	pub chain: String,
+	pub n_atoms: usize,
+	/// Atoms within `separation_cutoff` of the other molecule, as in `SurfaceStats::n_buried_atoms`
+	pub n_buried_atoms: usize,
+	pub n_trimmed_dots: usize,
+	pub trimmed_area: ScValue,
+	/// Median distance and S over the chain's trimmed dots and their partners on the other surface
+	pub d_median: ScValue,
+	pub s_median: ScValue,
+}
+
+/// Atom owning buried dots on one side of the interface
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InterfaceAtom {
+	/// Index into the calculator's atoms (both molecules, in insertion order)
+	pub atom_index: usize,
+	/// Total area of the atom's buried dots
+	pub buried_area: ScValue,
+	pub n_buried_dots: usize,
+	/// Area and count of the atom's dots that survived peripheral trimming
+	pub trimmed_area: ScValue,
+	pub n_trimmed_dots: usize,
+}
+
+/// Connected group of buried dots on one surface
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Patch {
+	pub area: ScValue,
+	pub n_dots: usize,
+	/// Area-weighted centroid of the patch dots
+	pub centroid: Vec3,
+}
+
+/// Water molecule hydrogen-bonded to polar atoms of a residue on each molecule
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WaterBridge {
+	pub water: ResidueId,
+	/// Residue on molecule 1 and the distance from its closest N/O atom to the water oxygen
+	pub residue1: ResidueId,
+	pub distance1: ScValue,
+	/// Residue on molecule 2 and the distance from its closest N/O atom to the water oxygen
+	pub residue2: ResidueId,
+	pub distance2: ScValue,
+}
+
+/// Enclosed gap between the two trimmed surfaces, wide enough to hold solvent
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Void {
+	/// Approximate volume in Å^3 (gap columns of the lining dots, averaged over both sides)
+	pub volume: ScValue,
+	/// Lining dot area on both surfaces
+	pub area: ScValue,
+	/// Widest gap to the partner surface among the lining dots
+	pub max_gap: ScValue,
+	pub n_dots: usize,
+	pub centroid: Vec3,
+}
+
+/// Comparison of the two half-interfaces of a homodimer. For a perfectly symmetric complex the
+/// deviations are zero; residues are matched by sequence position when both chains carry the
+/// same sequence, otherwise by residue number and name.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SymmetryStats {
+	/// Both molecules have the same residue sequence
+	pub equivalent: bool,
+	/// Residue pairs matched between the two protomers
+	pub n_matched_residues: usize,
+	/// Sc averaged over the two half-interfaces (equal to `Results::sc`)
+	pub sc: ScValue,
+	/// Absolute difference between the per-surface Sc values
+	pub sc_deviation: ScValue,
+	/// Median gap distance averaged over both halves, and the absolute difference
+	pub distance: ScValue,
+	pub distance_deviation: ScValue,
+	/// Trimmed area per half, and the absolute difference between halves
+	pub half_area: ScValue,
+	pub area_deviation: ScValue,
+	/// Buried SASA per protomer, and the absolute difference between protomers
+	pub half_bsa: ScValue,
+	pub bsa_deviation: ScValue,
+	/// RMS difference of buried area over matched residues buried on either side (Å^2)
+	pub residue_area_rmsd: ScValue,
+}
+
+/// Shape descriptors of the buried dot cloud (both surfaces, area-weighted)
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InterfaceGeometry {
+	pub centroid: Vec3,
+	/// Principal axes, major first
+	pub axes: [Vec3; 3],
+	/// Variance of the dot cloud along each principal axis (Å^2)
+	pub variances: [ScValue; 3],
+	pub radius_of_gyration: ScValue,
+	/// RMS deviation from the least-squares plane in Å (0 for a perfectly flat interface)
+	pub planarity: ScValue,
+}
+
+/// Curvature complementarity of the paired trimmed dots, next to the normal-based Sc. Curvatures
+/// are mean curvatures in Å^-1, positive where the surface is convex.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CurvatureStats {
+	/// Area-weighted mean curvature of each surface's trimmed dots
+	pub mean_curvature: [ScValue; 2],
+	/// Correlation of each dot's curvature with the negated curvature of its partner, averaged over
+	/// both directions; 1 when every bump meets a groove of the same curvature
+	pub complementarity: ScValue,
+	/// Median |H_A + H_B| over the pairs, averaged over both directions; 0 for matching curvatures
+	pub mismatch: ScValue,
+	pub n_pairs: usize,
+}
+
+/// One slab of the Sc profile along the interface major axis
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProfileBin {
+	/// Slab center along the major axis, relative to the interface centroid (Å)
+	pub position: ScValue,
+	pub sc: ScValue,
+	/// Trimmed dot area falling in the slab (both surfaces)
+	pub area: ScValue,
+	pub n_dots: usize,
+}
+
+/// Sc over the paired trimmed dots of the selected kinds only
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DotKindSc {
+	pub kinds: Vec<DotKind>,
+	/// Average of the per-surface median S; surfaces without such dots are left out
+	pub sc: ScValue,
+	/// Median nearest-neighbor distance, averaged the same way
+	pub distance: ScValue,
+	/// Trimmed dot area of the selected kinds (both surfaces)
+	pub area: ScValue,
+	pub n_dots: usize,
+}
+
+/// Sc over the paired trimmed dots of atoms in the selected residue types only
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResidueTypeSc {
+	/// Upper-case residue names, e.g. PHE
+	pub residues: Vec<String>,
+	/// Average of the per-surface median S; surfaces without such dots are left out
+	pub sc: ScValue,
+	/// Median nearest-neighbor distance, averaged the same way
+	pub distance: ScValue,
+	/// Trimmed dot area of the selected residues (both surfaces)
+	pub area: ScValue,
+	pub n_dots: usize,
+}
+
+/// Realized sampling of one atom's contact surface against its target density
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AtomDensity {
+	/// Index into the calculator's atoms (both molecules, in insertion order)
+	pub atom_index: usize,
+	pub molecule: usize,
+	/// Target dots/Å² (`Settings::dot_density`, per molecule or adaptive)
+	pub target: ScValue,
+	/// Contact dots per Å² of `area`
+	pub achieved: ScValue,
+	pub n_dots: usize,
+	/// Geometric accessible contact area: 4πr² times the uncovered fraction of the expanded sphere
+	pub area: ScValue,
+	/// Achieved density below the requested fraction of the target
+	pub undersampled: bool,
+}
+
+/// Realized contact dot density of one surface
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SurfaceDensity {
+	/// Area-weighted mean of the atom targets
+	pub target: ScValue,
+	pub achieved: ScValue,
+	pub n_dots: usize,
+	pub area: ScValue,
+	/// Surfaced atoms with accessible area, and how many of them are undersampled
+	pub n_atoms: usize,
+	pub n_undersampled: usize,
+}
+
+/// Achieved versus target dot density of the last surface generation, per surface and per atom
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DensityReport {
+	pub surfaces: [SurfaceDensity; 2],
+	/// Every surfaced atom with accessible area, with or without dots, by atom index
+	pub atoms: Vec<AtomDensity>,
+}
+
+/// Sc evaluated with one summary statistic (average of both surfaces)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StatisticValue {
+	pub statistic: ScStatistic,
+	pub sc: ScValue,
+	/// Per-surface values
+	pub surfaces: [ScValue; 2],
+}
+
+/// Sc in each direction under the primary statistic: S(A→B) scores molecule 1's trimmed dots
+/// against their nearest molecule 2 dots, S(B→A) the reverse. `Results::sc` is their average.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DirectionalSc {
+	pub sc_ab: ScValue,
+	pub sc_ba: ScValue,
+	/// Median nearest-neighbor distance in each direction
+	pub distance_ab: ScValue,
+	pub distance_ba: ScValue,
+	/// S(A→B) − S(B→A): positive when molecule 1's surface fits its partner better than the reverse
+	pub asymmetry: ScValue,
+}
+
+/// Angles between paired dot normals over the trimmed interface (both directions pooled), with the
+/// orientation and distance factors of S taken apart
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NormalAngleStats {
+	/// Mean angle in degrees (180 for perfectly opposed normals)
+	pub mean: ScValue,
+	pub median: ScValue,
+	/// Fraction of pairs within `Settings::antiparallel_tolerance` degrees of antiparallel
+	pub fraction_antiparallel: ScValue,
+	/// Orientation factor alone, −(n_A · n_B) without distance weighting; per-surface medians
+	/// averaged as for Sc
+	pub orientation: ScValue,
+	/// Distance factor alone, the configured weight of each pair's gap; per-surface medians averaged
+	pub distance_weight: ScValue,
+	pub n_pairs: usize,
+}
+
+/// Atom pair across the interface whose van der Waals spheres overlap by more than
+/// `Settings::clash_tolerance`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Clash {
+	/// Indices into the calculator's atoms (both molecules, in insertion order), molecule 1 first
+	pub atom_indices: [usize; 2],
+	pub residue1: ResidueId,
+	pub atom1: String,
+	pub residue2: ResidueId,
+	pub atom2: String,
+	pub distance: ScValue,
+	/// r1 + r2 − distance in Å
+	pub overlap: ScValue,
+	/// Volume of the lens shared by the two spheres in Å^3
+	pub volume: ScValue,
+}
+
+/// Steric clashes between the two molecules. Clashing atoms interpenetrate, so the surfaces between
+/// them are not the contact the complex would make, and heavy clashes leave Sc without meaning.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ClashReport {
+	/// Clashing pairs, deepest overlap first
+	pub clashes: Vec<Clash>,
+	/// Sum of the lens volumes of the clashing pairs in Å^3
+	pub overlap_volume: ScValue,
+	pub max_overlap: ScValue,
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DotStats { pub convex: usize, pub toroidal: usize, pub concave: usize }
 
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SurfaceStats {
 	pub n_atoms: usize,
 	pub n_buried_atoms: usize,
@@ -96,18 +497,258 @@ pub struct SurfaceStats {
 	pub n_all_dots: usize,
 	pub n_trimmed_dots: usize,
 	pub trimmed_area: ScValue,
+	/// Solvent-accessible area lost on complex formation (ΔSASA), from buried contact dots
+	pub bsa: ScValue,
+	/// Electrostatic complementarity of this surface's trimmed dots; None without charges
+	pub ec: Option<ScValue>,
+	/// Solvent-excluded volume in Å^3 of the molecule on its own; None unless
+	/// `Settings::molecular_volume` is set
+	pub volume: Option<ScValue>,
+}
+
+/// Wall time per phase in seconds. `radii` accumulates over `add_atom` calls; the other phases cover
+/// the last `calc()`. Serial runs interleave contact and reentrant generation per atom, and both are
+/// timed separately.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Timings {
+	pub radii: f64,
+	pub attention: f64,
+	pub neighbors: f64,
+	pub contact: f64,
+	/// Probe placement and reentrant (toroidal) dots
+	pub toroidal: f64,
+	pub concave: f64,
+	/// Burial classified after generation (GPU backend, reused surfaces) and canonical dot ordering
+	pub burial: f64,
+	pub trimming: f64,
+	/// Nearest-neighbor pairing and the Sc statistics
+	pub statistic: f64,
+	/// Interface reports: gap volume, electrostatics, contacts, geometry, patches, voids, residues
+	pub reports: f64,
+}
+
+/// Atom left out of the calculation because no radius could be assigned
+/// (`Settings::skip_unassigned_atoms`) or it duplicates another atom (`Settings::drop_coincident_atoms`)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SkippedAtom {
+	pub molecule: usize,
+	pub residue: ResidueId,
+	pub atom: String,
+	/// Why the atom was left out
+	pub reason: String,
+}
+
+/// Non-fatal issue met while reading or preparing the input
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum WarningKind {
+	/// Alternate location other than ' ' or 'A' left out by the reader
+	SkippedAltloc,
+	/// Hydrogen left out by the reader
+	HydrogenRemoved,
+	/// No radius for the residue and atom name; the generic radius of its element was used
+	ElementFallbackRadius,
+	/// `HETATM` record (ligand, ion, solvent) left out by the reader
+	DroppedHetatm,
+	/// Arc needing more than `Settings::max_arc_points` points, sampled more coarsely at the cap
+	ArcSamplingCapped,
+}
+
+/// Occurrences of one kind of non-fatal issue, with the first few atoms it concerned
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Warning {
+	pub kind: WarningKind,
+	/// Molecule the atoms belong to, None when they were read without a chain selection
+	pub molecule: Option<usize>,
+	pub count: usize,
+	/// Up to `Warning::MAX_EXAMPLES` atoms, as `Atom::label`
+	pub examples: Vec<String>,
+}
+
+impl Warning {
+	pub const MAX_EXAMPLES: usize = 5;
+
+	/// Count one more occurrence in `warnings`, merging with an entry of the same kind and molecule
+	pub fn tally(warnings: &mut Vec<Warning>, kind: WarningKind, molecule: Option<usize>, example: impl FnOnce() -> String) {
+		let i = match warnings.iter().position(|w| w.kind == kind && w.molecule == molecule) {
+			Some(i) => i,
+			None => { warnings.push(Warning { kind, molecule, count: 0, examples: Vec::new() }); warnings.len() - 1 }
+		};
+		let w = &mut warnings[i];
+		w.count += 1;
+		if w.examples.len() < Self::MAX_EXAMPLES { w.examples.push(example()); }
+	}
+
+	/// Merge `other` into `warnings` entry by entry
+	pub fn merge(warnings: &mut Vec<Warning>, other: impl IntoIterator<Item = Warning>) {
+		for o in other {
+			match warnings.iter_mut().find(|w| w.kind == o.kind && w.molecule == o.molecule) {
+				Some(w) => {
+					w.count += o.count;
+					let room = Self::MAX_EXAMPLES.saturating_sub(w.examples.len());
+					w.examples.extend(o.examples.into_iter().take(room));
+				}
+				None => warnings.push(o),
+			}
+		}
+	}
+}
+
+impl std::fmt::Display for Warning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let what = match self.kind {
+			WarningKind::SkippedAltloc => "alternate location(s) other than ' '/'A' skipped",
+			WarningKind::HydrogenRemoved => "hydrogen(s) removed",
+			WarningKind::ElementFallbackRadius => "atom(s) given the generic radius of their element",
+			WarningKind::DroppedHetatm => "HETATM record(s) dropped",
+			WarningKind::ArcSamplingCapped => "arc(s) sampled at the max_arc_points cap, below the requested density",
+		};
+		write!(f, "{} {what}", self.count)?;
+		if let Some(m) = self.molecule { write!(f, " in molecule {}", m + 1)?; }
+		if self.examples.is_empty() { return Ok(()); }
+		write!(f, ": {}{}", self.examples.join(", "), if self.count > self.examples.len() { ", ..." } else { "" })
+	}
 }
 
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Results {
 	pub valid: i32,
+	/// Atoms added, each context atom counted once
 	pub n_atoms: usize,
+	/// Context atoms occluding both surfaces; each is stored once per molecule internally but counted
+	/// once in `n_atoms` and in neither `SurfaceStats::n_atoms`
+	pub n_context_atoms: usize,
 	pub surfaces: [SurfaceStats; 2],
 	pub combined: SurfaceStats,
 	pub dots: DotStats,
+	/// Cutoff the atoms were classified with: `Settings::separation_cutoff`, or the derived value
+	/// with `Settings::auto_separation_cutoff`. `SurfaceStats::n_buried_atoms` and `n_blocked_atoms`
+	/// count the atoms on either side of it.
+	pub separation_cutoff: ScValue,
+	/// Sc under `Settings::statistic` (the median of Lawrence & Colman by default)
 	pub sc: ScValue,
+	/// Primary statistic followed by `Settings::extra_statistics`
+	pub statistics: Vec<StatisticValue>,
+	/// The two directions behind `sc`, kept apart
+	pub directional: DirectionalSc,
+	pub normal_angles: NormalAngleStats,
 	pub distance: ScValue,
 	pub area: ScValue,
+	/// Total buried surface area of both molecules (sum of per-surface ΔSASA)
+	pub bsa: ScValue,
+	/// Volume between the two trimmed surfaces in Å^3 (each dot's area times its gap to the partner, averaged over both directions)
+	pub gap_volume: ScValue,
+	/// Gap volume divided by the per-side trimmed interface area (Å); lower means tighter packing
+	pub gap_index: ScValue,
+	/// Electrostatic complementarity (mean of both surfaces) when atom charges are available
+	pub ec: Option<ScValue>,
+	/// Residue-pair contact map over the trimmed interface, sorted by residue identity
+	pub contacts: Vec<ResidueContact>,
+	/// Residues on each molecule that own at least one buried dot, sorted by residue identity
+	pub interface_residues: [Vec<InterfaceResidue>; 2],
+	/// Per-chain breakdown of each surface, sorted by chain ID
+	pub chains: [Vec<ChainStats>; 2],
+	/// Connected buried-dot patches per molecule, largest first
+	pub patches: [Vec<Patch>; 2],
+	/// Interfacial voids, largest first
+	pub voids: Vec<Void>,
+	pub geometry: InterfaceGeometry,
+	pub curvature: CurvatureStats,
+	pub clashes: ClashReport,
+	/// Half-interface comparison, present when `Settings::homodimer` is set
+	pub symmetry: Option<SymmetryStats>,
+	/// Atoms left out for lack of a radius (when added) or as duplicates (at the start of a calculation)
+	pub skipped_atoms: Vec<SkippedAtom>,
+	/// Non-fatal issues met reading the input and assigning radii, one entry per kind and molecule
+	pub warnings: Vec<Warning>,
+	pub timings: Timings,
+}
+
+/// Sc recomputed for one (gaussian_w, peripheral_band) combination of a parameter sweep
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SweepPoint {
+	pub gaussian_w: ScValue,
+	pub peripheral_band: ScValue,
+	pub sc: ScValue,
+	/// Median nearest-neighbor distance (average of both surfaces)
+	pub distance: ScValue,
+	/// Trimmed interface area at this band
+	pub area: ScValue,
+}
+
+/// Sc recomputed from scratch at one probe radius of a probe radius scan
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProbeRadiusPoint {
+	pub probe_radius: ScValue,
+	pub sc: ScValue,
+	/// Median nearest-neighbor distance (average of both surfaces)
+	pub distance: ScValue,
+	/// Trimmed interface area (both surfaces)
+	pub area: ScValue,
+	pub bsa: ScValue,
+}
+
+/// Sc at one dot density of a multi-resolution run
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DensityPoint {
+	/// Dots per Å²
+	pub density: ScValue,
+	pub sc: ScValue,
+	/// Median nearest-neighbor distance (average of both surfaces)
+	pub distance: ScValue,
+	/// Trimmed interface area (both surfaces)
+	pub area: ScValue,
+	/// Trimmed dots (both surfaces)
+	pub n_dots: usize,
+}
+
+/// Cheap estimate of one structure or pose for ranking many of them before the full calculation
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Prescreen {
+	/// Molecule 2 atoms whose probe-expanded sphere overlaps one of molecule 1's: those that can
+	/// bury dots
+	pub contacts: usize,
+	/// Sc from coarsely sampled surfaces; None when there were no contacts and no surfaces were built
+	pub sc: Option<ScValue>,
+	/// Trimmed interface area of the coarse surfaces (both surfaces)
+	pub area: ScValue,
+}
+
+impl Prescreen {
+	/// Indices of the `keep` best estimates, best first: highest coarse Sc, then most contacts, then
+	/// input order. Estimates without surfaces are never selected.
+	pub fn select(estimates: &[Prescreen], keep: usize) -> Vec<usize> {
+		let mut ranked: Vec<usize> = (0..estimates.len()).filter(|&i| estimates[i].sc.is_some()).collect();
+		ranked.sort_by(|&a, &b| {
+			let (ea, eb) = (&estimates[a], &estimates[b]);
+			eb.sc.partial_cmp(&ea.sc).unwrap_or(std::cmp::Ordering::Equal).then(eb.contacts.cmp(&ea.contacts))
+		});
+		ranked.truncate(keep);
+		ranked
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ConvergenceStep { pub density: ScValue, pub sc: ScValue }
+
+/// Outcome of density convergence: the last density used and the Sc obtained with it
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Convergence {
+	pub converged: bool,
+	pub density: ScValue,
+	pub sc: ScValue,
+	pub steps: Vec<ConvergenceStep>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -1,6 +1,7 @@
 use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, Div};
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Vec3 {
 	pub x: f64,
 	pub y: f64,
@@ -29,6 +30,87 @@ impl Vec3 {
 	pub fn distance(&self, other: Vec3) -> f64 { self.distance_squared(other).sqrt() }
 }
 
+impl From<[f64; 3]> for Vec3 {
+	fn from(v: [f64; 3]) -> Self { Vec3::new(v[0], v[1], v[2]) }
+}
+impl From<Vec3> for [f64; 3] {
+	fn from(v: Vec3) -> Self { [v.x, v.y, v.z] }
+}
+impl From<(f64, f64, f64)> for Vec3 {
+	fn from((x, y, z): (f64, f64, f64)) -> Self { Vec3::new(x, y, z) }
+}
+impl From<Vec3> for (f64, f64, f64) {
+	fn from(v: Vec3) -> Self { (v.x, v.y, v.z) }
+}
+impl From<[f32; 3]> for Vec3 {
+	fn from(v: [f32; 3]) -> Self { Vec3::new(v[0] as f64, v[1] as f64, v[2] as f64) }
+}
+#[cfg(feature = "glam")]
+impl From<glam::DVec3> for Vec3 {
+	fn from(v: glam::DVec3) -> Self { Vec3::new(v.x, v.y, v.z) }
+}
+#[cfg(feature = "glam")]
+impl From<Vec3> for glam::DVec3 {
+	fn from(v: Vec3) -> Self { glam::DVec3::new(v.x, v.y, v.z) }
+}
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Point3<f64>> for Vec3 {
+	fn from(p: nalgebra::Point3<f64>) -> Self { Vec3::new(p.x, p.y, p.z) }
+}
+#[cfg(feature = "nalgebra")]
+impl From<Vec3> for nalgebra::Point3<f64> {
+	fn from(v: Vec3) -> Self { nalgebra::Point3::new(v.x, v.y, v.z) }
+}
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Vector3<f64>> for Vec3 {
+	fn from(v: nalgebra::Vector3<f64>) -> Self { Vec3::new(v.x, v.y, v.z) }
+}
+#[cfg(feature = "nalgebra")]
+impl From<Vec3> for nalgebra::Vector3<f64> {
+	fn from(v: Vec3) -> Self { nalgebra::Vector3::new(v.x, v.y, v.z) }
+}
+#[cfg(feature = "mint")]
+impl From<mint::Vector3<f64>> for Vec3 {
+	fn from(v: mint::Vector3<f64>) -> Self { Vec3::new(v.x, v.y, v.z) }
+}
+#[cfg(feature = "mint")]
+impl From<Vec3> for mint::Vector3<f64> {
+	fn from(v: Vec3) -> Self { mint::Vector3 { x: v.x, y: v.y, z: v.z } }
+}
+#[cfg(feature = "mint")]
+impl From<mint::Point3<f64>> for Vec3 {
+	fn from(p: mint::Point3<f64>) -> Self { Vec3::new(p.x, p.y, p.z) }
+}
+#[cfg(feature = "mint")]
+impl From<Vec3> for mint::Point3<f64> {
+	fn from(v: Vec3) -> Self { mint::Point3 { x: v.x, y: v.y, z: v.z } }
+}
+
+/// Rigid-body motion `p' = rotation · p + translation`, with `rotation` given row-major
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RigidTransform {
+	pub rotation: [[f64; 3]; 3],
+	pub translation: Vec3,
+}
+
+impl Default for RigidTransform {
+	fn default() -> Self { Self::identity() }
+}
+
+impl RigidTransform {
+	pub fn new(rotation: [[f64; 3]; 3], translation: Vec3) -> Self { Self { rotation, translation } }
+	pub fn identity() -> Self { Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]], Vec3::zero()) }
+	pub fn translation(t: Vec3) -> Self { Self::new(Self::identity().rotation, t) }
+	pub fn apply(&self, p: Vec3) -> Vec3 {
+		let r = &self.rotation;
+		Vec3::new(
+			r[0][0]*p.x + r[0][1]*p.y + r[0][2]*p.z,
+			r[1][0]*p.x + r[1][1]*p.y + r[1][2]*p.z,
+			r[2][0]*p.x + r[2][1]*p.y + r[2][2]*p.z,
+		) + self.translation
+	}
+}
+
 impl Add for Vec3 {
 	type Output = Vec3;
 	fn add(self, rhs: Vec3) -> Vec3 { Vec3::new(self.x+rhs.x, self.y+rhs.y, self.z+rhs.z) }
@@ -0,0 +1,83 @@
+use crate::sc::surface_generator::SurfaceGenerator;
+use crate::sc::types::*;
+use crate::sc::vector3::Vec3;
+
+/// Which dots `SurfaceGenerator::dot_views` yields; unset fields match every dot
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DotFilter {
+	pub molecule: Option<usize>,
+	pub kind: Option<DotKind>,
+	pub buried: Option<bool>,
+}
+
+impl DotFilter {
+	pub fn new() -> Self { Self::default() }
+	pub fn molecule(mut self, molecule: usize) -> Self { self.molecule = Some(molecule); self }
+	pub fn kind(mut self, kind: DotKind) -> Self { self.kind = Some(kind); self }
+	pub fn buried(mut self, buried: bool) -> Self { self.buried = Some(buried); self }
+	pub fn matches(&self, molecule: usize, dot: &Dot) -> bool {
+		self.molecule.is_none_or(|m| m == molecule) && self.kind.is_none_or(|k| k == dot.kind) && self.buried.is_none_or(|b| b == dot.buried)
+	}
+}
+
+/// One generated dot with its position in `dots(molecule)` and its owning atom
+#[derive(Clone, Copy, Debug)]
+pub struct DotView<'a> {
+	pub molecule: usize,
+	/// Index into `dots(molecule)`, the same index used by `DotPair`
+	pub index: usize,
+	pub dot: &'a Dot,
+	pub atom: &'a Atom,
+}
+
+/// Probe sphere resting on three atoms, from which reentrant (concave) dots are sampled
+#[derive(Clone, Copy, Debug)]
+pub struct ProbeSphere<'a> {
+	pub molecule: usize,
+	pub center: Vec3,
+	pub radius: ScValue,
+	/// Height of the center above the plane of the three parent atoms
+	pub height: ScValue,
+	/// Indices of the parent atoms into `atoms()`
+	pub atom_indices: [usize; 3],
+	pub atoms: [&'a Atom; 3],
+}
+
+impl SurfaceGenerator {
+	/// Dots of the last surface generation matching `filter`, molecule 1 first, each in canonical order
+	pub fn dot_views(&self, filter: DotFilter) -> impl Iterator<Item = DotView<'_>> + '_ {
+		(0..2).flat_map(move |molecule| {
+			self.run.dots[molecule].iter().enumerate()
+				.filter(move |(_, dot)| filter.matches(molecule, dot))
+				.map(move |(index, dot)| DotView { molecule, index, dot, atom: &self.run.atoms[dot.atom_index] })
+		})
+	}
+
+	/// Indices into `dots(molecule)` of the dots that survived peripheral trimming in the last
+	/// `calc()`: the dots the Sc statistic and `dot_pairs` were computed from
+	pub fn trimmed_dot_indices(&self, molecule: usize) -> &[usize] { &self.run.trimmed_dots[molecule] }
+
+	/// Trimmed dots of `molecule` with their index and owning atom, in the order of `trimmed_dot_indices`
+	pub fn trimmed_dot_views(&self, molecule: usize) -> impl Iterator<Item = DotView<'_>> + '_ {
+		self.run.trimmed_dots[molecule].iter().map(move |&index| {
+			let dot = &self.run.dots[molecule][index];
+			DotView { molecule, index, dot, atom: &self.run.atoms[dot.atom_index] }
+		})
+	}
+
+	/// Total area of the trimmed dots of `molecule` (`SurfaceStats::trimmed_area` of the last `calc()`)
+	pub fn trimmed_dot_area(&self, molecule: usize) -> ScValue {
+		self.run.trimmed_dots[molecule].iter().map(|&i| self.run.dots[molecule][i].area).sum()
+	}
+
+	/// Probe spheres placed during the last surface generation. Reused surfaces are not re-probed:
+	/// with a frozen molecule 1 only molecule 2 probes appear, and after `update_atoms` only those
+	/// around the moved atoms.
+	pub fn probe_spheres(&self) -> impl Iterator<Item = ProbeSphere<'_>> + '_ {
+		self.run.probes.iter().map(move |p| {
+			let atoms = p.atom_indices.map(|i| &self.run.atoms[i]);
+			let molecule = atoms[0].molecule;
+			ProbeSphere { molecule, center: p.point, radius: self.settings.probe_radius(molecule), height: p.height, atom_indices: p.atom_indices, atoms }
+		})
+	}
+}
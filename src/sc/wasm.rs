@@ -0,0 +1,73 @@
+// wasm-bindgen entry point for client-side use in structure viewers. Atoms and settings arrive as
+// plain JS objects and the full `Results` goes back as one; radii come from the embedded table
+// unless an atom carries its own, so nothing touches the filesystem.
+
+use wasm_bindgen::prelude::*;
+
+use crate::sc::sc_calculator::ScCalculator;
+use crate::sc::types::{Atom, ScValue};
+use crate::sc::vector3::Vec3;
+
+/// `{ atom, residue, chain?, resnum?, x, y, z, radius?, charge? }`
+#[derive(serde::Deserialize)]
+struct JsAtom {
+	atom: String,
+	residue: String,
+	#[serde(default)]
+	chain: String,
+	#[serde(default)]
+	resnum: i32,
+	x: ScValue,
+	y: ScValue,
+	z: ScValue,
+	radius: Option<ScValue>,
+	charge: Option<ScValue>,
+}
+
+impl From<JsAtom> for Atom {
+	fn from(a: JsAtom) -> Self {
+		let mut atom = Atom::new();
+		atom.atom = a.atom;
+		atom.residue = a.residue;
+		atom.chain = a.chain;
+		atom.resnum = a.resnum;
+		atom.coor = Vec3::new(a.x, a.y, a.z);
+		atom.radius = a.radius.unwrap_or(0.0);
+		atom.charge = a.charge;
+		atom
+	}
+}
+
+/// Optional overrides of the core settings; omitted keys keep the defaults
+#[derive(serde::Deserialize, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+struct JsSettings {
+	probe_radius: Option<ScValue>,
+	dot_density: Option<ScValue>,
+	peripheral_band: Option<ScValue>,
+	separation_cutoff: Option<ScValue>,
+	gaussian_w: Option<ScValue>,
+}
+
+fn atoms(value: JsValue) -> Result<Vec<Atom>, JsError> {
+	let atoms: Vec<JsAtom> = serde_wasm_bindgen::from_value(value)?;
+	Ok(atoms.into_iter().map(Atom::from).collect())
+}
+
+/// Sc between two atom arrays; `settings` may be `undefined`. Returns the `Results` object or
+/// throws with the calculator's error message.
+#[wasm_bindgen(js_name = computeSc)]
+pub fn compute_sc(atoms1: JsValue, atoms2: JsValue, settings: JsValue) -> Result<JsValue, JsError> {
+	let overrides: JsSettings = if settings.is_undefined() || settings.is_null() { JsSettings::default() } else { serde_wasm_bindgen::from_value(settings)? };
+	let mut sc = ScCalculator::new();
+	let s = sc.settings_mut();
+	// The browser main thread cannot block on a thread pool
+	s.enable_parallel = false;
+	if let Some(rp) = overrides.probe_radius { s.rp = rp; }
+	if let Some(density) = overrides.dot_density { s.dot_density = density; }
+	if let Some(band) = overrides.peripheral_band { s.peripheral_band = band; }
+	if let Some(cutoff) = overrides.separation_cutoff { s.separation_cutoff = cutoff; }
+	if let Some(w) = overrides.gaussian_w { s.gaussian_w = w; }
+	let results = sc.calc_atoms(&atoms(atoms1)?, &atoms(atoms2)?)?;
+	Ok(serde_wasm_bindgen::to_value(&results)?)
+}
@@ -0,0 +1,108 @@
+use std::fs::File;
+use std::io::{self, Read};
+
+use crate::sc::surface_generator::RunState;
+use crate::sc::types::*;
+
+/// Weight for the atoms matching `chain`, `resnum` and `atom`; `None` fields match anything
+#[derive(Clone, Debug, PartialEq)]
+pub struct AtomWeight {
+	pub chain: Option<String>,
+	pub resnum: Option<i32>,
+	pub atom: Option<String>,
+	pub weight: ScValue,
+}
+
+impl AtomWeight {
+	pub fn matches(&self, atom: &Atom) -> bool {
+		self.chain.as_ref().is_none_or(|c| *c == atom.chain)
+			&& self.resnum.is_none_or(|r| r == atom.resnum)
+			&& self.atom.as_ref().is_none_or(|a| a == atom.atom.trim())
+	}
+}
+
+/// Sc with each trimmed dot's S value weighted by its parent atom's weight: per-surface weighted
+/// medians, averaged as for the global Sc
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WeightedSc {
+	pub sc: ScValue,
+	/// Weighted median nearest-neighbor distance, averaged the same way
+	pub distance: ScValue,
+	/// Trimmed dot area scaled by the weights (both surfaces)
+	pub weighted_area: ScValue,
+	/// Paired dots with a non-zero weight
+	pub n_dots: usize,
+}
+
+pub fn read_weights_from_path(path: &str) -> io::Result<Vec<AtomWeight>> {
+	let mut f = File::open(path)?;
+	let mut buf = String::new();
+	f.read_to_string(&mut buf)?;
+	read_weights_from_str(&buf)
+}
+
+/// Parse atom weights, one per line: `CHAIN RESNUM ATOM WEIGHT`, where `*` in any of the first three
+/// fields matches anything (`H 52 * 2.0` weights a whole residue, `* * * 0` sets a default). The
+/// last matching line wins; unmatched atoms weigh 1. Blank lines and `#` comments are ignored.
+pub fn read_weights_from_str(data: &str) -> io::Result<Vec<AtomWeight>> {
+	let invalid = |line: usize, msg: String| io::Error::new(io::ErrorKind::InvalidData, format!("invalid atom weight on line {line}: {msg}"));
+	let wildcard = |f: &str| (f != "*").then(|| f.to_string());
+	let mut weights = Vec::new();
+	for (n, raw) in data.lines().enumerate() {
+		let line = raw.split('#').next().unwrap_or("").trim();
+		if line.is_empty() { continue; }
+		let fields: Vec<&str> = line.split_whitespace().collect();
+		if fields.len() != 4 { return Err(invalid(n + 1, "expected CHAIN RESNUM ATOM WEIGHT".into())); }
+		let resnum = wildcard(fields[1]).map(|r| r.parse::<i32>()).transpose().map_err(|_| invalid(n + 1, format!("bad residue number '{}'", fields[1])))?;
+		let weight: ScValue = fields[3].parse().map_err(|_| invalid(n + 1, format!("bad weight '{}'", fields[3])))?;
+		if !weight.is_finite() || weight < 0.0 { return Err(invalid(n + 1, format!("weight must be finite and non-negative, got {weight}"))); }
+		weights.push(AtomWeight { chain: wildcard(fields[0]), resnum, atom: wildcard(fields[2]), weight });
+	}
+	Ok(weights)
+}
+
+/// Upper weighted median: the first value, in sorted order, at which the running weight reaches half
+/// the total. Zero total weight gives 0.
+fn weighted_median(values: &mut [(ScValue, ScValue)]) -> ScValue {
+	let total: ScValue = values.iter().map(|v| v.1).sum();
+	if total <= 0.0 { return 0.0; }
+	values.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+	let mut acc = 0.0;
+	for &(v, w) in values.iter() {
+		acc += w;
+		if acc >= total / 2.0 { return v; }
+	}
+	values[values.len() - 1].0
+}
+
+pub(crate) fn weighted_sc(run: &RunState, weights: &[AtomWeight]) -> WeightedSc {
+	let atom_weight: Vec<ScValue> = run.atoms.iter()
+		.map(|a| weights.iter().rev().find(|w| w.matches(a)).map_or(1.0, |w| w.weight))
+		.collect();
+	let mut medians = Vec::new();
+	let mut weighted_area = 0.0;
+	let mut n_dots = 0;
+	for my in 0..2 {
+		let mut scores = Vec::new();
+		let mut distances = Vec::new();
+		for p in &run.pairs[my] {
+			let dot = &run.dots[my][p.dot];
+			let w = atom_weight[dot.atom_index];
+			if w <= 0.0 { continue; }
+			scores.push((p.score, w));
+			distances.push((p.distance, w));
+			weighted_area += w * dot.area;
+		}
+		n_dots += scores.len();
+		if !scores.is_empty() { medians.push((weighted_median(&mut scores), weighted_median(&mut distances))); }
+	}
+	if medians.is_empty() { return WeightedSc { sc: 0.0, distance: 0.0, weighted_area, n_dots }; }
+	let n = medians.len() as ScValue;
+	WeightedSc {
+		sc: medians.iter().map(|m| m.0).sum::<ScValue>() / n,
+		distance: medians.iter().map(|m| m.1).sum::<ScValue>() / n,
+		weighted_area,
+		n_dots,
+	}
+}
@@ -0,0 +1,131 @@
+use crate::sc::surface_generator::RunState;
+use crate::sc::types::*;
+use crate::sc::vector3::Vec3;
+
+/// Order of the descriptors when none is given (36 invariants)
+pub const DEFAULT_ZERNIKE_ORDER: usize = 10;
+
+/// Rotation-invariant 3D Zernike descriptor (Novotni & Klein 2003) of a set of surface dots. The
+/// dots, weighted by area, are centered on their centroid and scaled into the unit ball; the
+/// moments Ω_nl^m are projected on the Zernike functions and each invariant is the norm of Ω_nl
+/// over m, so two patches can be compared by the distance between their `values` whatever their
+/// orientation.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ZernikeDescriptor {
+	/// Highest order n
+	pub order: usize,
+	/// Invariants ‖Ω_nl‖ for n ≤ `order`, l ≤ n and n − l even, ordered by n then l
+	pub values: Vec<ScValue>,
+	/// Radius in Å of the ball the dots were scaled into: their largest distance from the centroid
+	pub radius: ScValue,
+	pub area: ScValue,
+	pub n_dots: usize,
+}
+
+impl ZernikeDescriptor {
+	/// (n, l) of each entry of `values`
+	pub fn indices(order: usize) -> Vec<(usize, usize)> {
+		(0..=order).flat_map(|n| (n % 2..=n).step_by(2).map(move |l| (n, l))).collect()
+	}
+
+	/// Euclidean distance between the invariants of two descriptors, over the orders both have
+	pub fn distance(&self, other: &ZernikeDescriptor) -> ScValue {
+		self.values.iter().zip(&other.values).map(|(a, b)| (a - b) * (a - b)).sum::<ScValue>().sqrt()
+	}
+}
+
+/// Jacobi polynomials P_k^(0, beta)(x) for k = 0..=k_max
+fn jacobi(k_max: usize, beta: ScValue, x: ScValue) -> Vec<ScValue> {
+	let mut p = vec![1.0; k_max + 1];
+	if k_max >= 1 { p[1] = 1.0 + (beta + 2.0) * (x - 1.0) / 2.0; }
+	for k in 2..=k_max {
+		let n = k as ScValue;
+		let s = 2.0 * n + beta;
+		p[k] = ((s - 1.0) * (s * (s - 2.0) * x - beta * beta) * p[k - 1] - 2.0 * (n - 1.0) * (n + beta - 1.0) * s * p[k - 2]) / (2.0 * n * (n + beta) * (s - 2.0));
+	}
+	p
+}
+
+/// Orthonormal real spherical harmonics Y_l^m of the unit vector `u` for l ≤ `l_max`; entry l holds
+/// m = -l..=l. The Condon-Shortley phase is left out, which the invariants do not see.
+fn spherical_harmonics(l_max: usize, u: Vec3) -> Vec<Vec<ScValue>> {
+	let (x, s) = (u.z.clamp(-1.0, 1.0), (u.x * u.x + u.y * u.y).sqrt());
+	let phi = u.y.atan2(u.x);
+	// Normalized associated Legendre functions, p[l][m] for 0 ≤ m ≤ l
+	let mut p = vec![vec![0.0; l_max + 1]; l_max + 1];
+	p[0][0] = (1.0 / (4.0 * std::f64::consts::PI)).sqrt();
+	for m in 0..=l_max {
+		if m > 0 { p[m][m] = ((2 * m + 1) as ScValue / (2 * m) as ScValue).sqrt() * s * p[m - 1][m - 1]; }
+		if m < l_max { p[m + 1][m] = ((2 * m + 3) as ScValue).sqrt() * x * p[m][m]; }
+		let a = |l: usize| (((4 * l * l - 1) as ScValue) / ((l * l - m * m) as ScValue)).sqrt();
+		for l in m + 2..=l_max { p[l][m] = a(l) * (x * p[l - 1][m] - p[l - 2][m] / a(l - 1)); }
+	}
+	(0..=l_max).map(|l| {
+		(-(l as i64)..=l as i64).map(|m| {
+			let am = m.unsigned_abs() as usize;
+			match m {
+				0 => p[l][0],
+				m if m > 0 => std::f64::consts::SQRT_2 * p[l][am] * (am as ScValue * phi).cos(),
+				_ => std::f64::consts::SQRT_2 * p[l][am] * (am as ScValue * phi).sin(),
+			}
+		}).collect()
+	}).collect()
+}
+
+/// 3D Zernike descriptor of area-weighted points up to `order`
+pub fn zernike_descriptor(points: &[(Vec3, ScValue)], order: usize) -> ZernikeDescriptor {
+	let area: ScValue = points.iter().map(|(_, a)| a).sum();
+	let indices = ZernikeDescriptor::indices(order);
+	if points.is_empty() || area <= 0.0 {
+		return ZernikeDescriptor { order, values: vec![0.0; indices.len()], radius: 0.0, area, n_dots: points.len() };
+	}
+	let centroid = points.iter().fold(Vec3::zero(), |acc, (p, a)| acc + *p * *a) / area;
+	let radius = points.iter().map(|(p, _)| p.distance(centroid)).fold(0.0, ScValue::max);
+	let scale = if radius > 0.0 { radius } else { 1.0 };
+	// moments[i][m + l] for the (n, l) of indices[i]
+	let mut moments: Vec<Vec<ScValue>> = indices.iter().map(|&(_, l)| vec![0.0; 2 * l + 1]).collect();
+	for (p, a) in points {
+		let v = (*p - centroid) / scale;
+		let r = v.magnitude().min(1.0);
+		let u = if r > 0.0 { v / r } else { Vec3::new(0.0, 0.0, 1.0) };
+		let harmonics = spherical_harmonics(order, u);
+		let radial: Vec<Vec<ScValue>> = (0..=order).map(|l| jacobi((order - l) / 2, l as ScValue + 0.5, 2.0 * r * r - 1.0)).collect();
+		for (i, &(n, l)) in indices.iter().enumerate() {
+			let r_nl = ((2 * n + 3) as ScValue).sqrt() * r.powi(l as i32) * radial[l][(n - l) / 2];
+			for (m, y) in harmonics[l].iter().enumerate() { moments[i][m] += a * r_nl * y; }
+		}
+	}
+	let norm = 3.0 / (4.0 * std::f64::consts::PI) / area;
+	let values = moments.iter().map(|m| m.iter().map(|v| v * v).sum::<ScValue>().sqrt() * norm).collect();
+	ZernikeDescriptor { order, values, radius, area, n_dots: points.len() }
+}
+
+/// Descriptor of the buried dots of `molecule`: its side of the interface
+pub(crate) fn patch_descriptor(run: &RunState, molecule: usize, order: usize) -> ZernikeDescriptor {
+	let points: Vec<(Vec3, ScValue)> = run.dots[molecule].iter().filter(|d| d.buried).map(|d| (d.coor, d.area)).collect();
+	zernike_descriptor(&points, order)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sc::vector3::RigidTransform;
+
+	#[test]
+	fn descriptor_is_rotation_invariant() {
+		let points: Vec<(Vec3, ScValue)> = (0..200).map(|i| {
+			let t = i as ScValue;
+			(Vec3::new((t * 0.37).sin() * 6.0, (t * 0.91).cos() * 4.0, (t * 0.13).sin() * 2.0 + t * 0.01), 0.5 + (t * 0.7).cos().abs())
+		}).collect();
+		// Rz(1 rad)·Rx(0.6 rad), then a shift
+		let ((sa, ca), (sb, cb)) = (1.0f64.sin_cos(), 0.6f64.sin_cos());
+		let pose = RigidTransform::new([[ca, -sa * cb, sa * sb], [sa, ca * cb, -ca * sb], [0.0, sb, cb]], Vec3::new(3.0, -1.0, 7.0));
+		let moved: Vec<(Vec3, ScValue)> = points.iter().map(|&(p, a)| (pose.apply(p), a)).collect();
+		let (d1, d2) = (zernike_descriptor(&points, 8), zernike_descriptor(&moved, 8));
+		assert!(d1.values.iter().any(|&v| v > 1e-3));
+		for (a, b) in d1.values.iter().zip(&d2.values) {
+			assert!((a - b).abs() <= 1e-9 * a.abs().max(1.0), "{a} vs {b}");
+		}
+	}
+}